@@ -0,0 +1,766 @@
+//! 整站备份与恢复
+//!
+//! 备份文件是一个 tar.gz 归档，包含：
+//! - `database.db`：通过 SQLite 在线备份接口得到的一致快照，服务运行中也可以执行
+//! - `jwt-secret`：JWT 密钥文件（存在时）
+//! - `markdown/`、`attachments/`、`music/`、`img/` 目录下的全部文件
+//! - `manifest.json`：格式版本、数据库结构版本和每个文件的大小、SHA-256 校验和
+//!
+//! 恢复时先把归档解压到临时目录并逐一核对清单，数据库结构版本不一致时拒绝恢复；
+//! 核对通过后先为当前数据做一次备份，再通过在线备份接口把快照写回数据库并替换各目录
+
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use r2d2::Pool;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::backup::{Backup, StepResult};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::CliArgs;
+
+/// 归档格式版本
+pub const FORMAT_VERSION: u32 = 1;
+
+/// 需要备份的内容目录（与静态文件服务一样相对当前工作目录）
+pub const CONTENT_DIRS: [&str; 4] = ["markdown", "attachments", "music", "img"];
+
+const DATABASE_ENTRY: &str = "database.db";
+const JWT_SECRET_ENTRY: &str = "jwt-secret";
+const MANIFEST_ENTRY: &str = "manifest.json";
+
+const FILE_PREFIX: &str = "rustblog-backup-";
+const FILE_SUFFIX: &str = ".tar.gz";
+
+/// 备份文件名中的时间戳格式（精确到毫秒，按文件名排序即按时间排序）
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S%3f";
+const TIMESTAMP_LEN: usize = 18;
+
+/// 数据库被锁定时的重试间隔和次数
+const BUSY_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+const BUSY_RETRY_LIMIT: usize = 300;
+
+/// 备份配置
+#[derive(Debug, Clone)]
+pub struct BackupConfig {
+    /// 备份文件目录
+    pub dir: PathBuf,
+    /// JWT 密钥文件路径
+    pub jwt_secret_path: PathBuf,
+    /// 定时备份间隔（小时），0 表示不启用
+    pub interval_hours: u64,
+    /// 定时备份保留的份数（只清理定时备份，手动备份不自动删除）
+    pub keep: usize,
+}
+
+impl BackupConfig {
+    /// 从命令行参数创建配置
+    pub fn from_cli(args: &CliArgs) -> Self {
+        Self {
+            dir: PathBuf::from(&args.backup_dir),
+            jwt_secret_path: args.get_base_dir().join("data").join("jwt-secret"),
+            interval_hours: args.backup_interval_hours,
+            keep: args.backup_keep,
+        }
+    }
+}
+
+/// 备份来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BackupKind {
+    /// 管理员或命令行手动创建
+    Manual,
+    /// 定时备份
+    Auto,
+    /// 恢复前自动保存的当前数据
+    PreRestore,
+    /// 上传的备份文件
+    Upload,
+}
+
+impl BackupKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BackupKind::Manual => "manual",
+            BackupKind::Auto => "auto",
+            BackupKind::PreRestore => "pre-restore",
+            BackupKind::Upload => "upload",
+        }
+    }
+
+    fn from_str(kind: &str) -> Option<Self> {
+        match kind {
+            "manual" => Some(BackupKind::Manual),
+            "auto" => Some(BackupKind::Auto),
+            "pre-restore" => Some(BackupKind::PreRestore),
+            "upload" => Some(BackupKind::Upload),
+            _ => None,
+        }
+    }
+}
+
+/// 清单中的单个文件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestFile {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// 备份清单
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub format_version: u32,
+    pub app_version: String,
+    pub schema_version: i64,
+    pub created_at: DateTime<Utc>,
+    pub kind: BackupKind,
+    /// 备份时存在的内容目录，恢复时只替换这些目录
+    pub directories: Vec<String>,
+    pub files: Vec<ManifestFile>,
+}
+
+/// 备份文件信息
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupInfo {
+    pub name: String,
+    pub kind: BackupKind,
+    pub size: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 恢复结果
+#[derive(Debug, Serialize)]
+pub struct RestoreReport {
+    /// 备份的创建时间
+    pub created_at: DateTime<Utc>,
+    pub files: usize,
+    pub directories: Vec<String>,
+    /// 是否恢复了 JWT 密钥（需要重启服务后生效）
+    pub jwt_secret: bool,
+    /// 恢复前为当前数据创建的备份
+    pub safety_backup: String,
+}
+
+/// 创建一份备份，返回备份文件信息
+pub fn create(pool: &Pool<SqliteConnectionManager>, config: &BackupConfig, kind: BackupKind) -> Result<BackupInfo, String> {
+    fs::create_dir_all(&config.dir).map_err(|e| format!("创建备份目录失败: {}", e))?;
+
+    let created_at = Utc::now();
+    let name = backup_name(created_at, kind);
+    let path = config.dir.join(&name);
+    if path.exists() {
+        return Err(format!("备份文件已存在: {}", name));
+    }
+    let partial = config.dir.join(format!(".{}.partial", name));
+    let snapshot = config.dir.join(format!(".{}.db", name));
+
+    let result = write_archive(pool, config, kind, created_at, &snapshot, &partial)
+        .and_then(|_| fs::rename(&partial, &path).map_err(|e| format!("保存备份文件失败: {}", e)));
+    remove_file_quietly(&snapshot);
+    if let Err(e) = result {
+        remove_file_quietly(&partial);
+        return Err(e);
+    }
+
+    let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    Ok(BackupInfo { name, kind, size, created_at })
+}
+
+fn write_archive(
+    pool: &Pool<SqliteConnectionManager>,
+    config: &BackupConfig,
+    kind: BackupKind,
+    created_at: DateTime<Utc>,
+    snapshot: &Path,
+    partial: &Path,
+) -> Result<(), String> {
+    // 数据库快照
+    let schema_version = {
+        let source = pool.get().map_err(|e| format!("获取数据库连接失败: {}", e))?;
+        let mut target = Connection::open(snapshot).map_err(|e| format!("创建数据库快照失败: {}", e))?;
+        copy_database(&source, &mut target).map_err(|e| format!("创建数据库快照失败: {}", e))?;
+        schema_version(&target).map_err(|e| format!("读取数据库结构版本失败: {}", e))?
+    };
+
+    let mut sources: Vec<(String, PathBuf)> = vec![(DATABASE_ENTRY.to_string(), snapshot.to_path_buf())];
+    if config.jwt_secret_path.is_file() {
+        sources.push((JWT_SECRET_ENTRY.to_string(), config.jwt_secret_path.clone()));
+    }
+    let mut directories = Vec::new();
+    for dir in CONTENT_DIRS {
+        let root = Path::new(dir);
+        if !root.is_dir() {
+            continue;
+        }
+        directories.push(dir.to_string());
+        collect_files(root, dir, &mut sources).map_err(|e| format!("读取目录 {} 失败: {}", dir, e))?;
+    }
+
+    let file = fs::File::create(partial).map_err(|e| format!("创建备份文件失败: {}", e))?;
+    let encoder = GzEncoder::new(io::BufWriter::new(file), flate2::Compression::default());
+    let mut writer = tar::Builder::new(encoder);
+    let mtime = created_at.timestamp().max(0) as u64;
+
+    let mut files = Vec::with_capacity(sources.len() + 1);
+    for (entry, source) in &sources {
+        let file = fs::File::open(source).map_err(|e| format!("读取 {} 失败: {}", source.display(), e))?;
+        let size = file.metadata().map_err(|e| format!("读取 {} 失败: {}", source.display(), e))?.len();
+        let mut reader = HashingReader::new(file.take(size));
+        append_file(&mut writer, entry, size, mtime, &mut reader).map_err(|e| format!("写入 {} 失败: {}", entry, e))?;
+        if reader.len != size {
+            return Err(format!("文件在写入过程中发生变化: {}", source.display()));
+        }
+        files.push(ManifestFile { path: entry.clone(), size, sha256: reader.finish() });
+    }
+
+    let manifest = Manifest {
+        format_version: FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version,
+        created_at,
+        kind,
+        directories,
+        files,
+    };
+    let manifest = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    append_file(&mut writer, MANIFEST_ENTRY, manifest.len() as u64, mtime, &mut manifest.as_slice())
+        .map_err(|e| format!("写入备份清单失败: {}", e))?;
+
+    let encoder = writer.into_inner().map_err(|e| format!("写入备份文件失败: {}", e))?;
+    let file = encoder
+        .finish()
+        .and_then(|w| w.into_inner().map_err(|e| e.into_error()))
+        .map_err(|e| format!("写入备份文件失败: {}", e))?;
+    file.sync_all().map_err(|e| format!("写入备份文件失败: {}", e))
+}
+
+/// 从备份文件恢复全部数据
+pub fn restore(pool: &Pool<SqliteConnectionManager>, config: &BackupConfig, archive: &Path) -> Result<RestoreReport, String> {
+    fs::create_dir_all(&config.dir).map_err(|e| format!("创建备份目录失败: {}", e))?;
+    let staging = config.dir.join(format!(".restore-{}", crate::id_generator::generate_unique_id()));
+    let result = restore_from(pool, config, archive, &staging);
+    if let Err(e) = fs::remove_dir_all(&staging) {
+        if e.kind() != io::ErrorKind::NotFound {
+            eprintln!("⚠️  删除恢复临时目录失败 {}: {}", staging.display(), e);
+        }
+    }
+    result
+}
+
+fn restore_from(
+    pool: &Pool<SqliteConnectionManager>,
+    config: &BackupConfig,
+    archive: &Path,
+    staging: &Path,
+) -> Result<RestoreReport, String> {
+    let files_dir = staging.join("files");
+    let (manifest, entries) = extract(archive, &files_dir)?;
+    let manifest: Manifest = serde_json::from_slice(&manifest).map_err(|e| format!("备份清单格式错误: {}", e))?;
+    validate(&manifest, &entries, crate::db::SCHEMA_VERSION)?;
+
+    // 数据库快照本身也要核对结构版本和完整性
+    let snapshot = Connection::open(files_dir.join(DATABASE_ENTRY)).map_err(|e| format!("打开数据库快照失败: {}", e))?;
+    let snapshot_version = schema_version(&snapshot).map_err(|e| format!("读取数据库快照失败: {}", e))?;
+    if snapshot_version != manifest.schema_version {
+        return Err(format!(
+            "数据库快照的结构版本 ({}) 与备份清单 ({}) 不一致",
+            snapshot_version, manifest.schema_version
+        ));
+    }
+    let check: String = snapshot
+        .query_row("PRAGMA quick_check", [], |row| row.get(0))
+        .map_err(|e| format!("检查数据库快照失败: {}", e))?;
+    if check != "ok" {
+        return Err(format!("数据库快照已损坏: {}", check));
+    }
+
+    let safety_backup = create(pool, config, BackupKind::PreRestore)
+        .map_err(|e| format!("恢复前备份当前数据失败: {}", e))?
+        .name;
+
+    {
+        let mut target = pool.get().map_err(|e| format!("获取数据库连接失败: {}", e))?;
+        copy_database(&snapshot, &mut target).map_err(|e| format!("恢复数据库失败: {}", e))?;
+    }
+
+    let old_dir = staging.join("old");
+    for dir in &manifest.directories {
+        let current = Path::new(dir);
+        let restored = files_dir.join(dir);
+        fs::create_dir_all(&restored).map_err(|e| format!("恢复目录 {} 失败: {}", dir, e))?;
+        if current.exists() {
+            fs::create_dir_all(&old_dir).map_err(|e| format!("恢复目录 {} 失败: {}", dir, e))?;
+            move_dir(current, &old_dir.join(dir)).map_err(|e| format!("移走当前目录 {} 失败: {}", dir, e))?;
+        }
+        if let Err(e) = move_dir(&restored, current) {
+            // 放回原来的目录，避免目录缺失
+            let _ = move_dir(&old_dir.join(dir), current);
+            return Err(format!("恢复目录 {} 失败: {}", dir, e));
+        }
+    }
+
+    let jwt_secret = manifest.files.iter().any(|f| f.path == JWT_SECRET_ENTRY);
+    if jwt_secret {
+        if let Some(parent) = config.jwt_secret_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("恢复 JWT 密钥失败: {}", e))?;
+        }
+        fs::copy(files_dir.join(JWT_SECRET_ENTRY), &config.jwt_secret_path)
+            .map_err(|e| format!("恢复 JWT 密钥失败: {}", e))?;
+    }
+
+    Ok(RestoreReport {
+        created_at: manifest.created_at,
+        files: manifest.files.len(),
+        directories: manifest.directories,
+        jwt_secret,
+        safety_backup,
+    })
+}
+
+/// 归档中每个文件的 (大小, SHA-256)
+type Entries = BTreeMap<String, (u64, String)>;
+
+/// 解压归档到 `dest`，返回清单内容和解压出的文件
+fn extract(archive: &Path, dest: &Path) -> Result<(Vec<u8>, Entries), String> {
+    let file = fs::File::open(archive).map_err(|e| format!("打开备份文件失败: {}", e))?;
+    read_archive(GzDecoder::new(io::BufReader::new(file)), dest).map_err(|e| format!("读取备份文件失败: {}", e))
+}
+
+/// 读取 tar 归档中的普通文件并写入 `dest`（忽略目录项，链接、设备等其他类型直接报错）
+fn read_archive<R: Read>(reader: R, dest: &Path) -> io::Result<(Vec<u8>, Entries)> {
+    let mut manifest = None;
+    let mut entries = BTreeMap::new();
+
+    for entry in tar::Archive::new(reader).entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.to_str().map(str::to_string).ok_or_else(|| invalid("备份中包含无法识别的文件名"))?;
+        match entry.header().entry_type() {
+            tar::EntryType::Regular | tar::EntryType::Continuous => {}
+            tar::EntryType::Directory => continue,
+            other => return Err(invalid(&format!("备份中包含不支持的文件类型 {:?}: {}", other, path))),
+        }
+
+        if path == MANIFEST_ENTRY {
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data)?;
+            manifest = Some(data);
+            continue;
+        }
+        let relative = entry_path(&path).ok_or_else(|| invalid(&format!("备份中包含不允许的文件: {}", path)))?;
+        if entries.contains_key(&path) {
+            return Err(invalid(&format!("备份中的文件重复: {}", path)));
+        }
+
+        let target = dest.join(relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut reader = HashingReader::new(&mut entry);
+        io::copy(&mut reader, &mut fs::File::create(&target)?)?;
+        entries.insert(path, (reader.len, reader.finish()));
+    }
+
+    let manifest = manifest.ok_or_else(|| invalid("备份文件中没有清单"))?;
+    Ok((manifest, entries))
+}
+
+/// 向归档写入一个普通文件，`content` 提供 `size` 字节的内容
+fn append_file<W: io::Write>(writer: &mut tar::Builder<W>, path: &str, size: u64, mtime: u64, content: &mut dyn Read) -> io::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(tar::EntryType::Regular);
+    header.set_mode(0o644);
+    header.set_size(size);
+    header.set_mtime(mtime);
+    writer.append_data(&mut header, path, content)
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// 核对清单：格式版本、数据库结构版本以及每个文件的大小和校验和
+fn validate(manifest: &Manifest, entries: &Entries, schema_version: i64) -> Result<(), String> {
+    if manifest.format_version != FORMAT_VERSION {
+        return Err(format!("不支持的备份格式版本: {}", manifest.format_version));
+    }
+    if manifest.schema_version != schema_version {
+        return Err(format!(
+            "备份的数据库结构版本 ({}) 与当前程序 ({}) 不一致，请使用版本 {} 的程序恢复",
+            manifest.schema_version, schema_version, manifest.app_version
+        ));
+    }
+    if let Some(dir) = manifest.directories.iter().find(|d| !CONTENT_DIRS.contains(&d.as_str())) {
+        return Err(format!("备份清单中包含未知目录: {}", dir));
+    }
+    if !manifest.files.iter().any(|f| f.path == DATABASE_ENTRY) {
+        return Err("备份中没有数据库快照".to_string());
+    }
+
+    for file in &manifest.files {
+        match entries.get(&file.path) {
+            None => return Err(format!("备份中缺少文件: {}", file.path)),
+            Some((size, sha256)) if *size != file.size || *sha256 != file.sha256 => {
+                return Err(format!("文件校验失败: {}", file.path));
+            }
+            Some(_) => {}
+        }
+    }
+    if entries.len() != manifest.files.len() {
+        let listed: Vec<&str> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+        let extra = entries.keys().find(|path| !listed.contains(&path.as_str())).cloned().unwrap_or_default();
+        return Err(format!("备份中包含清单之外的文件: {}", extra));
+    }
+    Ok(())
+}
+
+/// 归档内的路径：只允许数据库快照、JWT 密钥和内容目录下的普通路径
+fn entry_path(path: &str) -> Option<PathBuf> {
+    let relative = Path::new(path);
+    let mut components = relative.components();
+    let first = match components.next()? {
+        Component::Normal(first) => first.to_str()?,
+        _ => return None,
+    };
+    let rest_ok = components.all(|c| matches!(c, Component::Normal(_)));
+    let allowed = if path == DATABASE_ENTRY || path == JWT_SECRET_ENTRY {
+        true
+    } else {
+        CONTENT_DIRS.contains(&first) && relative.components().count() > 1
+    };
+    (allowed && rest_ok && !path.contains('\\')).then(|| relative.to_path_buf())
+}
+
+/// 列出备份目录中的备份文件（新的在前）
+pub fn list(config: &BackupConfig) -> Result<Vec<BackupInfo>, String> {
+    let entries = match fs::read_dir(&config.dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("读取备份目录失败: {}", e)),
+    };
+
+    let mut backups: Vec<BackupInfo> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let (created_at, kind) = parse_name(&name)?;
+            let metadata = entry.metadata().ok().filter(|m| m.is_file())?;
+            Some(BackupInfo { name, kind, size: metadata.len(), created_at })
+        })
+        .collect();
+    backups.sort_by(|a, b| b.name.cmp(&a.name));
+    Ok(backups)
+}
+
+/// 备份文件的完整路径，文件名不合法或文件不存在时返回 None
+pub fn find(config: &BackupConfig, name: &str) -> Option<PathBuf> {
+    parse_name(name)?;
+    let path = config.dir.join(name);
+    path.is_file().then_some(path)
+}
+
+/// 为上传的备份文件生成文件名
+pub fn upload_name() -> String {
+    backup_name(Utc::now(), BackupKind::Upload)
+}
+
+/// 按保留份数清理定时备份，返回删除的文件数
+pub fn prune(config: &BackupConfig) -> Result<usize, String> {
+    let mut removed = 0;
+    let auto_backups = list(config)?.into_iter().filter(|b| b.kind == BackupKind::Auto);
+    for backup in auto_backups.skip(config.keep) {
+        match fs::remove_file(config.dir.join(&backup.name)) {
+            Ok(()) => removed += 1,
+            Err(e) => eprintln!("⚠️  删除过期备份 {} 失败: {}", backup.name, e),
+        }
+    }
+    Ok(removed)
+}
+
+fn backup_name(created_at: DateTime<Utc>, kind: BackupKind) -> String {
+    format!("{}{}-{}{}", FILE_PREFIX, created_at.format(TIMESTAMP_FORMAT), kind.as_str(), FILE_SUFFIX)
+}
+
+/// 解析备份文件名，返回创建时间和来源
+fn parse_name(name: &str) -> Option<(DateTime<Utc>, BackupKind)> {
+    let rest = name.strip_prefix(FILE_PREFIX)?.strip_suffix(FILE_SUFFIX)?;
+    if !rest.is_char_boundary(TIMESTAMP_LEN) {
+        return None;
+    }
+    let (timestamp, kind) = rest.split_at(TIMESTAMP_LEN);
+    let kind = BackupKind::from_str(kind.strip_prefix('-')?)?;
+    let created_at = chrono::NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()?.and_utc();
+    Some((created_at, kind))
+}
+
+/// 通过在线备份接口一次性复制整个数据库（保证是同一时刻的快照）
+fn copy_database(source: &Connection, target: &mut Connection) -> rusqlite::Result<()> {
+    let backup = Backup::new(source, target)?;
+    for _ in 0..BUSY_RETRY_LIMIT {
+        match backup.step(-1)? {
+            StepResult::Done => return Ok(()),
+            _ => std::thread::sleep(BUSY_RETRY_INTERVAL),
+        }
+    }
+    Err(rusqlite::Error::SqliteFailure(
+        rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+        Some("数据库持续被锁定".to_string()),
+    ))
+}
+
+fn schema_version(conn: &Connection) -> rusqlite::Result<i64> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+}
+
+/// 递归收集目录下的文件（按路径排序，跳过符号链接）
+fn collect_files(dir: &Path, prefix: &str, files: &mut Vec<(String, PathBuf)>) -> io::Result<()> {
+    let mut entries: Vec<fs::DirEntry> = fs::read_dir(dir)?.collect::<Result<_, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let Ok(name) = entry.file_name().into_string() else {
+            eprintln!("⚠️  跳过文件名不是 UTF-8 的文件: {}", entry.path().display());
+            continue;
+        };
+        let entry_name = format!("{}/{}", prefix, name);
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_files(&entry.path(), &entry_name, files)?;
+        } else if file_type.is_file() {
+            files.push((entry_name, entry.path()));
+        }
+    }
+    Ok(())
+}
+
+/// 移动目录，跨文件系统时退回到复制后删除
+fn move_dir(from: &Path, to: &Path) -> io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    copy_dir(from, to)?;
+    fs::remove_dir_all(from)
+}
+
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+fn remove_file_quietly(path: &Path) {
+    if let Err(e) = fs::remove_file(path) {
+        if e.kind() != io::ErrorKind::NotFound {
+            eprintln!("⚠️  删除临时文件失败 {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// 读取时顺便计算 SHA-256
+struct HashingReader<R> {
+    inner: R,
+    hasher: Sha256,
+    /// 已读取的字节数
+    len: u64,
+}
+
+impl<R: Read> HashingReader<R> {
+    fn new(inner: R) -> Self {
+        Self { inner, hasher: Sha256::new(), len: 0 }
+    }
+
+    fn finish(self) -> String {
+        hex::encode(self.hasher.finalize())
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.len += n as u64;
+        Ok(n)
+    }
+}
+
+/// 定时备份调度器
+pub struct BackupScheduler {
+    _handle: tokio::task::JoinHandle<()>,
+}
+
+impl BackupScheduler {
+    /// 创建并启动调度器（首次备份在一个间隔之后执行），未配置间隔时返回 None
+    pub fn new(pool: Arc<Pool<SqliteConnectionManager>>, config: Arc<BackupConfig>) -> Option<Self> {
+        if config.interval_hours == 0 {
+            return None;
+        }
+        let period = Duration::from_secs(config.interval_hours * 3600);
+        let handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+            loop {
+                interval.tick().await;
+                let (pool, config) = (pool.clone(), config.clone());
+                let result = tokio::task::spawn_blocking(move || {
+                    let backup = create(&pool, &config, BackupKind::Auto)?;
+                    let removed = prune(&config)?;
+                    Ok::<_, String>((backup, removed))
+                })
+                .await;
+                match result {
+                    Ok(Ok((backup, removed))) => {
+                        println!("💾 定时备份完成: {}（清理过期备份 {} 个）", backup.name, removed);
+                    }
+                    Ok(Err(e)) => eprintln!("❌ 定时备份失败: {}", e),
+                    Err(e) => eprintln!("❌ 定时备份任务异常: {}", e),
+                }
+            }
+        });
+        Some(Self { _handle: handle })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(files: Vec<ManifestFile>) -> Manifest {
+        Manifest {
+            format_version: FORMAT_VERSION,
+            app_version: "1.0.0".to_string(),
+            schema_version: 3,
+            created_at: Utc::now(),
+            kind: BackupKind::Manual,
+            directories: vec!["markdown".to_string()],
+            files,
+        }
+    }
+
+    fn file(path: &str, size: u64, sha256: &str) -> ManifestFile {
+        ManifestFile { path: path.to_string(), size, sha256: sha256.to_string() }
+    }
+
+    #[test]
+    fn test_validate() {
+        let manifest = manifest(vec![file("database.db", 4, "aa"), file("markdown/a.md", 1, "bb")]);
+        let mut entries = BTreeMap::new();
+        entries.insert("database.db".to_string(), (4, "aa".to_string()));
+        entries.insert("markdown/a.md".to_string(), (1, "bb".to_string()));
+        assert!(validate(&manifest, &entries, 3).is_ok());
+
+        // 结构版本不一致
+        assert!(validate(&manifest, &entries, 4).unwrap_err().contains("结构版本"));
+
+        // 校验和不一致
+        let mut tampered = entries.clone();
+        tampered.insert("markdown/a.md".to_string(), (1, "cc".to_string()));
+        assert!(validate(&manifest, &tampered, 3).is_err());
+
+        // 缺少文件或多出文件
+        let mut missing = entries.clone();
+        missing.remove("markdown/a.md");
+        assert!(validate(&manifest, &missing, 3).is_err());
+        let mut extra = entries.clone();
+        extra.insert("markdown/b.md".to_string(), (1, "dd".to_string()));
+        assert!(validate(&manifest, &extra, 3).is_err());
+    }
+
+    #[test]
+    fn test_entry_path() {
+        assert_eq!(entry_path("database.db"), Some(PathBuf::from("database.db")));
+        assert_eq!(entry_path("markdown/2024/01/01/a.md"), Some(PathBuf::from("markdown/2024/01/01/a.md")));
+        assert_eq!(entry_path("markdown"), None);
+        assert_eq!(entry_path("markdown/../data/blog.db"), None);
+        assert_eq!(entry_path("/etc/passwd"), None);
+        assert_eq!(entry_path("data/blog.db"), None);
+    }
+
+    #[test]
+    fn test_backup_name() {
+        let created_at = DateTime::parse_from_rfc3339("2024-05-01T08:09:10.123Z").unwrap().with_timezone(&Utc);
+        let name = backup_name(created_at, BackupKind::PreRestore);
+        assert_eq!(name, "rustblog-backup-20240501-080910123-pre-restore.tar.gz");
+        assert_eq!(parse_name(&name), Some((created_at, BackupKind::PreRestore)));
+        assert_eq!(parse_name("rustblog-backup-20240501-080910123-other.tar.gz"), None);
+        assert_eq!(parse_name("../rustblog-backup-20240501-080910123-auto.tar.gz"), None);
+    }
+
+    /// 把文件写成 tar 归档
+    fn archive(files: &[(String, Vec<u8>)]) -> Vec<u8> {
+        let mut writer = tar::Builder::new(Vec::new());
+        for (path, data) in files {
+            append_file(&mut writer, path, data.len() as u64, 1_700_000_000, &mut data.as_slice()).unwrap();
+        }
+        writer.into_inner().unwrap()
+    }
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("rustblog-backup-{}", crate::id_generator::generate_unique_id()))
+    }
+
+    #[test]
+    fn test_archive_round_trip() {
+        let long_dir = "目录".repeat(30);
+        let long_path = format!("markdown/{}/{}.md", long_dir, "很长的文件名".repeat(10));
+        let files = vec![
+            ("manifest.json".to_string(), b"{}".to_vec()),
+            ("attachments/empty.txt".to_string(), Vec::new()),
+            (format!("markdown/{}/a.md", long_dir), vec![b'x'; 1000]),
+            (long_path.clone(), b"# title".to_vec()),
+        ];
+
+        let dest = temp_dir();
+        let (manifest, entries) = read_archive(archive(&files).as_slice(), &dest).unwrap();
+        assert_eq!(manifest, b"{}");
+        assert_eq!(entries.keys().collect::<Vec<_>>(), vec!["attachments/empty.txt", &files[2].0, &long_path]);
+        assert_eq!(entries[&long_path].0, 7);
+        assert_eq!(fs::read(dest.join(&long_path)).unwrap(), b"# title");
+        fs::remove_dir_all(&dest).unwrap();
+    }
+
+    #[test]
+    fn test_archive_rejects_unexpected_entries() {
+        let dest = temp_dir();
+        let read = |files: &[(String, Vec<u8>)]| read_archive(archive(files).as_slice(), &dest).map(|_| ());
+
+        // 没有清单、不允许的路径、重复的文件
+        assert!(read(&[("markdown/a.md".to_string(), Vec::new())]).is_err());
+        assert!(read(&[("manifest.json".to_string(), Vec::new()), ("data/blog.db".to_string(), Vec::new())]).is_err());
+        let duplicate = ("markdown/a.md".to_string(), Vec::new());
+        assert!(read(&[("manifest.json".to_string(), Vec::new()), duplicate.clone(), duplicate]).is_err());
+
+        // 符号链接
+        let mut writer = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_size(0);
+        writer.append_link(&mut header, "markdown/link.md", "/etc/passwd").unwrap();
+        let linked = writer.into_inner().unwrap();
+        assert!(read_archive(linked.as_slice(), &dest).is_err());
+        assert!(!dest.join("markdown/link.md").exists());
+
+        // 校验和被破坏的归档
+        let mut corrupted = archive(&[("manifest.json".to_string(), b"{}".to_vec())]);
+        corrupted[0] = b'b';
+        assert!(read_archive(corrupted.as_slice(), &dest).is_err());
+        let _ = fs::remove_dir_all(&dest);
+    }
+}
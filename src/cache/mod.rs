@@ -1,5 +1,34 @@
 // Cache module - simplified version for future use
 
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::sitemap::SitemapEntry;
+
+mod lru;
+pub use lru::LruCache;
+
+/// 文章数据版本号：文章创建、更新、删除、同步或定时发布/下线后递增，
+/// 依赖文章数据的缓存条目记录生成时的版本号，版本号变化后自动失效
+static PASSAGES_VERSION: AtomicU64 = AtomicU64::new(0);
+
+/// 站点地图缓存的站点地址数量（按请求的站点地址分别缓存）
+const SITEMAP_CAPACITY: usize = 16;
+
+/// 文章数据发生变化：使依赖文章的缓存失效，并触发相关文章重算
+pub fn invalidate_passages() {
+    PASSAGES_VERSION.fetch_add(1, Ordering::Relaxed);
+    crate::db::related::mark_dirty();
+}
+
+/// 当前文章数据版本号
+pub fn passages_version() -> u64 {
+    PASSAGES_VERSION.load(Ordering::Relaxed)
+}
+
+/// 带文章版本号的站点地图条目
+type VersionedEntries = (u64, Arc<Vec<SitemapEntry>>);
+
 /// 缓存配置
 #[derive(Debug, Clone)]
 pub struct CacheConfig;
@@ -11,10 +40,55 @@ impl Default for CacheConfig {
 }
 
 /// 应用缓存
-pub struct AppCache;
+pub struct AppCache {
+    /// 站点地图条目，按站点地址缓存，值为（生成时的文章版本号，条目）
+    sitemap: Mutex<LruCache<String, VersionedEntries>>,
+}
 
 impl AppCache {
     pub fn new(_config: CacheConfig) -> Self {
-        Self
+        Self {
+            sitemap: Mutex::new(LruCache::new(SITEMAP_CAPACITY)),
+        }
+    }
+
+    /// 使文章相关的缓存失效
+    pub fn invalidate_passages(&self) {
+        invalidate_passages();
+    }
+
+    /// 读取站点地图条目（文章数据变化后生成的缓存不再返回）
+    pub fn sitemap_entries(&self, origin: &str) -> Option<Arc<Vec<SitemapEntry>>> {
+        let (version, entries) = self.sitemap.lock().unwrap().get(&origin.to_string())?;
+        (version == passages_version()).then_some(entries)
     }
-}
\ No newline at end of file
+
+    /// 缓存站点地图条目，`version` 为开始读取文章数据前的版本号
+    pub fn put_sitemap_entries(&self, origin: &str, version: u64, entries: Arc<Vec<SitemapEntry>>) {
+        self.sitemap.lock().unwrap().put(origin.to_string(), (version, entries));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sitemap_entries_expire_with_passages_version() {
+        let cache = AppCache::new(CacheConfig);
+        let entries = Arc::new(vec![SitemapEntry { loc: "http://a/".to_string(), lastmod: None }]);
+
+        cache.put_sitemap_entries("http://a", passages_version(), entries.clone());
+        assert_eq!(cache.sitemap_entries("http://a").map(|e| e.len()), Some(1));
+        assert!(cache.sitemap_entries("http://b").is_none());
+
+        cache.invalidate_passages();
+        assert!(cache.sitemap_entries("http://a").is_none());
+
+        // 读取期间文章发生变化时，按旧版本号写入的结果不会被使用
+        let stale_version = passages_version();
+        invalidate_passages();
+        cache.put_sitemap_entries("http://a", stale_version, entries);
+        assert!(cache.sitemap_entries("http://a").is_none());
+    }
+}
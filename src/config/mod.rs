@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::fs;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
 /// 配置文件结构
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +22,10 @@ pub struct ConfigFile {
     pub logging: Option<LoggingConfigFile>,
     #[serde(default)]
     pub jwt: Option<JwtConfigFile>,
+    #[serde(default)]
+    pub backup: Option<BackupConfigFile>,
+    #[serde(default)]
+    pub watch: Option<WatchConfigFile>,
 }
 
 impl Default for ConfigFile {
@@ -35,6 +39,8 @@ impl Default for ConfigFile {
             tls: None,
             logging: None,
             jwt: None,
+            backup: None,
+            watch: None,
         }
     }
 }
@@ -92,6 +98,20 @@ pub struct JwtConfigFile {
     pub secret: Option<String>,
 }
 
+/// 备份配置（配置文件）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupConfigFile {
+    pub dir: Option<String>,
+    pub interval_hours: Option<u64>,
+    pub keep: Option<usize>,
+}
+
+/// 文件变化监听配置（配置文件）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchConfigFile {
+    pub enabled: Option<bool>,
+}
+
 /// 命令行参数配置
 #[derive(Parser, Debug, Clone)]
 #[command(name = "rustblog")]
@@ -150,9 +170,60 @@ pub struct CliArgs {
     #[arg(long)]
     pub jwt_secret: Option<String>,
 
+    /// 备份文件目录
+    #[arg(long, default_value = "./data/backups")]
+    pub backup_dir: String,
+
+    /// 定时备份间隔（小时），0 表示不启用
+    #[arg(long, default_value = "0")]
+    pub backup_interval_hours: u64,
+
+    /// 定时备份保留的份数
+    #[arg(long, default_value = "7")]
+    pub backup_keep: usize,
+
+    /// 禁用 markdown、music 目录的文件变化监听
+    #[arg(long)]
+    pub disable_file_watch: bool,
+
     /// 基础目录（可执行文件所在目录，自动计算）
     #[clap(skip)]
     pub base_dir: PathBuf,
+
+    /// 维护命令，不指定时启动服务器
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// 维护命令
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// 按当前规则重新清理已有的评论、非管理员文章和自定义 CSS
+    Sanitize,
+    /// 从 Hexo、Hugo、Jekyll 站点目录或 WordPress 导出文件（WXR）导入文章，默认只输出预览报告
+    Import {
+        /// 站点源码目录或 WordPress 导出的 XML 文件
+        path: PathBuf,
+        /// 确认导入，写入文章、分类、标签、评论和附件
+        #[arg(long)]
+        apply: bool,
+    },
+    /// 把所有公开页面渲染成静态 HTML，连同 CSS、JS、订阅源、站点地图和公开附件导出为只读镜像
+    ExportStatic {
+        /// 导出目录
+        #[arg(long)]
+        out: PathBuf,
+        /// 订阅源、站点地图和规范链接中使用的站点地址
+        #[arg(long, default_value = "http://localhost")]
+        base_url: String,
+    },
+    /// 备份数据库快照、文章、附件、音乐、图片目录和 JWT 密钥到备份目录
+    Backup,
+    /// 从备份文件恢复全部数据（恢复前会先备份当前数据）
+    Restore {
+        /// 备份文件路径
+        path: PathBuf,
+    },
 }
 
 impl CliArgs {
@@ -232,6 +303,26 @@ impl CliArgs {
                 self.jwt_secret = Some(secret);
             }
         }
+
+        // 备份配置
+        if let Some(backup) = config.backup {
+            if let Some(dir) = backup.dir {
+                self.backup_dir = dir;
+            }
+            if let Some(interval_hours) = backup.interval_hours {
+                self.backup_interval_hours = interval_hours;
+            }
+            if let Some(keep) = backup.keep {
+                self.backup_keep = keep;
+            }
+        }
+
+        // 文件变化监听配置
+        if let Some(watch) = config.watch {
+            if let Some(enabled) = watch.enabled {
+                self.disable_file_watch = !enabled;
+            }
+        }
     }
 
     /// 将相对路径转换为绝对路径
@@ -273,6 +364,9 @@ impl CliArgs {
         // GeoIP 数据库路径
         self.geoip_db_path = Self::make_absolute(&self.base_dir, &self.geoip_db_path);
 
+        // 备份目录
+        self.backup_dir = Self::make_absolute(&self.base_dir, &self.backup_dir);
+
         // TLS 证书和密钥
         if let Some(ref mut cert) = self.tls_cert {
             *cert = Self::make_absolute(&self.base_dir, cert.as_str());
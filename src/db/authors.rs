@@ -0,0 +1,139 @@
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// 旧数据中表示站长的默认作者名
+const DEFAULT_AUTHOR_NAMES: [&str; 3] = ["管理员", "Admin", "admin"];
+
+/// 创建作者资料表，每个用户最多一条（显示名、头像、简介）
+pub fn create_table(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS author_profiles (
+            user_id INTEGER PRIMARY KEY,
+            display_name TEXT NOT NULL DEFAULT '',
+            avatar TEXT NOT NULL DEFAULT '',
+            bio TEXT NOT NULL DEFAULT '',
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// 默认作者：最早创建的管理员
+pub fn default_author_id(conn: &Connection) -> Result<Option<i64>, rusqlite::Error> {
+    conn.query_row("SELECT id FROM users WHERE role = 'admin' ORDER BY id LIMIT 1", [], |row| row.get(0))
+        .optional()
+}
+
+/// 作者是否可信（管理员）：可信作者的文章保留原始 HTML，其余作者的文章需要清理
+///
+/// 没有关联用户的文章来自站点目录、导入或升级前的数据，由站长维护，视为可信
+pub fn is_trusted(conn: &Connection, author_id: Option<i64>) -> Result<bool, rusqlite::Error> {
+    let Some(author_id) = author_id else {
+        return Ok(true);
+    };
+    let role: Option<String> = conn
+        .query_row("SELECT role FROM users WHERE id = ?", params![author_id], |row| row.get(0))
+        .optional()?;
+    Ok(role.as_deref() == Some("admin"))
+}
+
+/// 作者显示名：资料中设置了显示名时使用显示名，否则使用用户名
+pub fn display_name(conn: &Connection, user_id: i64) -> Result<Option<String>, rusqlite::Error> {
+    conn.query_row(
+        "SELECT COALESCE(NULLIF(ap.display_name, ''), u.username)
+         FROM users u LEFT JOIN author_profiles ap ON ap.user_id = u.id WHERE u.id = ?",
+        params![user_id],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// 为还没有关联用户的文章补上 author_id（添加 author_id 列时执行一次）
+///
+/// author 与用户名相同的文章关联到该用户，默认作者名关联到最早创建的管理员
+pub fn backfill_author_ids(conn: &Connection) -> Result<usize, rusqlite::Error> {
+    let mut linked = conn.execute(
+        "UPDATE passages SET author_id = (SELECT id FROM users WHERE users.username = passages.author)
+         WHERE author_id IS NULL AND author IN (SELECT username FROM users)",
+        [],
+    )?;
+
+    if let Some(admin_id) = default_author_id(conn)? {
+        for name in DEFAULT_AUTHOR_NAMES {
+            linked += conn.execute(
+                "UPDATE passages SET author_id = ? WHERE author_id IS NULL AND author = ?",
+                params![admin_id, name],
+            )?;
+        }
+    }
+    Ok(linked)
+}
+
+/// 作者显示名变化后，同步更新其文章中的 author 文本
+pub fn sync_author_name(conn: &Connection, user_id: i64) -> Result<usize, rusqlite::Error> {
+    let Some(name) = display_name(conn, user_id)? else {
+        return Ok(0);
+    };
+    conn.execute(
+        "UPDATE passages SET author = ? WHERE author_id = ? AND author != ?",
+        params![name, user_id, name],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use r2d2::PooledConnection;
+    use r2d2_sqlite::SqliteConnectionManager;
+
+    fn setup() -> PooledConnection<SqliteConnectionManager> {
+        let conn = crate::db::init::test_pool().get().unwrap();
+        conn.execute_batch(
+            "INSERT INTO users (id, username, password, email, role)
+             VALUES (1, 'admin', '', 'admin@example.com', 'admin'), (2, 'alice', '', 'alice@example.com', 'user');
+             INSERT INTO passages (id, uuid, title, content, author)
+             VALUES (1, 'a', 'a', '', '管理员'), (2, 'b', 'b', '', 'alice'), (3, 'c', 'c', '', 'Anonymous'), (4, 'd', 'd', '', 'Admin');",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn author_of(conn: &Connection, id: i64) -> (String, Option<i64>) {
+        conn.query_row("SELECT author, author_id FROM passages WHERE id = ?", params![id], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_backfill_links_known_authors() {
+        let conn = setup();
+        assert_eq!(backfill_author_ids(&conn).unwrap(), 3);
+        assert_eq!(author_of(&conn, 1).1, Some(1));
+        assert_eq!(author_of(&conn, 2).1, Some(2));
+        assert_eq!(author_of(&conn, 3).1, None);
+        assert_eq!(author_of(&conn, 4).1, Some(1));
+    }
+
+    #[test]
+    fn test_display_name_sync() {
+        let conn = setup();
+        backfill_author_ids(&conn).unwrap();
+        assert_eq!(display_name(&conn, 2).unwrap().as_deref(), Some("alice"));
+
+        conn.execute("INSERT INTO author_profiles (user_id, display_name) VALUES (2, '爱丽丝')", []).unwrap();
+        assert_eq!(sync_author_name(&conn, 2).unwrap(), 1);
+        assert_eq!(author_of(&conn, 2).0, "爱丽丝");
+        assert_eq!(display_name(&conn, 99).unwrap(), None);
+    }
+
+    #[test]
+    fn test_is_trusted() {
+        let conn = setup();
+        assert!(is_trusted(&conn, None).unwrap());
+        assert!(is_trusted(&conn, Some(1)).unwrap());
+        assert!(!is_trusted(&conn, Some(2)).unwrap());
+        assert!(!is_trusted(&conn, Some(99)).unwrap());
+    }
+}
@@ -0,0 +1,104 @@
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// 分类及其全部子孙分类的名称（`?n` 为根分类名）
+pub fn subtree_names_sql(param: usize) -> String {
+    format!(
+        "WITH RECURSIVE subtree(id, name) AS (
+            SELECT id, name FROM categories WHERE name = ?{0}
+            UNION
+            SELECT c.id, c.name FROM categories c JOIN subtree s ON c.parent_id = s.id
+        ) SELECT name FROM subtree",
+        param
+    )
+}
+
+/// 从根分类到指定分类的路径（含自身），分类不存在时返回空
+pub fn path_to(conn: &Connection, name: &str) -> Result<Vec<(i64, String)>, rusqlite::Error> {
+    // depth 限制用于防御历史数据中已存在的环
+    let mut stmt = conn.prepare(
+        "WITH RECURSIVE path(id, name, parent_id, depth) AS (
+            SELECT id, name, parent_id, 0 FROM categories WHERE name = ?
+            UNION ALL
+            SELECT c.id, c.name, c.parent_id, p.depth + 1 FROM categories c JOIN path p ON c.id = p.parent_id
+            WHERE p.depth < 64
+        ) SELECT id, name FROM path ORDER BY depth DESC",
+    )?;
+    let path = stmt
+        .query_map(params![name], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(path)
+}
+
+/// `ancestor_id` 是否为 `id` 本身或其祖先分类
+pub fn is_self_or_ancestor(conn: &Connection, ancestor_id: i64, id: i64) -> Result<bool, rusqlite::Error> {
+    let found = conn
+        .query_row(
+            "WITH RECURSIVE ancestors(id, depth) AS (
+                SELECT ?2, 0
+                UNION ALL
+                SELECT c.parent_id, a.depth + 1 FROM categories c JOIN ancestors a ON c.id = a.id
+                WHERE c.parent_id IS NOT NULL AND a.depth < 64
+            ) SELECT 1 FROM ancestors WHERE id = ?1 LIMIT 1",
+            params![ancestor_id, id],
+            |row| row.get::<_, i64>(0),
+        )
+        .optional()?;
+    Ok(found.is_some())
+}
+
+/// 删除分类前把它的子分类挂到它的父分类下
+pub fn reparent_children(conn: &Connection, id: i64) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "UPDATE categories SET parent_id = (SELECT parent_id FROM categories WHERE id = ?1) WHERE parent_id = ?1",
+        params![id],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        // 编程 > Rust > 异步，编程 > Go
+        conn.execute_batch(
+            "CREATE TABLE categories (id INTEGER PRIMARY KEY, name TEXT UNIQUE NOT NULL, parent_id INTEGER);
+             INSERT INTO categories (id, name, parent_id) VALUES
+                (1, '编程', NULL), (2, 'Rust', 1), (3, '异步', 2), (4, 'Go', 1), (5, '生活', NULL);",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn subtree(conn: &Connection, name: &str) -> Vec<String> {
+        let mut stmt = conn.prepare(&format!("{} ORDER BY name", subtree_names_sql(1))).unwrap();
+        stmt.query_map(params![name], |row| row.get(0)).unwrap().map(Result::unwrap).collect()
+    }
+
+    #[test]
+    fn test_subtree_and_path() {
+        let conn = setup();
+        assert_eq!(subtree(&conn, "编程"), vec!["Go", "Rust", "异步", "编程"]);
+        assert_eq!(subtree(&conn, "异步"), vec!["异步"]);
+        assert!(subtree(&conn, "不存在").is_empty());
+
+        let path: Vec<String> = path_to(&conn, "异步").unwrap().into_iter().map(|(_, name)| name).collect();
+        assert_eq!(path, vec!["编程", "Rust", "异步"]);
+        assert!(path_to(&conn, "不存在").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_cycle_detection_and_reparent() {
+        let conn = setup();
+        // 编程不能移动到自身或 Rust、异步之下
+        assert!(is_self_or_ancestor(&conn, 1, 1).unwrap());
+        assert!(is_self_or_ancestor(&conn, 1, 3).unwrap());
+        assert!(!is_self_or_ancestor(&conn, 3, 1).unwrap());
+        assert!(!is_self_or_ancestor(&conn, 5, 3).unwrap());
+
+        reparent_children(&conn, 2).unwrap();
+        let parent: Option<i64> = conn.query_row("SELECT parent_id FROM categories WHERE id = 3", [], |row| row.get(0)).unwrap();
+        assert_eq!(parent, Some(1));
+    }
+}
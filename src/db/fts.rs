@@ -0,0 +1,329 @@
+use jieba_rs::Jieba;
+use once_cell::sync::Lazy;
+use rusqlite::{params, Connection};
+
+use super::models::Passage;
+use super::repositories::published_filter;
+
+/// jieba 分词器（加载词典较慢，全局只初始化一次）
+static JIEBA: Lazy<Jieba> = Lazy::new(Jieba::new);
+
+/// 词与词之间的分隔符
+///
+/// 零宽空格在 unicode61 分词器中属于分隔符，
+/// 用它拼接分词结果既能让 FTS5 按词切分中文，又不会改变文本的显示效果
+const WORD_SEPARATOR: char = '\u{200B}';
+
+/// 片段高亮的起止标记（私有区字符，转义 HTML 之后再替换为 <mark>）
+const MARK_START: &str = "\u{E000}";
+const MARK_END: &str = "\u{E001}";
+
+/// 创建全文搜索虚拟表
+pub fn create_table(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS passages_fts USING fts5(
+            uuid UNINDEXED,
+            title,
+            content,
+            summary,
+            tags,
+            tokenize = 'unicode61 remove_diacritics 2'
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// 对文本进行中文分词，词之间以零宽空格分隔
+pub fn segment(text: &str) -> String {
+    let mut output = String::with_capacity(text.len() + text.len() / 2);
+    for word in JIEBA.cut(text, true) {
+        if !output.is_empty() {
+            output.push(WORD_SEPARATOR);
+        }
+        output.push_str(word);
+    }
+    output
+}
+
+/// 提取文本中的词项（小写，去掉标点和单字），用于相关文章的相似度计算
+pub fn terms(text: &str) -> Vec<String> {
+    JIEBA
+        .cut(text, false)
+        .into_iter()
+        .filter(|word| word.chars().count() >= 2 && word.chars().all(|c| c.is_alphanumeric()))
+        .map(|word| word.to_lowercase())
+        .collect()
+}
+
+/// 将 tags 列（JSON 数组）转换为空格分隔的文本
+fn tags_to_text(tags: &str) -> String {
+    serde_json::from_str::<Vec<String>>(tags)
+        .map(|list| list.join(" "))
+        .unwrap_or_else(|_| tags.to_string())
+}
+
+/// 写入或更新文章的索引
+pub fn index_passage(conn: &Connection, uuid: &str, passage: &Passage) -> Result<(), rusqlite::Error> {
+    conn.execute("DELETE FROM passages_fts WHERE uuid = ?", params![uuid])?;
+    conn.execute(
+        "INSERT INTO passages_fts (uuid, title, content, summary, tags) VALUES (?, ?, ?, ?, ?)",
+        params![
+            uuid,
+            segment(&passage.title),
+            segment(passage.original_content.as_deref().unwrap_or(&passage.content)),
+            segment(passage.summary.as_deref().unwrap_or("")),
+            segment(&tags_to_text(&passage.tags)),
+        ],
+    )?;
+    Ok(())
+}
+
+/// 只更新文章索引中的标签（标签改名、删除时使用）
+pub fn update_tags(conn: &Connection, uuid: &str, tags: &str) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "UPDATE passages_fts SET tags = ? WHERE uuid = ?",
+        params![segment(&tags_to_text(tags)), uuid],
+    )?;
+    Ok(())
+}
+
+/// 从索引中移除文章
+pub fn remove_passage(conn: &Connection, uuid: &str) -> Result<(), rusqlite::Error> {
+    conn.execute("DELETE FROM passages_fts WHERE uuid = ?", params![uuid])?;
+    Ok(())
+}
+
+/// 清理已不存在于 passages 表中的索引记录
+pub fn remove_orphans(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "DELETE FROM passages_fts WHERE uuid NOT IN (SELECT uuid FROM passages)",
+        [],
+    )?;
+    Ok(())
+}
+
+/// 索引条数与文章数不一致时重建索引（首次启用搜索、直接在库中增删文章之后）
+///
+/// 只比较条数，直接在库中修改已有文章的内容不会被发现，需要通过接口保存或重新同步文件
+pub fn rebuild_if_needed(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+    let passage_count: i64 = conn.query_row("SELECT COUNT(*) FROM passages", [], |row| row.get(0))?;
+    let indexed_count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM passages_fts WHERE uuid IN (SELECT uuid FROM passages)",
+        [],
+        |row| row.get(0),
+    )?;
+
+    if passage_count == indexed_count {
+        return Ok(());
+    }
+
+    // 清空和重新写入在同一事务中完成，中途失败时保留原索引
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM passages_fts", [])?;
+    let mut stmt = tx.prepare(
+        "SELECT uuid, title, content, original_content, summary, tags FROM passages",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, Option<String>>(3)?,
+            row.get::<_, Option<String>>(4)?,
+            row.get::<_, String>(5)?,
+        ))
+    })?;
+
+    let mut count = 0;
+    for row in rows {
+        let (uuid, title, content, original_content, summary, tags) = row?;
+        tx.execute(
+            "INSERT INTO passages_fts (uuid, title, content, summary, tags) VALUES (?, ?, ?, ?, ?)",
+            params![
+                &uuid,
+                segment(&title),
+                segment(original_content.as_deref().unwrap_or(&content)),
+                segment(summary.as_deref().unwrap_or("")),
+                segment(&tags_to_text(&tags)),
+            ],
+        )?;
+        count += 1;
+    }
+    drop(stmt);
+    tx.commit()?;
+
+    println!("🔍 全文索引已重建: {} 篇文章", count);
+    Ok(())
+}
+
+/// 将用户输入转换为 FTS5 查询表达式
+///
+/// 每个词都作为短语加引号，避免用户输入的 AND/OR/NEAR 及特殊符号被当作查询语法；
+/// 多个词之间为 AND 关系。没有可搜索的词时返回 None
+pub fn build_match_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = JIEBA
+        .cut(input, true)
+        .into_iter()
+        .filter(|word| word.chars().any(|c| c.is_alphanumeric()))
+        .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// 搜索结果
+#[derive(Debug)]
+pub struct SearchHit {
+    pub id: i64,
+    pub uuid: String,
+    pub title: String,
+    pub summary: Option<String>,
+    pub author: String,
+    pub tags: String,
+    pub category: String,
+    pub cover_image: Option<String>,
+    pub published_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub title_highlight: String,
+    pub snippet: String,
+    pub score: f64,
+}
+
+/// 执行全文搜索，结果按相关度排序（标题、标签权重高于正文）
+pub fn search(
+    conn: &Connection,
+    match_query: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<SearchHit>, rusqlite::Error> {
+    let sql = format!(
+        "SELECT p.id, p.uuid, p.title, p.summary, p.author, p.tags, p.category, p.cover_image, p.published_at, p.created_at,
+                highlight(passages_fts, 1, '{start}', '{end}'),
+                snippet(passages_fts, 2, '{start}', '{end}', '…', 24),
+                bm25(passages_fts, 0.0, 10.0, 1.0, 3.0, 5.0) AS rank
+         FROM passages_fts
+         JOIN passages p ON p.uuid = passages_fts.uuid
+         WHERE passages_fts MATCH ?1 AND {filter}
+         ORDER BY rank
+         LIMIT ?3 OFFSET ?4",
+        start = MARK_START,
+        end = MARK_END,
+        filter = published_filter("p", 2),
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let hits = stmt
+        .query_map(params![match_query, chrono::Utc::now(), limit, offset], |row| {
+            Ok(SearchHit {
+                id: row.get(0)?,
+                uuid: row.get(1)?,
+                title: row.get(2)?,
+                summary: row.get(3)?,
+                author: row.get(4)?,
+                tags: row.get(5)?,
+                category: row.get(6)?,
+                cover_image: row.get(7)?,
+                published_at: row.get(8)?,
+                created_at: row.get(9)?,
+                title_highlight: render_highlight(&row.get::<_, String>(10)?),
+                snippet: render_highlight(&row.get::<_, String>(11)?),
+                score: -row.get::<_, f64>(12)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(hits)
+}
+
+/// 统计匹配的公开文章数量
+pub fn count(conn: &Connection, match_query: &str) -> Result<i64, rusqlite::Error> {
+    let sql = format!(
+        "SELECT COUNT(*) FROM passages_fts
+         JOIN passages p ON p.uuid = passages_fts.uuid
+         WHERE passages_fts MATCH ?1 AND {}",
+        published_filter("p", 2),
+    );
+    conn.query_row(&sql, params![match_query, chrono::Utc::now()], |row| row.get(0))
+}
+
+/// 去掉分词分隔符，转义 HTML，并把高亮标记替换为 <mark>
+fn render_highlight(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            WORD_SEPARATOR => {}
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            '\n' | '\r' | '\t' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+        .replace(MARK_START, "<mark>")
+        .replace(MARK_END, "</mark>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use r2d2::PooledConnection;
+    use r2d2_sqlite::SqliteConnectionManager;
+
+    fn setup() -> PooledConnection<SqliteConnectionManager> {
+        crate::db::init::test_pool().get().unwrap()
+    }
+
+    fn insert(conn: &Connection, uuid: &str, title: &str, content: &str, status: &str) {
+        conn.execute(
+            "INSERT INTO passages (uuid, title, content, original_content, status) VALUES (?, ?, ?, ?, ?)",
+            params![uuid, title, content, content, status],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_search_chinese() {
+        let conn = setup();
+        insert(&conn, "a", "全文搜索入门", "使用 SQLite 实现中文全文搜索", "published");
+        insert(&conn, "b", "音乐播放器", "介绍博客的音乐播放功能", "published");
+        rebuild_if_needed(&conn).unwrap();
+
+        let query = build_match_query("搜索").unwrap();
+        let hits = search(&conn, &query, 10, 0).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].uuid, "a");
+        assert!(hits[0].title_highlight.contains("<mark>搜索</mark>"));
+        assert!(!hits[0].snippet.contains(WORD_SEPARATOR));
+        assert_eq!(count(&conn, &query).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_search_skips_drafts() {
+        let conn = setup();
+        insert(&conn, "a", "草稿文章", "尚未发布的内容", "draft");
+        rebuild_if_needed(&conn).unwrap();
+
+        let query = build_match_query("草稿").unwrap();
+        assert!(search(&conn, &query, 10, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_build_match_query_escapes_syntax() {
+        assert_eq!(build_match_query("  ，。 "), None);
+        assert_eq!(build_match_query("rust\" OR"), Some("\"rust\" \"OR\"".to_string()));
+    }
+
+    #[test]
+    fn test_snippet_escapes_html() {
+        let rendered = render_highlight(&format!("<script>{}x{}", MARK_START, MARK_END));
+        assert_eq!(rendered, "&lt;script&gt;<mark>x</mark>");
+    }
+}
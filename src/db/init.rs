@@ -12,6 +12,11 @@ const DB_CONNECTION_TIMEOUT: u64 = 30;  // 连接超时（秒）
 const DB_IDLE_TIMEOUT: u64 = 600;   // 空闲连接超时（秒，10分钟）
 const DB_MAX_LIFETIME: u64 = 1800;  // 连接最大生命周期（秒，30分钟）
 
+/// 数据库结构版本，写入 `PRAGMA user_version`
+///
+/// 表结构或列发生变化时递增，备份恢复时用来拒绝结构不一致的数据库快照
+pub const SCHEMA_VERSION: i64 = 1;
+
 /// 初始化数据库
 pub fn init_db(db_path: &str) -> Result<(), Box<dyn std::error::Error>> {
     // 创建数据库目录
@@ -95,6 +100,12 @@ pub fn init_db(db_path: &str) -> Result<(), Box<dyn std::error::Error>> {
         
         create_tables(&conn)?;
         seed_default_data(&conn)?;
+        backfill_passage_slugs(&conn)?;
+        backfill_rendered_content(&conn)?;
+        backfill_reading_stats(&conn)?;
+        super::passage_tags::backfill_if_needed(&conn)?;
+        super::fts::rebuild_if_needed(&conn)?;
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
     }
 
     // 保存连接池到全局变量
@@ -278,11 +289,124 @@ fn create_tables(conn: &rusqlite::Connection) -> Result<(), Box<dyn std::error::
             published_at DATETIME,
             cover_image TEXT DEFAULT '/img/passage-cover.webp',
             created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            expires_at DATETIME,
+            slug TEXT,
+            word_count INTEGER,
+            reading_minutes INTEGER,
+            author_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+            pinned INTEGER NOT NULL DEFAULT 0,
+            featured INTEGER NOT NULL DEFAULT 0,
+            pin_order INTEGER NOT NULL DEFAULT 0,
+            password_hash TEXT
         )",
         [],
     )?;
 
+    // 检查是否有 expires_at 列（定时下线）
+    let has_expires_at_column = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('passages') WHERE name = 'expires_at'",
+        [],
+        |row| {
+            let count: i64 = row.get(0)?;
+            Ok(count > 0)
+        }
+    ).unwrap_or(false);
+    if !has_expires_at_column {
+        println!("⚠️  检测到缺少 expires_at 列，正在添加...");
+        conn.execute("ALTER TABLE passages ADD COLUMN expires_at DATETIME", [])?;
+        println!("✅ 已添加 expires_at 列");
+    }
+
+    // 检查是否有 slug 列（永久链接）
+    let has_slug_column = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('passages') WHERE name = 'slug'",
+        [],
+        |row| {
+            let count: i64 = row.get(0)?;
+            Ok(count > 0)
+        }
+    ).unwrap_or(false);
+    if !has_slug_column {
+        println!("⚠️  检测到缺少 slug 列，正在添加...");
+        conn.execute("ALTER TABLE passages ADD COLUMN slug TEXT", [])?;
+        println!("✅ 已添加 slug 列");
+    }
+
+    // 检查是否有 word_count / reading_minutes 列（字数和阅读时间，由 backfill_reading_stats 填充）
+    let has_word_count_column = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('passages') WHERE name = 'word_count'",
+        [],
+        |row| {
+            let count: i64 = row.get(0)?;
+            Ok(count > 0)
+        }
+    ).unwrap_or(false);
+    if !has_word_count_column {
+        println!("⚠️  检测到缺少 word_count 列，正在添加...");
+        conn.execute("ALTER TABLE passages ADD COLUMN word_count INTEGER", [])?;
+        conn.execute("ALTER TABLE passages ADD COLUMN reading_minutes INTEGER", [])?;
+        println!("✅ 已添加 word_count、reading_minutes 列");
+    }
+
+    // 检查是否有 author_id 列（文章作者对应的用户）
+    let has_author_id_column = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('passages') WHERE name = 'author_id'",
+        [],
+        |row| {
+            let count: i64 = row.get(0)?;
+            Ok(count > 0)
+        }
+    ).unwrap_or(false);
+    if !has_author_id_column {
+        println!("⚠️  检测到缺少 author_id 列，正在添加...");
+        conn.execute("ALTER TABLE passages ADD COLUMN author_id INTEGER REFERENCES users(id) ON DELETE SET NULL", [])?;
+        // 旧数据库中用户表已经存在，按作者名关联已有用户
+        let has_users_table = conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='users'",
+            [],
+            |row| row.get::<_, i64>(0),
+        )? > 0;
+        if has_users_table {
+            let linked = super::authors::backfill_author_ids(conn)?;
+            println!("✅ 已添加 author_id 列，{} 篇文章已关联作者", linked);
+        } else {
+            println!("✅ 已添加 author_id 列");
+        }
+    }
+
+    // 检查是否有 pinned / featured / pin_order 列（置顶、推荐及其手动排序）
+    let has_pinned_column = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('passages') WHERE name = 'pinned'",
+        [],
+        |row| {
+            let count: i64 = row.get(0)?;
+            Ok(count > 0)
+        }
+    ).unwrap_or(false);
+    if !has_pinned_column {
+        println!("⚠️  检测到缺少 pinned 列，正在添加...");
+        conn.execute("ALTER TABLE passages ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0", [])?;
+        conn.execute("ALTER TABLE passages ADD COLUMN featured INTEGER NOT NULL DEFAULT 0", [])?;
+        conn.execute("ALTER TABLE passages ADD COLUMN pin_order INTEGER NOT NULL DEFAULT 0", [])?;
+        println!("✅ 已添加 pinned、featured、pin_order 列");
+    }
+
+    // 检查是否有 password_hash 列（visibility = 'password' 的文章的访问密码，Argon2 哈希）
+    let has_password_hash_column = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('passages') WHERE name = 'password_hash'",
+        [],
+        |row| {
+            let count: i64 = row.get(0)?;
+            Ok(count > 0)
+        }
+    ).unwrap_or(false);
+    if !has_password_hash_column {
+        println!("⚠️  检测到缺少 password_hash 列，正在添加...");
+        conn.execute("ALTER TABLE passages ADD COLUMN password_hash TEXT", [])?;
+        println!("✅ 已添加 password_hash 列");
+    }
+
     // 创建文章表索引
     conn.execute("CREATE UNIQUE INDEX IF NOT EXISTS idx_passages_uuid ON passages(uuid)", [])?;
     conn.execute("CREATE UNIQUE INDEX IF NOT EXISTS idx_passages_file_path ON passages(file_path)", [])?;
@@ -293,7 +417,13 @@ fn create_tables(conn: &rusqlite::Connection) -> Result<(), Box<dyn std::error::
     conn.execute("CREATE INDEX IF NOT EXISTS idx_passages_category_status ON passages(category, status)", [])?;
     conn.execute("CREATE INDEX IF NOT EXISTS idx_passages_visibility ON passages(visibility)", [])?;
     conn.execute("CREATE INDEX IF NOT EXISTS idx_passages_published_at ON passages(published_at)", [])?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_passages_author_id ON passages(author_id)", [])?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_passages_pinned ON passages(pinned, pin_order)", [])?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_passages_featured ON passages(featured, pin_order)", [])?;
     conn.execute("CREATE INDEX IF NOT EXISTS idx_passages_scheduled ON passages(is_scheduled, published_at)", [])?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_passages_expires_at ON passages(expires_at)", [])?;
+    conn.execute("CREATE UNIQUE INDEX IF NOT EXISTS idx_passages_slug ON passages(slug)", [])?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_passages_word_count ON passages(word_count)", [])?;
     // 添加复合索引优化统计查询
     conn.execute("CREATE INDEX IF NOT EXISTS idx_passages_status_visibility ON passages(status, visibility)", [])?;
 
@@ -437,12 +567,30 @@ fn create_tables(conn: &rusqlite::Connection) -> Result<(), Box<dyn std::error::
             sort_order INTEGER DEFAULT 0,
             is_enabled BOOLEAN DEFAULT 1,
             created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            parent_id INTEGER REFERENCES categories(id) ON DELETE SET NULL
         )",
         [],
     )?;
+
+    // 检查是否有 parent_id 列（分类层级）
+    let has_parent_id_column = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('categories') WHERE name = 'parent_id'",
+        [],
+        |row| {
+            let count: i64 = row.get(0)?;
+            Ok(count > 0)
+        }
+    ).unwrap_or(false);
+    if !has_parent_id_column {
+        println!("⚠️  检测到分类表缺少 parent_id 列，正在添加...");
+        conn.execute("ALTER TABLE categories ADD COLUMN parent_id INTEGER REFERENCES categories(id) ON DELETE SET NULL", [])?;
+        println!("✅ 已添加 parent_id 列");
+    }
+
     conn.execute("CREATE UNIQUE INDEX IF NOT EXISTS idx_categories_name ON categories(name)", [])?;
     conn.execute("CREATE INDEX IF NOT EXISTS idx_categories_sort ON categories(sort_order)", [])?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_categories_parent ON categories(parent_id, sort_order)", [])?;
 
     // 创建标签表
     conn.execute(
@@ -522,10 +670,184 @@ fn create_tables(conn: &rusqlite::Connection) -> Result<(), Box<dyn std::error::
     conn.execute("CREATE INDEX IF NOT EXISTS idx_friend_links_sort ON friend_links(sort_order)", [])?;
     conn.execute("CREATE INDEX IF NOT EXISTS idx_friend_links_enabled ON friend_links(is_enabled)", [])?;
 
+    // 创建文章修订记录表
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS passage_revisions (
+            id INTEGER PRIMARY KEY,
+            passage_uuid TEXT NOT NULL,
+            title TEXT NOT NULL,
+            original_content TEXT NOT NULL DEFAULT '',
+            summary TEXT,
+            tags TEXT DEFAULT '[]',
+            category TEXT DEFAULT '未分类',
+            editor TEXT DEFAULT '',
+            source TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (passage_uuid) REFERENCES passages(uuid) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_passage_revisions_uuid_created ON passage_revisions(passage_uuid, created_at DESC)", [])?;
+
+    // 创建文章定时发布/下线记录表
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS passage_schedule_log (
+            id INTEGER PRIMARY KEY,
+            passage_uuid TEXT NOT NULL,
+            action TEXT NOT NULL,
+            from_status TEXT NOT NULL,
+            to_status TEXT NOT NULL,
+            scheduled_at DATETIME,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (passage_uuid) REFERENCES passages(uuid) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_passage_schedule_log_uuid ON passage_schedule_log(passage_uuid)", [])?;
+
+    // 创建文章旧 slug 跳转表（slug 修改后旧链接 301 跳转到新链接）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS passage_slug_redirects (
+            slug TEXT PRIMARY KEY,
+            passage_uuid TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (passage_uuid) REFERENCES passages(uuid) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // 创建文章预览链接表
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS preview_links (
+            id INTEGER PRIMARY KEY,
+            token_id TEXT UNIQUE NOT NULL,
+            passage_uuid TEXT NOT NULL,
+            note TEXT,
+            created_by TEXT DEFAULT '',
+            expires_at DATETIME NOT NULL,
+            revoked_at DATETIME,
+            last_used_at DATETIME,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (passage_uuid) REFERENCES passages(uuid) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_preview_links_passage_uuid ON preview_links(passage_uuid)", [])?;
+
+    // 创建系列表
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS series (
+            id INTEGER PRIMARY KEY,
+            title TEXT NOT NULL,
+            description TEXT DEFAULT '',
+            cover_image TEXT,
+            sort_order INTEGER DEFAULT 0,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    // 创建系列文章表（一篇文章最多属于一个系列，position 从 1 开始）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS series_passages (
+            series_id INTEGER NOT NULL,
+            passage_uuid TEXT UNIQUE NOT NULL,
+            position INTEGER NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (series_id, passage_uuid),
+            FOREIGN KEY (series_id) REFERENCES series(id) ON DELETE CASCADE,
+            FOREIGN KEY (passage_uuid) REFERENCES passages(uuid) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_series_passages_position ON series_passages(series_id, position)", [])?;
+
+    // 创建全文搜索索引表
+    super::fts::create_table(conn)?;
+
+    // 创建相关文章表
+    super::related::create_table(conn)?;
+
+    // 创建文章-标签关联表
+    super::passage_tags::create_table(conn)?;
+
+    // 创建作者资料表
+    super::authors::create_table(conn)?;
+
     println!("✅ 数据库表结构创建完成");
     Ok(())
 }
 
+/// 为没有 slug 的文章（升级前的数据、默认文章）根据标题生成 slug，
+/// 纯数字的旧 slug（会被当作文章 ID）改为带前缀的 slug，旧 slug 保留为跳转
+fn backfill_passage_slugs(conn: &rusqlite::Connection) -> Result<(), Box<dyn std::error::Error>> {
+    let mut stmt = conn.prepare(
+        "SELECT uuid, title, slug FROM passages
+         WHERE slug IS NULL OR (slug <> '' AND slug NOT GLOB '*[^0-9]*')
+         ORDER BY id"
+    )?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (uuid, title, old_slug) in &rows {
+        let slug = super::repositories::unique_slug_with(conn, old_slug.as_deref().unwrap_or(title), Some(uuid))?;
+        conn.execute("UPDATE passages SET slug = ? WHERE uuid = ?", rusqlite::params![slug, uuid])?;
+        super::repositories::record_slug_change(conn, uuid, old_slug.as_deref(), Some(&slug))?;
+    }
+    if !rows.is_empty() {
+        println!("🔗 已为 {} 篇文章生成 slug", rows.len());
+    }
+    Ok(())
+}
+
+/// 重新渲染旧版渲染器生成的正文（标题没有锚点、代码块没有服务端高亮）
+fn backfill_rendered_content(conn: &rusqlite::Connection) -> Result<(), Box<dyn std::error::Error>> {
+    let mut stmt = conn.prepare(
+        "SELECT uuid, original_content, author_id FROM passages
+         WHERE original_content IS NOT NULL AND (content GLOB '*<h[1-6]>*' OR content GLOB '*<pre><code*')
+         ORDER BY id",
+    )?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<i64>>(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (uuid, original_content, author_id) in &rows {
+        let trusted = super::authors::is_trusted(conn, *author_id)?;
+        let html_content = crate::markdown::render_passage(original_content, trusted);
+        let reading = crate::reading::stats(&html_content);
+        conn.execute(
+            "UPDATE passages SET content = ?, word_count = ?, reading_minutes = ? WHERE uuid = ?",
+            rusqlite::params![html_content, reading.word_count, reading.reading_minutes, uuid],
+        )?;
+    }
+    if !rows.is_empty() {
+        println!("📝 已重新渲染 {} 篇文章的正文", rows.len());
+    }
+    Ok(())
+}
+
+/// 为还没有统计字数的文章计算字数和阅读时间
+fn backfill_reading_stats(conn: &rusqlite::Connection) -> Result<(), Box<dyn std::error::Error>> {
+    let mut stmt = conn.prepare("SELECT uuid, content FROM passages WHERE word_count IS NULL OR reading_minutes IS NULL")?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (uuid, content) in &rows {
+        let reading = crate::reading::stats(content);
+        conn.execute(
+            "UPDATE passages SET word_count = ?, reading_minutes = ? WHERE uuid = ?",
+            rusqlite::params![reading.word_count, reading.reading_minutes, uuid],
+        )?;
+    }
+    if !rows.is_empty() {
+        println!("📏 已统计 {} 篇文章的字数和阅读时间", rows.len());
+    }
+    Ok(())
+}
+
 /// 插入默认数据
 fn seed_default_data(conn: &rusqlite::Connection) -> Result<(), Box<dyn std::error::Error>> {
     // 检查是否已有用户
@@ -626,6 +948,13 @@ fn seed_default_data(conn: &rusqlite::Connection) -> Result<(), Box<dyn std::err
             ("icp_number", "", "string", "ICP 备案号", "template"),
             ("police_record_code", "", "string", "公安备案代码（用于链接）", "template"),
             ("police_record_content", "", "string", "公安备案内容（显示文字）", "template"),
+            
+            // 订阅源设置
+            ("feed_full_content", "false", "boolean", "订阅源是否输出全文（否则只输出摘要）", "feed"),
+            ("feed_item_limit", "20", "number", "订阅源包含的文章数量", "feed"),
+            
+            // 搜索引擎设置
+            ("robots_txt", "", "string", "自定义 robots.txt 内容（留空使用默认规则）", "seo"),
         ];
 
         for (key, value, setting_type, description, category) in default_settings {
@@ -711,6 +1040,13 @@ fn seed_default_data(conn: &rusqlite::Connection) -> Result<(), Box<dyn std::err
             ("icp_number", "", "string", "ICP 备案号", "template"),
             ("police_record_code", "", "string", "公安备案代码（用于链接）", "template"),
             ("police_record_content", "", "string", "公安备案内容（显示文字）", "template"),
+            
+            // 订阅源设置
+            ("feed_full_content", "false", "boolean", "订阅源是否输出全文（否则只输出摘要）", "feed"),
+            ("feed_item_limit", "20", "number", "订阅源包含的文章数量", "feed"),
+            
+            // 搜索引擎设置
+            ("robots_txt", "", "string", "自定义 robots.txt 内容（留空使用默认规则）", "seo"),
         ];
 
         // 获取所有现有设置的键名
@@ -782,15 +1118,15 @@ fn seed_default_data(conn: &rusqlite::Connection) -> Result<(), Box<dyn std::err
         ];
 
         for (title, content, summary, author, tags, category, status, file_path, visibility) in sample_passages {
-            // 将 Markdown 转换为 HTML
-            let html_content = convert_markdown_to_html(content);
+            // 将 Markdown 转换为 HTML（默认文章由站长创建）
+            let html_content = crate::markdown::render_passage(content, true);
             
             // 生成 UUID
             let uuid = crate::id_generator::generate_unique_id();
             
             match conn.execute(
-                "INSERT OR IGNORE INTO passages (uuid, title, content, original_content, summary, author, tags, category, status, file_path, visibility, created_at, updated_at) 
-                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                "INSERT OR IGNORE INTO passages (uuid, title, content, original_content, summary, author, tags, category, status, file_path, visibility, created_at, updated_at, author_id) 
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, (SELECT id FROM users WHERE username = ?))",
                 rusqlite::params![
                     &uuid,
                     title,
@@ -805,6 +1141,7 @@ fn seed_default_data(conn: &rusqlite::Connection) -> Result<(), Box<dyn std::err
                     visibility,
                     chrono::Utc::now(),
                     chrono::Utc::now(),
+                    author,
                 ],
             ) {
                 Ok(rows_affected) => {
@@ -934,19 +1271,23 @@ fn seed_default_data(conn: &rusqlite::Connection) -> Result<(), Box<dyn std::err
     Ok(())
 }
 
-/// 将 Markdown 转换为 HTML
-fn convert_markdown_to_html(markdown: &str) -> String {
-    use pulldown_cmark::{Parser, html, Options};
-
-    // 配置选项，启用表格和其他扩展
-    let mut options = Options::empty();
-    options.insert(Options::ENABLE_TABLES);
-    options.insert(Options::ENABLE_STRIKETHROUGH);
-    options.insert(Options::ENABLE_TASKLISTS);
+/// 测试用数据库：共享缓存的内存数据库（连接池中的连接访问同一个库），已建立完整表结构
+#[cfg(test)]
+pub fn test_pool() -> std::sync::Arc<Pool<SqliteConnectionManager>> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
-    let parser = Parser::new_ext(markdown, options);
-    let mut html_output = String::new();
-    html::push_html(&mut html_output, parser);
-
-    html_output
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+    let name = format!(
+        "file:rustblog-test-{}-{}?mode=memory&cache=shared",
+        std::process::id(),
+        NEXT_ID.fetch_add(1, Ordering::Relaxed)
+    );
+    // 保持至少一个空闲连接，内存数据库在最后一个连接关闭时销毁
+    let pool = Pool::builder()
+        .max_size(4)
+        .min_idle(Some(1))
+        .build(SqliteConnectionManager::file(name))
+        .unwrap();
+    create_tables(&pool.get().unwrap()).unwrap();
+    std::sync::Arc::new(pool)
 }
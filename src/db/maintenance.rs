@@ -0,0 +1,77 @@
+use rusqlite::{params, Connection};
+
+use crate::sanitize::{clean, sanitize_css, Policy};
+
+/// 重新清理的结果统计
+#[derive(Debug, Default)]
+pub struct SanitizeReport {
+    pub comments: usize,
+    pub passages: usize,
+    pub styles: usize,
+}
+
+/// 按当前规则重新清理已有数据：评论、非管理员作者的文章和自定义 CSS
+///
+/// 只有内容发生变化的行才会写回，可以重复执行
+pub fn resanitize(conn: &Connection) -> Result<SanitizeReport, Box<dyn std::error::Error>> {
+    let tx = conn.unchecked_transaction()?;
+    let mut report = SanitizeReport::default();
+
+    let comments = query_pairs(&tx, "SELECT id, content FROM comments")?;
+    for (id, content) in comments {
+        let cleaned = clean(&content, Policy::Comment);
+        if cleaned != content {
+            tx.execute("UPDATE comments SET content = ? WHERE id = ?", params![cleaned, id])?;
+            report.comments += 1;
+        }
+    }
+
+    // 与渲染时的规则一致：作者不是管理员的文章视为不可信（见 authors::is_trusted）
+    let passages = query_pairs(
+        &tx,
+        "SELECT p.id, p.content FROM passages p
+         WHERE p.author_id IS NOT NULL
+           AND NOT EXISTS (SELECT 1 FROM users u WHERE u.id = p.author_id AND u.role = 'admin')",
+    )?;
+    for (id, content) in passages {
+        let cleaned = clean(&content, Policy::Passage);
+        if cleaned != content {
+            let reading = crate::reading::stats(&cleaned);
+            tx.execute(
+                "UPDATE passages SET content = ?, word_count = ?, reading_minutes = ? WHERE id = ?",
+                params![cleaned, reading.word_count, reading.reading_minutes, id],
+            )?;
+            report.passages += 1;
+        }
+    }
+
+    for table in ["about_main_cards", "about_sub_cards"] {
+        let styles = query_pairs(&tx, &format!("SELECT id, custom_css FROM {} WHERE custom_css != ''", table))?;
+        for (id, css) in styles {
+            let cleaned = sanitize_css(&css);
+            if cleaned != css {
+                tx.execute(&format!("UPDATE {} SET custom_css = ? WHERE id = ?", table), params![cleaned, id])?;
+                report.styles += 1;
+            }
+        }
+    }
+    let settings = query_pairs(&tx, "SELECT id, value FROM settings WHERE key LIKE '%custom_css'")?;
+    for (id, css) in settings {
+        let cleaned = sanitize_css(&css);
+        if cleaned != css {
+            tx.execute("UPDATE settings SET value = ? WHERE id = ?", params![cleaned, id])?;
+            report.styles += 1;
+        }
+    }
+
+    tx.commit()?;
+    Ok(report)
+}
+
+fn query_pairs(conn: &Connection, sql: &str) -> Result<Vec<(i64, String)>, rusqlite::Error> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get::<_, Option<String>>(1)?.unwrap_or_default())))?
+        .collect();
+    rows
+}
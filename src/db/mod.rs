@@ -1,5 +1,11 @@
 pub mod models;
 pub mod repositories;
 pub mod init;
+pub mod fts;
+pub mod related;
+pub mod passage_tags;
+pub mod category_tree;
+pub mod authors;
+pub mod maintenance;
 
-pub use init::{init_db, get_db_pool, get_db_pool_sync, get_pool_status};
\ No newline at end of file
+pub use init::{init_db, get_db_pool, get_db_pool_sync, get_pool_status, SCHEMA_VERSION};
\ No newline at end of file
@@ -21,6 +21,84 @@ pub struct Passage {
     pub cover_image: Option<String>,  // 封面图片路径
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,  // 定时下线时间
+    pub slug: Option<String>,  // 永久链接中的 slug，唯一
+    pub word_count: i64,  // 正文字数（保存时计算）
+    pub reading_minutes: i64,  // 预计阅读分钟数（保存时计算）
+    pub author_id: Option<i64>,  // 作者对应的用户 ID，匿名投稿为空
+    pub pinned: bool,  // 置顶：首页列表中排在最前
+    pub featured: bool,  // 推荐：显示在首页头图区
+    pub pin_order: i64,  // 置顶、推荐文章的手动排序，小的在前
+}
+
+impl Passage {
+    /// 是否对访客可见（已发布、公开、定时发布时间已到、未下线）
+    pub fn is_publicly_visible(&self) -> bool {
+        self.visibility == "public" && self.is_live()
+    }
+
+    /// 是否已发布、定时发布时间已到且未下线（不考虑可见性）
+    pub fn is_live(&self) -> bool {
+        let now = Utc::now();
+        self.status == "published"
+            && !(self.is_scheduled && self.published_at.is_some_and(|d| d > now))
+            && self.expires_at.is_none_or(|d| d > now)
+    }
+
+    /// 是否需要输入访问密码才能阅读正文
+    pub fn is_password_protected(&self) -> bool {
+        self.visibility == "password"
+    }
+
+    /// 解锁前可以展示的摘要：自动生成的摘要取自正文，只展示手动填写的摘要
+    pub fn locked_summary(&self) -> Option<String> {
+        self.summary
+            .clone()
+            .filter(|summary| *summary != crate::markdown::extract_summary(&self.content))
+    }
+
+    /// 标签列表（tags 列为 JSON 数组字符串，解析失败时返回空列表）
+    pub fn tag_list(&self) -> Vec<String> {
+        serde_json::from_str(&self.tags).unwrap_or_default()
+    }
+
+    /// 规范链接（/passage/{yyyy}/{mm}/{dd}/{slug}），没有 slug 时返回 None
+    pub fn permalink(&self) -> Option<String> {
+        self.slug.as_deref().map(|slug| crate::slug::permalink(&self.created_at, slug))
+    }
+
+    /// 测试用的已发布公开文章
+    #[cfg(test)]
+    pub fn sample(title: &str, content: &str) -> Self {
+        let now = Utc::now();
+        Self {
+            id: None,
+            uuid: None,
+            title: title.to_string(),
+            content: crate::markdown::render(content, crate::markdown::Profile::Full),
+            original_content: Some(content.to_string()),
+            summary: None,
+            author: "admin".to_string(),
+            tags: "[]".to_string(),
+            category: "未分类".to_string(),
+            status: "published".to_string(),
+            file_path: None,
+            visibility: "public".to_string(),
+            is_scheduled: false,
+            published_at: None,
+            cover_image: None,
+            created_at: now,
+            updated_at: now,
+            expires_at: None,
+            slug: None,
+            word_count: 0,
+            reading_minutes: 0,
+            author_id: None,
+            pinned: false,
+            featured: false,
+            pin_order: 0,
+        }
+    }
 }
 
 /// 用户模型
@@ -36,6 +114,16 @@ pub struct User {
     pub updated_at: DateTime<Utc>,
 }
 
+/// 作者公开资料（用户名来自 users 表，其余来自 author_profiles 表）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorProfile {
+    pub user_id: i64,
+    pub username: String,
+    pub display_name: String,
+    pub avatar: String,
+    pub bio: String,
+}
+
 /// 评论模型
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Comment {
@@ -94,6 +182,8 @@ pub struct AboutSubCard {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Category {
     pub id: Option<i64>,
+    /// 父分类 ID，顶级分类为 None
+    pub parent_id: Option<i64>,
     pub name: String,
     pub description: String,
     pub icon: String,
@@ -117,6 +207,14 @@ pub struct Tag {
     pub updated_at: DateTime<Utc>,
 }
 
+/// 标签使用统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagCount {
+    pub id: i64,
+    pub name: String,
+    pub count: i64,
+}
+
 /// 附件模型
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Attachment {
@@ -158,4 +256,44 @@ pub struct FriendLink {
     pub is_enabled: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
-}
\ No newline at end of file
+}
+/// 系列模型（多篇文章组成的连载、教程）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Series {
+    pub id: Option<i64>,
+    pub title: String,
+    pub description: String,
+    pub cover_image: Option<String>,  // 封面图片路径
+    pub sort_order: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 文章预览链接模型（未发布或非公开文章的签名分享链接）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviewLink {
+    pub id: Option<i64>,
+    pub token_id: String,  // 令牌中的 jti，用于吊销
+    pub passage_uuid: String,
+    pub note: Option<String>,  // 备注（例如审阅人）
+    pub created_by: String,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 文章修订记录模型
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PassageRevision {
+    pub id: Option<i64>,
+    pub passage_uuid: String,
+    pub title: String,
+    pub original_content: String,  // 修订时的 Markdown 内容
+    pub summary: Option<String>,
+    pub tags: String,  // JSON 数组字符串
+    pub category: String,
+    pub editor: String,  // 修改人
+    pub source: String,  // 修改来源：api / editor / sync / restore / initial
+    pub created_at: DateTime<Utc>,
+}
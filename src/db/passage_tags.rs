@@ -0,0 +1,187 @@
+use rusqlite::{params, Connection};
+
+/// 创建文章-标签关联表
+///
+/// passages.tags 仍保留 JSON 数组供接口直接返回，关联表用于按标签筛选、统计和改名
+pub fn create_table(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS passage_tags (
+            passage_id INTEGER NOT NULL,
+            tag_id INTEGER NOT NULL,
+            PRIMARY KEY (passage_id, tag_id),
+            FOREIGN KEY (passage_id) REFERENCES passages(id) ON DELETE CASCADE,
+            FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_passage_tags_tag ON passage_tags(tag_id, passage_id)", [])?;
+    Ok(())
+}
+
+/// 解析 tags 列：JSON 数组或逗号分隔的字符串，去掉空白和重复项
+pub fn parse_names(tags: &str) -> Vec<String> {
+    let raw: Vec<String> = serde_json::from_str(tags)
+        .unwrap_or_else(|_| tags.split(',').map(str::to_string).collect());
+
+    let mut names: Vec<String> = Vec::new();
+    for name in raw {
+        let name = name.trim();
+        if !name.is_empty() && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// 按 tags 列重写文章的标签关联，不存在的标签自动创建
+pub fn set_passage_tags(conn: &Connection, passage_id: i64, tags: &str) -> Result<(), rusqlite::Error> {
+    conn.execute("DELETE FROM passage_tags WHERE passage_id = ?", params![passage_id])?;
+    let now = chrono::Utc::now();
+    for name in parse_names(tags) {
+        conn.execute(
+            "INSERT OR IGNORE INTO tags (name, description, created_at, updated_at) VALUES (?1, '用户创建的标签: ' || ?1, ?2, ?2)",
+            params![name, now],
+        )?;
+        conn.execute(
+            "INSERT OR IGNORE INTO passage_tags (passage_id, tag_id) SELECT ?, id FROM tags WHERE name = ?",
+            params![passage_id, name],
+        )?;
+    }
+    Ok(())
+}
+
+/// 清理已删除文章或已删除标签的关联
+pub fn remove_orphans(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "DELETE FROM passage_tags
+         WHERE passage_id NOT IN (SELECT id FROM passages) OR tag_id NOT IN (SELECT id FROM tags)",
+        [],
+    )?;
+    Ok(())
+}
+
+/// 关联表为空时根据 passages.tags 生成关联（从 JSON 列迁移）
+pub fn backfill_if_needed(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+    let linked: i64 = conn.query_row("SELECT COUNT(*) FROM passage_tags", [], |row| row.get(0))?;
+    if linked > 0 {
+        return Ok(());
+    }
+
+    let mut stmt = conn.prepare("SELECT id, tags FROM passages WHERE tags IS NOT NULL AND tags NOT IN ('', '[]')")?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let tx = conn.unchecked_transaction()?;
+    for (id, tags) in &rows {
+        set_passage_tags(&tx, *id, tags)?;
+    }
+    tx.commit()?;
+
+    if !rows.is_empty() {
+        println!("🏷️  已为 {} 篇文章建立标签关联", rows.len());
+    }
+    Ok(())
+}
+
+/// 标签改名或删除时更新关联文章的 tags 列；`new_name` 为 None 时从文章中移除该标签
+///
+/// 返回 tags 发生变化的文章 ID（调用方需要同步改写这些文章的 Markdown 文件）
+pub fn rewrite_tag(conn: &Connection, tag_id: i64, new_name: Option<&str>) -> Result<Vec<i64>, rusqlite::Error> {
+    let old_name: String = conn.query_row("SELECT name FROM tags WHERE id = ?", params![tag_id], |row| row.get(0))?;
+
+    let mut stmt = conn.prepare(
+        "SELECT p.id, p.uuid, p.tags FROM passage_tags pt JOIN passages p ON p.id = pt.passage_id WHERE pt.tag_id = ?",
+    )?;
+    let rows = stmt
+        .query_map(params![tag_id], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut changed = Vec::new();
+    for (id, uuid, tags) in &rows {
+        let mut names: Vec<String> = Vec::new();
+        for name in parse_names(tags) {
+            let name = if name == old_name {
+                match new_name {
+                    Some(new_name) => new_name.to_string(),
+                    None => continue,
+                }
+            } else {
+                name
+            };
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        let tags_json = serde_json::to_string(&names).unwrap_or_else(|_| "[]".to_string());
+        if tags_json == *tags {
+            continue;
+        }
+        conn.execute("UPDATE passages SET tags = ? WHERE id = ?", params![tags_json, id])?;
+        super::fts::update_tags(conn, uuid, &tags_json)?;
+        changed.push(*id);
+    }
+
+    if new_name.is_none() {
+        conn.execute("DELETE FROM passage_tags WHERE tag_id = ?", params![tag_id])?;
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use r2d2::PooledConnection;
+    use r2d2_sqlite::SqliteConnectionManager;
+
+    fn setup() -> PooledConnection<SqliteConnectionManager> {
+        crate::db::init::test_pool().get().unwrap()
+    }
+
+    fn tags_of(conn: &Connection, id: i64) -> String {
+        conn.query_row("SELECT tags FROM passages WHERE id = ?", params![id], |row| row.get(0)).unwrap()
+    }
+
+    fn linked(conn: &Connection, name: &str) -> Vec<i64> {
+        let mut stmt = conn
+            .prepare("SELECT pt.passage_id FROM passage_tags pt JOIN tags t ON t.id = pt.tag_id WHERE t.name = ? ORDER BY 1")
+            .unwrap();
+        stmt.query_map(params![name], |row| row.get(0)).unwrap().map(Result::unwrap).collect()
+    }
+
+    #[test]
+    fn test_parse_names() {
+        assert_eq!(parse_names(r#"["rust", " web ", "rust", ""]"#), vec!["rust", "web"]);
+        assert_eq!(parse_names("rust, web,,"), vec!["rust", "web"]);
+        assert!(parse_names("[]").is_empty());
+    }
+
+    #[test]
+    fn test_backfill_and_rewrite() {
+        let conn = setup();
+        conn.execute_batch(
+            r#"INSERT INTO passages (id, uuid, title, content, tags)
+               VALUES (1, 'a', 'a', '', '["rust","web"]'), (2, 'b', 'b', '', 'rust'), (3, 'c', 'c', '', '[]');"#,
+        )
+        .unwrap();
+        backfill_if_needed(&conn).unwrap();
+        assert_eq!(linked(&conn, "rust"), vec![1, 2]);
+        assert_eq!(linked(&conn, "web"), vec![1]);
+
+        // 改名后文章的 tags 列随之更新
+        let rust_id: i64 = conn.query_row("SELECT id FROM tags WHERE name = 'rust'", [], |row| row.get(0)).unwrap();
+        assert_eq!(rewrite_tag(&conn, rust_id, Some("rust-lang")).unwrap().len(), 2);
+        assert_eq!(tags_of(&conn, 1), r#"["rust-lang","web"]"#);
+        assert_eq!(tags_of(&conn, 2), r#"["rust-lang"]"#);
+        // 没有文章需要修改时不返回文章
+        assert!(rewrite_tag(&conn, rust_id, Some("rust-lang")).unwrap().is_empty());
+
+        // 删除标签时从文章中移除
+        let web_id: i64 = conn.query_row("SELECT id FROM tags WHERE name = 'web'", [], |row| row.get(0)).unwrap();
+        rewrite_tag(&conn, web_id, None).unwrap();
+        assert_eq!(tags_of(&conn, 1), r#"["rust-lang"]"#);
+        assert!(linked(&conn, "web").is_empty());
+    }
+}
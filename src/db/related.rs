@@ -0,0 +1,276 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use r2d2::Pool;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{params, Connection};
+use tokio::sync::Notify;
+
+use super::fts::terms;
+
+/// 每篇文章保存的相关文章数量
+pub const MAX_RELATED: usize = 10;
+
+/// 各项得分的权重：正文相似度、标签重合度、同分类
+const TEXT_WEIGHT: f64 = 0.5;
+const TAG_WEIGHT: f64 = 0.3;
+const CATEGORY_WEIGHT: f64 = 0.2;
+
+/// 文章变更后等待多久再重算（合并同步等批量操作触发的多次通知）
+const REBUILD_DELAY: Duration = Duration::from_secs(2);
+
+/// 重算通知（未被消费时会保留一次，不会丢失）
+static REBUILD_NOTIFY: Lazy<Notify> = Lazy::new(Notify::new);
+
+/// 参与计算的文章
+#[derive(Debug, Clone)]
+pub struct Document {
+    pub uuid: String,
+    pub text: String,
+    pub tags: Vec<String>,
+    pub category: String,
+    /// 是否可作为推荐结果（已发布且公开）
+    pub candidate: bool,
+}
+
+/// 创建相关文章表
+pub fn create_table(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS passage_related (
+            passage_uuid TEXT NOT NULL,
+            related_uuid TEXT NOT NULL,
+            score REAL NOT NULL,
+            rank INTEGER NOT NULL,
+            computed_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (passage_uuid, related_uuid),
+            FOREIGN KEY (passage_uuid) REFERENCES passages(uuid) ON DELETE CASCADE,
+            FOREIGN KEY (related_uuid) REFERENCES passages(uuid) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_passage_related_rank ON passage_related(passage_uuid, rank)", [])?;
+    Ok(())
+}
+
+/// 标记相关文章需要重算（文章创建、更新、删除、同步之后调用）
+pub fn mark_dirty() {
+    REBUILD_NOTIFY.notify_one();
+}
+
+/// 启动后台重算任务，启动时先计算一次
+pub fn spawn_worker(pool: Arc<Pool<SqliteConnectionManager>>) -> tokio::task::JoinHandle<()> {
+    mark_dirty();
+    tokio::spawn(async move {
+        loop {
+            REBUILD_NOTIFY.notified().await;
+            tokio::time::sleep(REBUILD_DELAY).await;
+
+            let pool = pool.clone();
+            // 计算过程是纯 CPU 和同步 IO，放到阻塞线程池执行；错误转为字符串（Box<dyn Error> 不是 Send）
+            let result = tokio::task::spawn_blocking(move || {
+                let conn = pool.get().map_err(|e| e.to_string())?;
+                rebuild(&conn).map_err(|e| e.to_string())
+            })
+            .await;
+
+            match result {
+                Ok(Ok(count)) => println!("🔗 相关文章已重算: {} 篇文章", count),
+                Ok(Err(e)) => eprintln!("❌ 重算相关文章失败: {}", e),
+                Err(e) => eprintln!("❌ 重算相关文章任务异常: {}", e),
+            }
+        }
+    })
+}
+
+/// 重新计算全部文章的相关文章，返回参与计算的文章数量
+pub fn rebuild(conn: &Connection) -> Result<usize, Box<dyn std::error::Error>> {
+    let documents = load_documents(conn)?;
+    let related = compute(&documents, MAX_RELATED);
+
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM passage_related", [])?;
+    {
+        let mut stmt = tx.prepare(
+            "INSERT INTO passage_related (passage_uuid, related_uuid, score, rank) VALUES (?, ?, ?, ?)",
+        )?;
+        for (source, list) in documents.iter().zip(&related) {
+            for (rank, (target, score)) in list.iter().enumerate() {
+                stmt.execute(params![&source.uuid, &documents[*target].uuid, score, rank as i64 + 1])?;
+            }
+        }
+    }
+    tx.commit()?;
+
+    Ok(documents.len())
+}
+
+/// 从文章表读取参与计算的文章
+fn load_documents(conn: &Connection) -> Result<Vec<Document>, rusqlite::Error> {
+    let sql = format!(
+        "SELECT uuid, title, content, original_content, tags, category,
+                {} AS candidate
+         FROM passages ORDER BY id",
+        super::repositories::published_filter("", 1)
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let documents = stmt
+        .query_map(params![chrono::Utc::now()], |row| {
+            let title: String = row.get(1)?;
+            let content: String = row.get(2)?;
+            let original_content: Option<String> = row.get(3)?;
+            let tags: String = row.get(4)?;
+            Ok(Document {
+                uuid: row.get(0)?,
+                text: format!("{}\n{}", title, original_content.unwrap_or(content)),
+                tags: serde_json::from_str(&tags).unwrap_or_default(),
+                category: row.get(5)?,
+                candidate: row.get(6)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(documents)
+}
+
+/// 计算每篇文章的相关文章（按得分降序，最多 `limit` 篇），返回 (文章下标, 得分)
+///
+/// 得分为正文 TF-IDF 余弦相似度、标签 Jaccard 系数和是否同分类的加权和
+pub fn compute(documents: &[Document], limit: usize) -> Vec<Vec<(usize, f64)>> {
+    let vectors = tfidf_vectors(documents);
+
+    // 倒排表：词项 -> (可推荐文章下标, 权重)，只需累加有共同词项的文章
+    let mut postings: HashMap<&str, Vec<(usize, f64)>> = HashMap::new();
+    for (index, vector) in vectors.iter().enumerate() {
+        if !documents[index].candidate {
+            continue;
+        }
+        for (term, weight) in vector {
+            postings.entry(term.as_str()).or_default().push((index, *weight));
+        }
+    }
+
+    // 标签不区分大小写
+    let tag_sets: Vec<HashSet<String>> = documents
+        .iter()
+        .map(|doc| doc.tags.iter().map(|tag| tag.trim().to_lowercase()).collect())
+        .collect();
+
+    documents
+        .iter()
+        .enumerate()
+        .map(|(source, doc)| {
+            let mut scores: HashMap<usize, f64> = HashMap::new();
+
+            for (term, weight) in &vectors[source] {
+                for (target, target_weight) in postings.get(term.as_str()).into_iter().flatten() {
+                    *scores.entry(*target).or_default() += TEXT_WEIGHT * weight * target_weight;
+                }
+            }
+
+            for (target, other) in documents.iter().enumerate() {
+                if target == source || !other.candidate {
+                    continue;
+                }
+                let shared_tags = tag_sets[source].intersection(&tag_sets[target]).count();
+                if shared_tags > 0 {
+                    let union = tag_sets[source].union(&tag_sets[target]).count();
+                    *scores.entry(target).or_default() += TAG_WEIGHT * shared_tags as f64 / union as f64;
+                }
+                if !doc.category.is_empty() && doc.category == other.category {
+                    *scores.entry(target).or_default() += CATEGORY_WEIGHT;
+                }
+            }
+
+            scores.remove(&source);
+            let mut ranked: Vec<(usize, f64)> = scores.into_iter().filter(|(_, score)| *score > 0.0).collect();
+            // 得分相同时按文章顺序排列，保证结果稳定
+            ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+            ranked.truncate(limit);
+            ranked
+        })
+        .collect()
+}
+
+/// 计算每篇文章归一化后的 TF-IDF 向量
+fn tfidf_vectors(documents: &[Document]) -> Vec<HashMap<String, f64>> {
+    let term_counts: Vec<HashMap<String, usize>> = documents
+        .iter()
+        .map(|doc| {
+            let mut counts = HashMap::new();
+            for term in terms(&doc.text) {
+                *counts.entry(term).or_default() += 1;
+            }
+            counts
+        })
+        .collect();
+
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+    for counts in &term_counts {
+        for term in counts.keys() {
+            *document_frequency.entry(term.as_str()).or_default() += 1;
+        }
+    }
+
+    let total = documents.len() as f64;
+    term_counts
+        .iter()
+        .map(|counts| {
+            let mut vector: HashMap<String, f64> = counts
+                .iter()
+                .map(|(term, count)| {
+                    let df = document_frequency[term.as_str()] as f64;
+                    let idf = ((total + 1.0) / (df + 1.0)).ln() + 1.0;
+                    (term.clone(), (1.0 + (*count as f64).ln()) * idf)
+                })
+                .collect();
+            let norm = vector.values().map(|w| w * w).sum::<f64>().sqrt();
+            if norm > 0.0 {
+                vector.values_mut().for_each(|w| *w /= norm);
+            }
+            vector
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(uuid: &str, text: &str, tags: &[&str], category: &str, candidate: bool) -> Document {
+        Document {
+            uuid: uuid.to_string(),
+            text: text.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            category: category.to_string(),
+            candidate,
+        }
+    }
+
+    #[test]
+    fn test_compute_ranks_similar_passages_first() {
+        let documents = vec![
+            doc("a", "Rust 异步编程入门，介绍 tokio 运行时", &["rust", "async"], "技术", true),
+            doc("b", "深入理解 tokio 运行时与 Rust 异步任务调度", &["rust"], "技术", true),
+            doc("c", "周末去爬山，山顶的风景很美", &["生活"], "随笔", true),
+            doc("d", "Rust 异步编程草稿", &["rust", "async"], "技术", false),
+        ];
+        let related = compute(&documents, 10);
+
+        // 相似的文章排在前面，草稿不会出现在推荐结果中，也不会推荐自己
+        assert_eq!(related[0].first().map(|(i, _)| *i), Some(1));
+        assert!(related[0].iter().all(|(i, _)| *i != 0 && *i != 3));
+        assert!(related[0].iter().all(|(i, _)| *i != 2));
+        // 草稿本身仍然可以得到推荐
+        assert_eq!(related[3].first().map(|(i, _)| *i), Some(0));
+    }
+
+    #[test]
+    fn test_compute_respects_limit() {
+        let documents: Vec<Document> = (0..5)
+            .map(|i| doc(&i.to_string(), "同一个分类下的文章", &[], "技术", true))
+            .collect();
+        let related = compute(&documents, 2);
+        assert!(related.iter().all(|list| list.len() == 2));
+    }
+}
@@ -35,6 +35,143 @@ pub fn create_repository(pool: Pool<SqliteConnectionManager>) -> Arc<dyn Reposit
     Arc::new(PassageRepository::new(Arc::new(pool)))
 }
 
+/// 从关联文章中移除标签，返回 tags 发生变化的文章 ID，标签已不存在时忽略
+fn remove_tag_from_passages(conn: &rusqlite::Connection, tag_id: i64) -> Result<Vec<i64>, rusqlite::Error> {
+    match super::passage_tags::rewrite_tag(conn, tag_id, None) {
+        Ok(changed) => Ok(changed),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// 前台文章列表的筛选条件
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PublishedFilter<'a> {
+    /// 标签名，通过文章-标签关联表查找
+    pub tag: Option<&'a str>,
+    /// 分类名
+    pub category: Option<&'a str>,
+    /// 是否包含子分类中的文章
+    pub recursive: bool,
+    /// 作者用户名
+    pub author: Option<&'a str>,
+}
+
+impl PublishedFilter<'_> {
+    /// 生成筛选条件，从 `?first` 开始依次绑定标签、分类、是否包含子分类和作者，对应值为 NULL 时不筛选
+    ///
+    /// 与 [`published_filter`] 一起使用，前台列表只包含公开文章，私密和需要密码的文章只能通过链接访问
+    fn sql(first: usize) -> String {
+        format!(
+            "(?{tag} IS NULL OR id IN (SELECT pt.passage_id FROM passage_tags pt JOIN tags t ON t.id = pt.tag_id WHERE t.name = ?{tag}))
+             AND (?{category} IS NULL OR category = ?{category} OR (?{recursive} AND category IN ({subtree})))
+             AND (?{author} IS NULL OR author_id = (SELECT id FROM users WHERE username = ?{author}))",
+            tag = first,
+            category = first + 1,
+            recursive = first + 2,
+            author = first + 3,
+            subtree = super::category_tree::subtree_names_sql(first + 1)
+        )
+    }
+}
+
+/// 管理后台文章列表的排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PassageSort {
+    /// 按创建时间，新的在前
+    #[default]
+    Newest,
+    /// 按字数，长的在前
+    Longest,
+    /// 按字数，短的在前
+    Shortest,
+}
+
+impl PassageSort {
+    /// 解析查询参数：newest、longest、shortest，无法识别时按创建时间排序
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "longest" => PassageSort::Longest,
+            "shortest" => PassageSort::Shortest,
+            _ => PassageSort::Newest,
+        }
+    }
+
+    fn order_by(self) -> &'static str {
+        match self {
+            PassageSort::Newest => "created_at DESC",
+            PassageSort::Longest => "word_count DESC, created_at DESC",
+            PassageSort::Shortest => "word_count ASC, created_at DESC",
+        }
+    }
+}
+
+/// 管理后台文章列表的筛选条件
+#[derive(Debug, Clone, Default)]
+pub struct PassageListFilter {
+    pub min_words: Option<i64>,
+    pub max_words: Option<i64>,
+    pub sort: PassageSort,
+    /// 只返回该用户的文章（非管理员作者只能看到自己的文章）
+    pub author_id: Option<i64>,
+}
+
+/// 前台可见文章的判定条件：公开、状态为已发布、定时发布时间已到、未到下线时间
+///
+/// `alias` 为 passages 表在查询中的别名（没有别名时传空串），`?{now}` 绑定当前时间
+pub fn published_filter(alias: &str, now: usize) -> String {
+    let p = if alias.is_empty() { String::new() } else { format!("{}.", alias) };
+    format!(
+        "{p}visibility = 'public' AND {p}status = 'published'
+         AND ({p}is_scheduled = 0 OR {p}published_at IS NULL OR {p}published_at <= ?{now})
+         AND ({p}expires_at IS NULL OR {p}expires_at > ?{now})",
+        p = p,
+        now = now,
+    )
+}
+
+/// slug 是否可被指定文章使用
+fn is_slug_available_with(conn: &rusqlite::Connection, slug: &str, uuid: Option<&str>) -> Result<bool, rusqlite::Error> {
+    let taken: i64 = conn.query_row(
+        "SELECT (SELECT COUNT(*) FROM passages WHERE slug = ?1 AND uuid IS NOT ?2)
+              + (SELECT COUNT(*) FROM passage_slug_redirects WHERE slug = ?1 AND passage_uuid IS NOT ?2)",
+        params![slug, uuid],
+        |row| row.get(0),
+    )?;
+    Ok(taken == 0)
+}
+
+/// 生成未被占用的 slug；`base` 无法生成 slug 时（例如只有标点）使用 `passage`
+pub(crate) fn unique_slug_with(conn: &rusqlite::Connection, base: &str, uuid: Option<&str>) -> Result<String, rusqlite::Error> {
+    let base = crate::slug::normalize(base).unwrap_or_else(|| "passage".to_string());
+    let mut candidate = base.clone();
+    let mut n = 2;
+    while !is_slug_available_with(conn, &candidate, uuid)? {
+        candidate = format!("{}-{}", base, n);
+        n += 1;
+    }
+    Ok(candidate)
+}
+
+/// 记录 slug 变化：旧 slug 保留为跳转，新 slug 如果曾是本文章的旧 slug 则移除该跳转
+pub(crate) fn record_slug_change(conn: &rusqlite::Connection, uuid: &str, old_slug: Option<&str>, new_slug: Option<&str>) -> Result<(), rusqlite::Error> {
+    let (Some(old_slug), Some(new_slug)) = (old_slug, new_slug) else {
+        return Ok(());
+    };
+    if old_slug == new_slug {
+        return Ok(());
+    }
+    conn.execute(
+        "INSERT OR REPLACE INTO passage_slug_redirects (slug, passage_uuid, created_at) VALUES (?, ?, ?)",
+        params![old_slug, uuid, chrono::Utc::now()],
+    )?;
+    conn.execute(
+        "DELETE FROM passage_slug_redirects WHERE slug = ? AND passage_uuid = ?",
+        params![new_slug, uuid],
+    )?;
+    Ok(())
+}
+
 /// 文章仓库
 pub struct PassageRepository {
     pool: Arc<Pool<SqliteConnectionManager>>,
@@ -55,13 +192,28 @@ impl PassageRepository {
     /// 创建文章
     pub async fn create(&self, passage: &Passage) -> Result<i64, Box<dyn std::error::Error>> {
         let conn = self.pool.get()?;
-        
+        let (id, _) = Self::insert_with(&conn, passage)?;
+        crate::cache::invalidate_passages();
+        Ok(id)
+    }
+
+    /// 在指定连接（可以是事务）上插入文章，同步标签关联和全文索引，返回 (ID, UUID)
+    pub(crate) fn insert_with(conn: &rusqlite::Connection, passage: &Passage) -> Result<(i64, String), Box<dyn std::error::Error>> {
         // 生成 Flake UUID（使用基于主机名的唯一 machine ID）
         let uuid = crate::id_generator::generate_unique_id();
         
-        let _ = conn.execute(
-            "INSERT INTO passages (uuid, title, content, original_content, summary, author, tags, category, status, file_path, visibility, is_scheduled, published_at, cover_image, created_at, updated_at) 
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        // 未指定 slug 时根据标题生成
+        let slug = match &passage.slug {
+            Some(slug) => slug.clone(),
+            None => unique_slug_with(conn, &passage.title, Some(&uuid))?,
+        };
+        
+        // 字数和阅读时间根据渲染后的内容计算
+        let reading = crate::reading::stats(&passage.content);
+        
+        conn.execute(
+            "INSERT INTO passages (uuid, title, content, original_content, summary, author, tags, category, status, file_path, visibility, is_scheduled, published_at, cover_image, created_at, updated_at, expires_at, slug, word_count, reading_minutes, author_id, pinned, featured, pin_order) 
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
             params![
                 &uuid,
                 &passage.title,
@@ -79,16 +231,30 @@ impl PassageRepository {
                 &passage.cover_image,
                 &passage.created_at,
                 &passage.updated_at,
+                &passage.expires_at,
+                &slug,
+                reading.word_count,
+                reading.reading_minutes,
+                &passage.author_id,
+                &passage.pinned,
+                &passage.featured,
+                &passage.pin_order,
             ],
         )?;
-        Ok(conn.last_insert_rowid())
+        let id = conn.last_insert_rowid();
+        super::passage_tags::set_passage_tags(conn, id, &passage.tags)?;
+
+        // 同步全文索引
+        super::fts::index_passage(conn, &uuid, passage)?;
+
+        Ok((id, uuid))
     }
 
     /// 根据 ID 获取文章
     pub async fn get_by_id(&self, id: i64) -> Result<Passage, Box<dyn std::error::Error>> {
         let conn = self.pool.get()?;
         let mut stmt = conn.prepare(
-            "SELECT id, uuid, title, content, original_content, summary, author, tags, category, status, file_path, visibility, is_scheduled, published_at, cover_image, created_at, updated_at 
+            "SELECT id, uuid, title, content, original_content, summary, author, tags, category, status, file_path, visibility, is_scheduled, published_at, cover_image, created_at, updated_at, expires_at, slug, word_count, reading_minutes, author_id, pinned, featured, pin_order 
              FROM passages WHERE id = ?"
         )?;
         
@@ -111,6 +277,14 @@ impl PassageRepository {
                 cover_image: row.get(14)?,
                 created_at: row.get(15)?,
                 updated_at: row.get(16)?,
+                expires_at: row.get(17)?,
+                slug: row.get(18)?,
+                word_count: row.get(19)?,
+                reading_minutes: row.get(20)?,
+                author_id: row.get(21)?,
+                pinned: row.get(22)?,
+                featured: row.get(23)?,
+                pin_order: row.get(24)?,
             })
         })?;
         
@@ -121,7 +295,7 @@ impl PassageRepository {
     pub async fn get_by_uuid(&self, uuid: &str) -> Result<Passage, Box<dyn std::error::Error>> {
         let conn = self.pool.get()?;
         let mut stmt = conn.prepare(
-            "SELECT id, uuid, title, content, original_content, summary, author, tags, category, status, file_path, visibility, is_scheduled, published_at, cover_image, created_at, updated_at 
+            "SELECT id, uuid, title, content, original_content, summary, author, tags, category, status, file_path, visibility, is_scheduled, published_at, cover_image, created_at, updated_at, expires_at, slug, word_count, reading_minutes, author_id, pinned, featured, pin_order 
              FROM passages WHERE uuid = ?"
         )?;
         
@@ -144,6 +318,14 @@ impl PassageRepository {
                 cover_image: row.get(14)?,
                 created_at: row.get(15)?,
                 updated_at: row.get(16)?,
+                expires_at: row.get(17)?,
+                slug: row.get(18)?,
+                word_count: row.get(19)?,
+                reading_minutes: row.get(20)?,
+                author_id: row.get(21)?,
+                pinned: row.get(22)?,
+                featured: row.get(23)?,
+                pin_order: row.get(24)?,
             })
         })?;
         
@@ -154,7 +336,7 @@ impl PassageRepository {
     pub async fn get_by_file_path(&self, file_path: &str) -> Result<Passage, Box<dyn std::error::Error>> {
         let conn = self.pool.get()?;
         let mut stmt = conn.prepare(
-            "SELECT id, uuid, title, content, original_content, summary, author, tags, category, status, file_path, visibility, is_scheduled, published_at, cover_image, created_at, updated_at 
+            "SELECT id, uuid, title, content, original_content, summary, author, tags, category, status, file_path, visibility, is_scheduled, published_at, cover_image, created_at, updated_at, expires_at, slug, word_count, reading_minutes, author_id, pinned, featured, pin_order 
              FROM passages WHERE file_path = ?"
         )?;
         
@@ -177,21 +359,30 @@ impl PassageRepository {
                 cover_image: row.get(14)?,
                 created_at: row.get(15)?,
                 updated_at: row.get(16)?,
+                expires_at: row.get(17)?,
+                slug: row.get(18)?,
+                word_count: row.get(19)?,
+                reading_minutes: row.get(20)?,
+                author_id: row.get(21)?,
+                pinned: row.get(22)?,
+                featured: row.get(23)?,
+                pin_order: row.get(24)?,
             })
         })?;
         
         Ok(passage)
     }
 
-    /// 获取所有文章
-    pub async fn get_all(&self, limit: i64, offset: i64) -> Result<Vec<Passage>, Box<dyn std::error::Error>> {
+    /// 按字数筛选、排序获取所有文章（管理后台列表）
+    pub async fn get_all_filtered(&self, filter: &PassageListFilter, limit: i64, offset: i64) -> Result<Vec<Passage>, Box<dyn std::error::Error>> {
         let conn = self.pool.get()?;
-        let mut stmt = conn.prepare(
-            "SELECT id, uuid, title, content, original_content, summary, author, tags, category, status, file_path, visibility, is_scheduled, published_at, cover_image, created_at, updated_at 
-             FROM passages ORDER BY created_at DESC LIMIT ? OFFSET ?"
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, uuid, title, content, original_content, summary, author, tags, category, status, file_path, visibility, is_scheduled, published_at, cover_image, created_at, updated_at, expires_at, slug, word_count, reading_minutes, author_id, pinned, featured, pin_order 
+             FROM passages WHERE word_count >= ?1 AND word_count <= ?2 AND (?5 IS NULL OR author_id = ?5) ORDER BY {} LIMIT ?3 OFFSET ?4",
+            filter.sort.order_by()
+        ))?;
         
-        let passages = stmt.query_map(params![limit, offset], |row| {
+        let passages = stmt.query_map(params![filter.min_words.unwrap_or(0), filter.max_words.unwrap_or(i64::MAX), limit, offset, filter.author_id], |row| {
             Ok(Passage {
                 id: Some(row.get(0)?),
                 uuid: Some(row.get(1)?),
@@ -210,21 +401,67 @@ impl PassageRepository {
                 cover_image: row.get(14)?,
                 created_at: row.get(15)?,
                 updated_at: row.get(16)?,
+                expires_at: row.get(17)?,
+                slug: row.get(18)?,
+                word_count: row.get(19)?,
+                reading_minutes: row.get(20)?,
+                author_id: row.get(21)?,
+                pinned: row.get(22)?,
+                featured: row.get(23)?,
+                pin_order: row.get(24)?,
             })
         })?.collect::<Result<Vec<_>, _>>()?;
         
         Ok(passages)
     }
 
-    /// 获取已发布的文章
+    /// 获取首页文章列表（只包含公开文章，定时发布未到期、已过期的不包含在内）
+    ///
+    /// 置顶文章按手动顺序排在最前，因此只出现在第一页，其余文章按创建时间排序
     pub async fn get_published(&self, limit: i64, offset: i64) -> Result<Vec<Passage>, Box<dyn std::error::Error>> {
+        self.query_published(&PublishedFilter::default(), "1", "pinned DESC, pin_order ASC, created_at DESC", limit, offset)
+    }
+
+    /// 按标签、分类筛选已发布的公开文章（按创建时间排序）
+    pub async fn get_published_filtered(&self, filter: &PublishedFilter<'_>, limit: i64, offset: i64) -> Result<Vec<Passage>, Box<dyn std::error::Error>> {
+        self.query_published(filter, "1", "created_at DESC", limit, offset)
+    }
+
+    /// 获取首页头图区的推荐文章（已发布且公开，按手动顺序排列）
+    pub async fn get_featured(&self, limit: i64) -> Result<Vec<Passage>, Box<dyn std::error::Error>> {
+        self.query_published(&PublishedFilter::default(), "featured = 1", "pin_order ASC, created_at DESC", limit, 0)
+    }
+
+    /// 调整置顶、推荐文章的顺序：按 `ids` 中的位置写入 pin_order
+    pub async fn reorder_pinned(&self, ids: &[i64]) -> Result<(), Box<dyn std::error::Error>> {
         let conn = self.pool.get()?;
-        let mut stmt = conn.prepare(
-            "SELECT id, uuid, title, content, original_content, summary, author, tags, category, status, file_path, visibility, is_scheduled, published_at, cover_image, created_at, updated_at 
-             FROM passages WHERE status = 'published' ORDER BY created_at DESC LIMIT ? OFFSET ?"
-        )?;
+        let tx = conn.unchecked_transaction()?;
+        for (position, id) in ids.iter().enumerate() {
+            let updated = tx.execute(
+                "UPDATE passages SET pin_order = ? WHERE id = ? AND (pinned = 1 OR featured = 1)",
+                params![position as i64, id],
+            )?;
+            if updated == 0 {
+                return Err(format!("文章 {} 不存在或未置顶、推荐", id).into());
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 查询已发布的文章，`condition` 为额外的筛选条件
+    fn query_published(&self, filter: &PublishedFilter<'_>, condition: &str, order_by: &str, limit: i64, offset: i64) -> Result<Vec<Passage>, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, uuid, title, content, original_content, summary, author, tags, category, status, file_path, visibility, is_scheduled, published_at, cover_image, created_at, updated_at, expires_at, slug, word_count, reading_minutes, author_id, pinned, featured, pin_order 
+             FROM passages WHERE {} AND {} AND {} ORDER BY {} LIMIT ?2 OFFSET ?3",
+            published_filter("", 1),
+            PublishedFilter::sql(4),
+            condition,
+            order_by
+        ))?;
         
-        let passages = stmt.query_map(params![limit, offset], |row| {
+        let passages = stmt.query_map(params![chrono::Utc::now(), limit, offset, filter.tag, filter.category, filter.recursive, filter.author], |row| {
             Ok(Passage {
                 id: Some(row.get(0)?),
                 uuid: Some(row.get(1)?),
@@ -243,18 +480,86 @@ impl PassageRepository {
                 cover_image: row.get(14)?,
                 created_at: row.get(15)?,
                 updated_at: row.get(16)?,
+                expires_at: row.get(17)?,
+                slug: row.get(18)?,
+                word_count: row.get(19)?,
+                reading_minutes: row.get(20)?,
+                author_id: row.get(21)?,
+                pinned: row.get(22)?,
+                featured: row.get(23)?,
+                pin_order: row.get(24)?,
             })
         })?.collect::<Result<Vec<_>, _>>()?;
         
         Ok(passages)
     }
 
+    /// 获取访客可见的全部文章（已发布且公开），用于订阅源、站点地图等
+    pub async fn get_all_public(&self) -> Result<Vec<Passage>, Box<dyn std::error::Error>> {
+        self.get_all_public_filtered(&PublishedFilter::default()).await
+    }
+
+    /// 按标签、分类筛选访客可见的全部文章
+    pub async fn get_all_public_filtered(&self, filter: &PublishedFilter<'_>) -> Result<Vec<Passage>, Box<dyn std::error::Error>> {
+        let total = self.count_published_filtered(filter).await?;
+        self.get_published_filtered(filter, total, 0).await
+    }
+
+    /// 获取预先计算好的相关文章（按得分排序，只返回当前仍然公开可见的文章）
+    pub async fn get_related(&self, uuid: &str, limit: i64) -> Result<Vec<(Passage, f64)>, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, uuid, title, content, original_content, summary, author, tags, category, status, file_path, visibility, is_scheduled, published_at, cover_image, created_at, updated_at, expires_at, slug, word_count, reading_minutes, author_id, pinned, featured, pin_order, r.score 
+             FROM passage_related r JOIN passages ON passages.uuid = r.related_uuid
+             WHERE r.passage_uuid = ?2 AND {} ORDER BY r.rank LIMIT ?3",
+            published_filter("passages", 1)
+        ))?;
+        
+        let passages = stmt.query_map(params![chrono::Utc::now(), uuid, limit], |row| {
+            Ok((Passage {
+                id: Some(row.get(0)?),
+                uuid: Some(row.get(1)?),
+                title: row.get(2)?,
+                content: row.get(3)?,
+                original_content: row.get(4)?,
+                summary: row.get(5)?,
+                author: row.get(6)?,
+                tags: row.get(7)?,
+                category: row.get(8)?,
+                status: row.get(9)?,
+                file_path: row.get(10)?,
+                visibility: row.get(11)?,
+                is_scheduled: row.get(12)?,
+                published_at: row.get(13)?,
+                cover_image: row.get(14)?,
+                created_at: row.get(15)?,
+                updated_at: row.get(16)?,
+                expires_at: row.get(17)?,
+                slug: row.get(18)?,
+                word_count: row.get(19)?,
+                reading_minutes: row.get(20)?,
+                author_id: row.get(21)?,
+                pinned: row.get(22)?,
+                featured: row.get(23)?,
+                pin_order: row.get(24)?,
+            }, row.get(25)?))
+        })?.collect::<Result<Vec<_>, _>>()?;
+        
+        Ok(passages)
+    }
+
     /// 更新文章
     pub async fn update(&self, passage: &Passage) -> Result<(), Box<dyn std::error::Error>> {
         let id = passage.id.ok_or("文章 ID 不能为空")?;
         let conn = self.pool.get()?;
+        let (uuid, old_slug): (String, Option<String>) = conn.query_row(
+            "SELECT uuid, slug FROM passages WHERE id = ?",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let reading = crate::reading::stats(&passage.content);
         conn.execute(
-            "UPDATE passages SET title = ?, content = ?, original_content = ?, summary = ?, author = ?, tags = ?, category = ?, status = ?, file_path = ?, visibility = ?, is_scheduled = ?, published_at = ?, cover_image = ?, updated_at = ? 
+            "UPDATE passages SET title = ?, content = ?, original_content = ?, summary = ?, author = ?, tags = ?, category = ?, status = ?, file_path = ?, visibility = ?, is_scheduled = ?, published_at = ?, cover_image = ?, updated_at = ?, expires_at = ?, slug = COALESCE(?, slug), word_count = ?, reading_minutes = ?, author_id = ?, pinned = ?, featured = ?, pin_order = ? 
              WHERE id = ?",
             params![
                 &passage.title,
@@ -271,9 +576,40 @@ impl PassageRepository {
                 &passage.published_at,
                 &passage.cover_image,
                 &passage.updated_at,
+                &passage.expires_at,
+                &passage.slug,
+                reading.word_count,
+                reading.reading_minutes,
+                &passage.author_id,
+                &passage.pinned,
+                &passage.featured,
+                &passage.pin_order,
                 id,
             ],
         )?;
+
+        // slug 变化时保留旧 slug 作为跳转
+        record_slug_change(&conn, &uuid, old_slug.as_deref(), passage.slug.as_deref())?;
+        super::passage_tags::set_passage_tags(&conn, id, &passage.tags)?;
+
+        // 同步全文索引
+        super::fts::index_passage(&conn, &uuid, passage)?;
+        crate::cache::invalidate_passages();
+
+        Ok(())
+    }
+
+    /// 获取文章访问密码的哈希（未设置时返回 None）
+    pub async fn get_password_hash(&self, id: i64) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let hash = conn.query_row("SELECT password_hash FROM passages WHERE id = ?", params![id], |row| row.get(0))?;
+        Ok(hash)
+    }
+
+    /// 设置或清除文章访问密码的哈希
+    pub async fn set_password_hash(&self, id: i64, hash: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        conn.execute("UPDATE passages SET password_hash = ? WHERE id = ?", params![hash, id])?;
         Ok(())
     }
 
@@ -281,6 +617,9 @@ impl PassageRepository {
     pub async fn delete_by_uuid(&self, uuid: &str) -> Result<(), Box<dyn std::error::Error>> {
         let conn = self.pool.get()?;
         conn.execute("DELETE FROM passages WHERE uuid = ?", params![uuid])?;
+        super::fts::remove_passage(&conn, uuid)?;
+        super::passage_tags::remove_orphans(&conn)?;
+        crate::cache::invalidate_passages();
         Ok(())
     }
 
@@ -294,6 +633,9 @@ impl PassageRepository {
         let sql = format!("DELETE FROM passages WHERE id IN ({})", placeholders);
         let params: Vec<&dyn rusqlite::ToSql> = ids.iter().map(|id| id as &dyn rusqlite::ToSql).collect();
         let affected = conn.execute(&sql, params.as_slice())?;
+        super::fts::remove_orphans(&conn)?;
+        super::passage_tags::remove_orphans(&conn)?;
+        crate::cache::invalidate_passages();
         Ok(affected as i64)
     }
 
@@ -304,10 +646,22 @@ impl PassageRepository {
         Ok(count)
     }
 
-    /// 获取已发布文章总数
-    pub async fn count_published(&self) -> Result<i64, Box<dyn std::error::Error>> {
+    /// 获取符合字数筛选条件的文章数
+    pub async fn count_filtered(&self, filter: &PassageListFilter) -> Result<i64, Box<dyn std::error::Error>> {
         let conn = self.pool.get()?;
-        let count: i64 = conn.query_row("SELECT COUNT(*) FROM passages WHERE status = 'published'", [], |row| row.get(0))?;
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM passages WHERE word_count >= ?1 AND word_count <= ?2 AND (?3 IS NULL OR author_id = ?3)",
+            params![filter.min_words.unwrap_or(0), filter.max_words.unwrap_or(i64::MAX), filter.author_id],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    /// 获取已发布的公开文章总数（与 get_published_filtered 规则一致）
+    pub async fn count_published_filtered(&self, filter: &PublishedFilter<'_>) -> Result<i64, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let sql = format!("SELECT COUNT(*) FROM passages WHERE {} AND {}", published_filter("", 1), PublishedFilter::sql(2));
+        let count: i64 = conn.query_row(&sql, params![chrono::Utc::now(), filter.tag, filter.category, filter.recursive, filter.author], |row| row.get(0))?;
         Ok(count)
     }
 
@@ -318,6 +672,88 @@ impl PassageRepository {
         let categories = stmt.query_map([], |row| row.get(0))?.collect::<Result<Vec<_>, _>>()?;
         Ok(categories)
     }
+
+    /// 根据 slug 获取文章
+    pub async fn get_by_slug(&self, slug: &str) -> Result<Passage, Box<dyn std::error::Error>> {
+        let uuid: String = {
+            let conn = self.pool.get()?;
+            conn.query_row("SELECT uuid FROM passages WHERE slug = ?", params![slug], |row| row.get(0))?
+        };
+        self.get_by_uuid(&uuid).await
+    }
+
+    /// 根据旧 slug 查找跳转目标文章的 UUID
+    pub async fn get_uuid_by_old_slug(&self, slug: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let uuid = conn.query_row(
+            "SELECT passage_uuid FROM passage_slug_redirects WHERE slug = ?",
+            params![slug],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(uuid)
+    }
+
+    /// 文章作者是否可信（管理员），查询失败时按不可信处理
+    pub async fn is_trusted_author(&self, author_id: Option<i64>) -> bool {
+        match self.pool.get() {
+            Ok(conn) => super::authors::is_trusted(&conn, author_id).unwrap_or(false),
+            Err(_) => false,
+        }
+    }
+
+    /// 根据标题或用户输入生成未被占用的 slug（已占用时追加 -2、-3…）
+    pub async fn unique_slug(&self, base: &str, uuid: Option<&str>) -> Result<String, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        Ok(unique_slug_with(&conn, base, uuid)?)
+    }
+
+    /// slug 是否可被指定文章使用（未被其他文章占用，也不是其他文章的旧 slug）
+    pub async fn is_slug_available(&self, slug: &str, uuid: Option<&str>) -> Result<bool, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        Ok(is_slug_available_with(&conn, slug, uuid)?)
+    }
+
+    /// 获取发布时间已到、仍处于定时状态的文章 UUID
+    pub async fn get_due_for_publish(&self, now: chrono::DateTime<chrono::Utc>) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT uuid FROM passages 
+             WHERE is_scheduled = 1 AND published_at IS NOT NULL AND published_at <= ? 
+             ORDER BY published_at"
+        )?;
+        let uuids = stmt.query_map(params![now], |row| row.get(0))?.collect::<Result<Vec<_>, _>>()?;
+        Ok(uuids)
+    }
+
+    /// 获取下线时间已到、仍处于发布状态的文章 UUID
+    pub async fn get_due_for_expiry(&self, now: chrono::DateTime<chrono::Utc>) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT uuid FROM passages 
+             WHERE status = 'published' AND expires_at IS NOT NULL AND expires_at <= ? 
+             ORDER BY expires_at"
+        )?;
+        let uuids = stmt.query_map(params![now], |row| row.get(0))?.collect::<Result<Vec<_>, _>>()?;
+        Ok(uuids)
+    }
+
+    /// 记录定时发布/下线的状态变化
+    pub async fn log_schedule_transition(
+        &self,
+        passage_uuid: &str,
+        action: &str,
+        from_status: &str,
+        to_status: &str,
+        scheduled_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        conn.execute(
+            "INSERT INTO passage_schedule_log (passage_uuid, action, from_status, to_status, scheduled_at, created_at) 
+             VALUES (?, ?, ?, ?, ?, ?)",
+            params![passage_uuid, action, from_status, to_status, scheduled_at, chrono::Utc::now()],
+        )?;
+        Ok(())
+    }
 }
 
 /// 评论仓库
@@ -333,6 +769,12 @@ impl CommentRepository {
     /// 创建评论
     pub async fn create(&self, comment: &Comment) -> Result<(), Box<dyn std::error::Error>> {
         let conn = self.pool.get()?;
+        Self::insert_with(&conn, comment)?;
+        Ok(())
+    }
+
+    /// 在指定连接（可以是事务）上插入评论
+    pub(crate) fn insert_with(conn: &rusqlite::Connection, comment: &Comment) -> Result<(), rusqlite::Error> {
         conn.execute(
             "INSERT INTO comments (username, content, passage_uuid, created_at) VALUES (?, ?, ?, ?)",
             params![
@@ -654,13 +1096,19 @@ impl SettingRepository {
 
     /// 设置值
     pub fn set(conn: &rusqlite::Connection, setting: &Setting) -> Result<(), Box<dyn std::error::Error>> {
+        // 自定义 CSS 会直接输出到页面，写入前先清理
+        let value = if setting.key.ends_with("custom_css") {
+            crate::sanitize::sanitize_css(&setting.value)
+        } else {
+            setting.value.clone()
+        };
         // 使用 query_row 执行 INSERT OR REPLACE，因为它可能返回结果
         let _ = conn.query_row(
             "INSERT OR REPLACE INTO settings (key, value, type, description, category, created_at, updated_at) 
              VALUES (?, ?, ?, ?, ?, ?, ?) RETURNING 1",
             params![
                 &setting.key,
-                &setting.value,
+                &value,
                 &setting.r#type,
                 &setting.description,
                 &setting.category,
@@ -687,8 +1135,8 @@ impl CategoryRepository {
     pub async fn create(&self, category: &Category) -> Result<i64, Box<dyn std::error::Error>> {
         let conn = self.pool.get()?;
         conn.execute(
-            "INSERT INTO categories (name, description, icon, sort_order, is_enabled, created_at, updated_at) 
-             VALUES (?, ?, ?, ?, ?, ?, ?)",
+            "INSERT INTO categories (name, description, icon, sort_order, is_enabled, created_at, updated_at, parent_id) 
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
             params![
                 &category.name,
                 &category.description,
@@ -697,6 +1145,7 @@ impl CategoryRepository {
                 &category.is_enabled,
                 &category.created_at,
                 &category.updated_at,
+                &category.parent_id,
             ],
         )?;
         Ok(conn.last_insert_rowid())
@@ -706,13 +1155,14 @@ impl CategoryRepository {
     pub async fn get_by_id(&self, id: i64) -> Result<Category, Box<dyn std::error::Error>> {
         let conn = self.pool.get()?;
         let mut stmt = conn.prepare(
-            "SELECT id, name, description, icon, sort_order, is_enabled, created_at, updated_at
+            "SELECT id, name, description, icon, sort_order, is_enabled, created_at, updated_at, parent_id
              FROM categories WHERE id = ?"
         )?;
 
         let category = stmt.query_row(params![id], |row| {
             Ok(Category {
                 id: Some(row.get(0)?),
+                parent_id: row.get(8)?,
                 name: row.get(1)?,
                 description: row.get(2)?,
                 icon: row.get(3)?,
@@ -730,13 +1180,14 @@ impl CategoryRepository {
     pub async fn get_by_name(&self, name: &str) -> Result<Category, Box<dyn std::error::Error>> {
         let conn = self.pool.get()?;
         let mut stmt = conn.prepare(
-            "SELECT id, name, description, icon, sort_order, is_enabled, created_at, updated_at
+            "SELECT id, name, description, icon, sort_order, is_enabled, created_at, updated_at, parent_id
              FROM categories WHERE name = ?"
         )?;
 
         let category = stmt.query_row(params![name], |row| {
             Ok(Category {
                 id: Some(row.get(0)?),
+                parent_id: row.get(8)?,
                 name: row.get(1)?,
                 description: row.get(2)?,
                 icon: row.get(3)?,
@@ -754,13 +1205,14 @@ impl CategoryRepository {
     pub async fn get_all(&self, limit: i64, offset: i64) -> Result<Vec<Category>, Box<dyn std::error::Error>> {
         let conn = self.pool.get()?;
         let mut stmt = conn.prepare(
-            "SELECT id, name, description, icon, sort_order, is_enabled, created_at, updated_at 
+            "SELECT id, name, description, icon, sort_order, is_enabled, created_at, updated_at, parent_id
              FROM categories ORDER BY sort_order ASC, created_at DESC LIMIT ? OFFSET ?"
         )?;
         
         let categories = stmt.query_map(params![limit, offset], |row| {
             Ok(Category {
                 id: Some(row.get(0)?),
+                parent_id: row.get(8)?,
                 name: row.get(1)?,
                 description: row.get(2)?,
                 icon: row.get(3)?,
@@ -796,10 +1248,53 @@ impl CategoryRepository {
         Ok(())
     }
 
-    /// 删除分类
+    /// 移动分类到新的父分类下（None 表示顶级），父分类是自身或子孙分类时拒绝
+    pub async fn move_to(&self, id: i64, parent_id: Option<i64>, sort_order: Option<i32>) -> Result<(), Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        if let Some(parent_id) = parent_id {
+            if super::category_tree::is_self_or_ancestor(&conn, id, parent_id)? {
+                return Err("不能将分类移动到自身或其子分类下".into());
+            }
+        }
+        conn.execute(
+            "UPDATE categories SET parent_id = ?, sort_order = COALESCE(?, sort_order), updated_at = ? WHERE id = ?",
+            params![parent_id, sort_order, chrono::Utc::now(), id],
+        )?;
+        Ok(())
+    }
+
+    /// 按给定顺序重排同一父分类下的子分类，返回更新的分类数
+    pub async fn reorder(&self, parent_id: Option<i64>, ids: &[i64]) -> Result<usize, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let tx = conn.unchecked_transaction()?;
+        let mut updated = 0;
+        for (index, id) in ids.iter().enumerate() {
+            updated += tx.execute(
+                "UPDATE categories SET sort_order = ? WHERE id = ? AND parent_id IS ?",
+                params![index as i32, id, parent_id],
+            )?;
+        }
+        if updated != ids.len() {
+            // 有分类不属于该父分类，整体放弃
+            return Err("分类不属于同一父分类".into());
+        }
+        tx.commit()?;
+        Ok(updated)
+    }
+
+    /// 获取从顶级分类到指定分类的路径（面包屑）
+    pub async fn get_path(&self, name: &str) -> Result<Vec<(i64, String)>, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        Ok(super::category_tree::path_to(&conn, name)?)
+    }
+
+    /// 删除分类（子分类移到其父分类下）
     pub async fn delete(&self, id: i64) -> Result<(), Box<dyn std::error::Error>> {
         let conn = self.pool.get()?;
-        conn.execute("DELETE FROM categories WHERE id = ?", params![id])?;
+        let tx = conn.unchecked_transaction()?;
+        super::category_tree::reparent_children(&tx, id)?;
+        tx.execute("DELETE FROM categories WHERE id = ?", params![id])?;
+        tx.commit()?;
         Ok(())
     }
 
@@ -809,10 +1304,14 @@ impl CategoryRepository {
             return Ok(0);
         }
         let conn = self.pool.get()?;
-        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
-        let sql = format!("DELETE FROM categories WHERE id IN ({})", placeholders);
-        let params: Vec<&dyn rusqlite::ToSql> = ids.iter().map(|id| id as &dyn rusqlite::ToSql).collect();
-        let affected = conn.execute(&sql, params.as_slice())?;
+        let tx = conn.unchecked_transaction()?;
+        let mut affected = 0;
+        // 逐个删除，保证每次都把子分类挂到仍然存在的父分类下
+        for id in &ids {
+            super::category_tree::reparent_children(&tx, *id)?;
+            affected += tx.execute("DELETE FROM categories WHERE id = ?", params![id])?;
+        }
+        tx.commit()?;
         Ok(affected as i64)
     }
 
@@ -929,11 +1428,13 @@ impl TagRepository {
         Ok(tags)
     }
 
-    /// 更新标签
+    /// 更新标签（改名时同步更新文章中的标签）
     pub async fn update(&self, tag: &Tag) -> Result<(), Box<dyn std::error::Error>> {
         let id = tag.id.ok_or("标签 ID 不能为空")?;
         let conn = self.pool.get()?;
-        conn.execute(
+        let tx = conn.unchecked_transaction()?;
+        let changed = super::passage_tags::rewrite_tag(&tx, id, Some(&tag.name))?;
+        tx.execute(
             "UPDATE tags SET name = ?, description = ?, color = ?, category_id = ?, sort_order = ?, is_enabled = ?, updated_at = ? 
              WHERE id = ?",
             params![
@@ -947,13 +1448,21 @@ impl TagRepository {
                 id,
             ],
         )?;
+        tx.commit()?;
+        drop(conn);
+        self.rewrite_passage_files(&changed).await;
         Ok(())
     }
 
-    /// 删除标签
+    /// 删除标签（同时从文章中移除）
     pub async fn delete(&self, id: i64) -> Result<(), Box<dyn std::error::Error>> {
         let conn = self.pool.get()?;
-        conn.execute("DELETE FROM tags WHERE id = ?", params![id])?;
+        let tx = conn.unchecked_transaction()?;
+        let changed = remove_tag_from_passages(&tx, id)?;
+        tx.execute("DELETE FROM tags WHERE id = ?", params![id])?;
+        tx.commit()?;
+        drop(conn);
+        self.rewrite_passage_files(&changed).await;
         Ok(())
     }
 
@@ -963,19 +1472,76 @@ impl TagRepository {
             return Ok(0);
         }
         let conn = self.pool.get()?;
+        let tx = conn.unchecked_transaction()?;
+        let mut changed = Vec::new();
+        for id in &ids {
+            changed.extend(remove_tag_from_passages(&tx, *id)?);
+        }
         let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
         let sql = format!("DELETE FROM tags WHERE id IN ({})", placeholders);
         let params: Vec<&dyn rusqlite::ToSql> = ids.iter().map(|id| id as &dyn rusqlite::ToSql).collect();
-        let affected = conn.execute(&sql, params.as_slice())?;
+        let affected = tx.execute(&sql, params.as_slice())?;
+        tx.commit()?;
+        drop(conn);
+        changed.sort_unstable();
+        changed.dedup();
+        self.rewrite_passage_files(&changed).await;
         Ok(affected as i64)
     }
 
+    /// 标签改名或删除后，把文章的新标签写回 Markdown 文件的 front matter（否则下次同步会还原）
+    async fn rewrite_passage_files(&self, passage_ids: &[i64]) {
+        if passage_ids.is_empty() {
+            return;
+        }
+        let passage_repo = PassageRepository::new(self.pool.clone());
+        for id in passage_ids {
+            let passage = match passage_repo.get_by_id(*id).await {
+                Ok(passage) => passage,
+                Err(e) => {
+                    eprintln!("⚠️  读取文章 {} 失败: {}", id, e);
+                    continue;
+                }
+            };
+            if let Some(ref file_path) = passage.file_path {
+                if let Err(e) = crate::handlers::api_handlers::passage::update_markdown_file(file_path, &passage) {
+                    eprintln!("⚠️  更新 Markdown 文件失败 {}: {}", file_path, e);
+                }
+            }
+        }
+        crate::cache::invalidate_passages();
+    }
+
     /// 获取标签总数
     pub async fn count(&self) -> Result<i64, Box<dyn std::error::Error>> {
         let conn = self.pool.get()?;
         let count: i64 = conn.query_row("SELECT COUNT(*) FROM tags", [], |row| row.get(0))?;
         Ok(count)
     }
+
+    /// 统计每个标签下访客可见的文章数（不含没有文章的标签），按文章数降序
+    pub async fn get_usage_counts(&self) -> Result<Vec<TagCount>, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT t.id, t.name, COUNT(*) AS passage_count
+             FROM tags t
+             JOIN passage_tags pt ON pt.tag_id = t.id
+             JOIN passages ON passages.id = pt.passage_id
+             WHERE {}
+             GROUP BY t.id ORDER BY passage_count DESC, t.name",
+            published_filter("passages", 1)
+        ))?;
+
+        let counts = stmt.query_map(params![chrono::Utc::now()], |row| {
+            Ok(TagCount {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                count: row.get(2)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(counts)
+    }
 }
 
 /// 用户仓库
@@ -1127,6 +1693,63 @@ impl UserRepository {
     }
 }
 
+/// 作者资料仓库
+pub struct AuthorRepository {
+    pool: Arc<Pool<SqliteConnectionManager>>,
+}
+
+impl AuthorRepository {
+    pub fn new(pool: Arc<Pool<SqliteConnectionManager>>) -> Self {
+        Self { pool }
+    }
+
+    fn get_where(&self, condition: &str, value: &dyn rusqlite::ToSql) -> Result<AuthorProfile, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let profile = conn.query_row(
+            &format!(
+                "SELECT u.id, u.username, COALESCE(NULLIF(ap.display_name, ''), u.username), COALESCE(ap.avatar, ''), COALESCE(ap.bio, '')
+                 FROM users u LEFT JOIN author_profiles ap ON ap.user_id = u.id WHERE {}",
+                condition
+            ),
+            params![value],
+            |row| {
+                Ok(AuthorProfile {
+                    user_id: row.get(0)?,
+                    username: row.get(1)?,
+                    display_name: row.get(2)?,
+                    avatar: row.get(3)?,
+                    bio: row.get(4)?,
+                })
+            },
+        )?;
+        Ok(profile)
+    }
+
+    /// 根据用户 ID 获取作者资料（未填写资料时显示名为用户名）
+    pub async fn get_by_user_id(&self, user_id: i64) -> Result<AuthorProfile, Box<dyn std::error::Error>> {
+        self.get_where("u.id = ?", &user_id)
+    }
+
+    /// 根据用户名获取作者资料
+    pub async fn get_by_username(&self, username: &str) -> Result<AuthorProfile, Box<dyn std::error::Error>> {
+        self.get_where("u.username = ?", &username)
+    }
+
+    /// 保存作者资料，并同步其文章显示的作者名
+    pub async fn save(&self, profile: &AuthorProfile) -> Result<(), Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO author_profiles (user_id, display_name, avatar, bio, updated_at) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(user_id) DO UPDATE SET display_name = ?2, avatar = ?3, bio = ?4, updated_at = ?5",
+            params![profile.user_id, profile.display_name, profile.avatar, profile.bio, chrono::Utc::now()],
+        )?;
+        super::authors::sync_author_name(&tx, profile.user_id)?;
+        tx.commit()?;
+        Ok(())
+    }
+}
+
 /// 音乐轨道仓库
 pub struct MusicTrackRepository {
     pool: Arc<Pool<SqliteConnectionManager>>,
@@ -1233,6 +1856,22 @@ impl MusicTrackRepository {
         conn.execute("DELETE FROM music_tracks WHERE id = ?", params![id])?;
         Ok(())
     }
+
+    pub async fn delete_by_filename(&self, file_name: &str) -> Result<usize, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let deleted = conn.execute("DELETE FROM music_tracks WHERE file_name = ?", params![file_name])?;
+        Ok(deleted)
+    }
+
+    /// 文件改名时更新文件名和播放路径，返回更新的记录数
+    pub async fn rename_file(&self, old_file_name: &str, new_file_name: &str) -> Result<usize, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let updated = conn.execute(
+            "UPDATE music_tracks SET file_name = ?, file_path = ? WHERE file_name = ?",
+            params![new_file_name, format!("/music/{}", new_file_name), old_file_name],
+        )?;
+        Ok(updated)
+    }
 }
 
 /// 附件仓库
@@ -1273,6 +1912,12 @@ impl AttachmentRepository {
 
     pub async fn create(&self, attachment: &Attachment) -> Result<(), Box<dyn std::error::Error>> {
         let conn = self.pool.get()?;
+        Self::insert_with(&conn, attachment)?;
+        Ok(())
+    }
+
+    /// 在指定连接（可以是事务）上插入附件记录
+    pub(crate) fn insert_with(conn: &rusqlite::Connection, attachment: &Attachment) -> Result<(), rusqlite::Error> {
         conn.execute(
             "INSERT INTO attachments (file_name, stored_name, file_path, file_type, content_type, file_size, passage_uuid, visibility, show_in_passage, uploaded_at) 
              VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
@@ -1721,4 +2366,612 @@ impl FriendLinkRepository {
         let count: i64 = conn.query_row("SELECT COUNT(*) FROM friend_links WHERE is_enabled = 1", [], |row| row.get(0))?;
         Ok(count)
     }
-}
\ No newline at end of file
+}
+/// 修订快照：标题、Markdown 内容、摘要、标签、分类
+type RevisionSnapshot = (String, String, Option<String>, String, String);
+
+/// 文章修订记录仓库
+pub struct PassageRevisionRepository {
+    pool: Arc<Pool<SqliteConnectionManager>>,
+}
+
+impl PassageRevisionRepository {
+    pub fn new(pool: Arc<Pool<SqliteConnectionManager>>) -> Self {
+        Self { pool }
+    }
+
+    /// 记录一次文章修改
+    ///
+    /// `previous` 为修改前的文章；文章还没有任何修订记录时，先把修改前的状态保存为 initial 版本。
+    /// 标题、Markdown 内容、摘要、标签、分类中任意一项变化都会记录新修订，与最新修订完全相同时不重复记录。
+    pub async fn record_change(&self, previous: Option<&Passage>, current: &Passage, editor: &str, source: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let uuid = current.uuid.as_deref().ok_or("文章 UUID 不能为空")?;
+        let conn = self.pool.get()?;
+
+        let mut latest: Option<RevisionSnapshot> = conn.query_row(
+            "SELECT title, original_content, summary, tags, category FROM passage_revisions WHERE passage_uuid = ? ORDER BY id DESC LIMIT 1",
+            params![uuid],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        ).optional()?;
+
+        let snapshot = Self::snapshot(current);
+        if let Some(previous) = previous {
+            let previous_snapshot = Self::snapshot(previous);
+            if previous_snapshot == snapshot {
+                return Ok(());
+            }
+            if latest.is_none() {
+                Self::insert(&conn, uuid, previous, &previous.author, "initial")?;
+                latest = Some(previous_snapshot);
+            }
+        }
+
+        if latest.as_ref() == Some(&snapshot) {
+            return Ok(());
+        }
+
+        Self::insert(&conn, uuid, current, editor, source)?;
+        Ok(())
+    }
+
+    /// 修订中保存的字段
+    fn snapshot(passage: &Passage) -> RevisionSnapshot {
+        (
+            passage.title.clone(),
+            passage.original_content.clone().unwrap_or_default(),
+            passage.summary.clone(),
+            passage.tags.clone(),
+            passage.category.clone(),
+        )
+    }
+
+    fn insert(conn: &rusqlite::Connection, uuid: &str, passage: &Passage, editor: &str, source: &str) -> Result<(), rusqlite::Error> {
+        conn.execute(
+            "INSERT INTO passage_revisions (passage_uuid, title, original_content, summary, tags, category, editor, source, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                uuid,
+                &passage.title,
+                passage.original_content.as_deref().unwrap_or(""),
+                &passage.summary,
+                &passage.tags,
+                &passage.category,
+                editor,
+                source,
+                chrono::Utc::now(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 根据 ID 获取修订
+    pub async fn get_by_id(&self, id: i64) -> Result<PassageRevision, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, passage_uuid, title, original_content, summary, tags, category, editor, source, created_at
+             FROM passage_revisions WHERE id = ?"
+        )?;
+
+        let revision = stmt.query_row(params![id], |row| {
+            Ok(PassageRevision {
+                id: Some(row.get(0)?),
+                passage_uuid: row.get(1)?,
+                title: row.get(2)?,
+                original_content: row.get(3)?,
+                summary: row.get(4)?,
+                tags: row.get(5)?,
+                category: row.get(6)?,
+                editor: row.get(7)?,
+                source: row.get(8)?,
+                created_at: row.get(9)?,
+            })
+        })?;
+
+        Ok(revision)
+    }
+
+    /// 获取文章的修订列表（最新的在前）
+    pub async fn get_by_passage_uuid(&self, passage_uuid: &str, limit: i64, offset: i64) -> Result<Vec<PassageRevision>, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, passage_uuid, title, original_content, summary, tags, category, editor, source, created_at
+             FROM passage_revisions WHERE passage_uuid = ? ORDER BY id DESC LIMIT ? OFFSET ?"
+        )?;
+
+        let revisions = stmt.query_map(params![passage_uuid, limit, offset], |row| {
+            Ok(PassageRevision {
+                id: Some(row.get(0)?),
+                passage_uuid: row.get(1)?,
+                title: row.get(2)?,
+                original_content: row.get(3)?,
+                summary: row.get(4)?,
+                tags: row.get(5)?,
+                category: row.get(6)?,
+                editor: row.get(7)?,
+                source: row.get(8)?,
+                created_at: row.get(9)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(revisions)
+    }
+
+    /// 获取文章的修订数
+    pub async fn count_by_passage_uuid(&self, passage_uuid: &str) -> Result<i64, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM passage_revisions WHERE passage_uuid = ?", params![passage_uuid], |row| row.get(0))?;
+        Ok(count)
+    }
+}
+
+/// 文章预览链接仓库
+pub struct PreviewLinkRepository {
+    pool: Arc<Pool<SqliteConnectionManager>>,
+}
+
+impl PreviewLinkRepository {
+    pub fn new(pool: Arc<Pool<SqliteConnectionManager>>) -> Self {
+        Self { pool }
+    }
+
+    /// 创建预览链接记录
+    pub async fn create(&self, link: &PreviewLink) -> Result<i64, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        conn.execute(
+            "INSERT INTO preview_links (token_id, passage_uuid, note, created_by, expires_at, created_at)
+             VALUES (?, ?, ?, ?, ?, ?)",
+            params![
+                &link.token_id,
+                &link.passage_uuid,
+                &link.note,
+                &link.created_by,
+                &link.expires_at,
+                &link.created_at,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// 根据令牌 ID 获取预览链接
+    pub async fn get_by_token_id(&self, token_id: &str) -> Result<Option<PreviewLink>, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let link = conn.query_row(
+            "SELECT id, token_id, passage_uuid, note, created_by, expires_at, revoked_at, last_used_at, created_at
+             FROM preview_links WHERE token_id = ?",
+            params![token_id],
+            |row| {
+                Ok(PreviewLink {
+                    id: Some(row.get(0)?),
+                    token_id: row.get(1)?,
+                    passage_uuid: row.get(2)?,
+                    note: row.get(3)?,
+                    created_by: row.get(4)?,
+                    expires_at: row.get(5)?,
+                    revoked_at: row.get(6)?,
+                    last_used_at: row.get(7)?,
+                    created_at: row.get(8)?,
+                })
+            },
+        ).optional()?;
+        Ok(link)
+    }
+
+    /// 获取预览链接列表（可按文章过滤，最新的在前）
+    pub async fn get_all(&self, passage_uuid: Option<&str>) -> Result<Vec<PreviewLink>, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, token_id, passage_uuid, note, created_by, expires_at, revoked_at, last_used_at, created_at
+             FROM preview_links WHERE ?1 IS NULL OR passage_uuid = ?1 ORDER BY id DESC"
+        )?;
+
+        let links = stmt.query_map(params![passage_uuid], |row| {
+            Ok(PreviewLink {
+                id: Some(row.get(0)?),
+                token_id: row.get(1)?,
+                passage_uuid: row.get(2)?,
+                note: row.get(3)?,
+                created_by: row.get(4)?,
+                expires_at: row.get(5)?,
+                revoked_at: row.get(6)?,
+                last_used_at: row.get(7)?,
+                created_at: row.get(8)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(links)
+    }
+
+    /// 吊销预览链接，返回是否有记录被吊销
+    pub async fn revoke(&self, id: i64) -> Result<bool, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let affected = conn.execute(
+            "UPDATE preview_links SET revoked_at = ? WHERE id = ? AND revoked_at IS NULL",
+            params![chrono::Utc::now(), id],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// 记录预览链接的最近使用时间
+    pub async fn touch(&self, id: i64) -> Result<(), Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        conn.execute(
+            "UPDATE preview_links SET last_used_at = ? WHERE id = ?",
+            params![chrono::Utc::now(), id],
+        )?;
+        Ok(())
+    }
+}
+
+/// 系列仓库
+pub struct SeriesRepository {
+    pool: Arc<Pool<SqliteConnectionManager>>,
+}
+
+impl SeriesRepository {
+    pub fn new(pool: Arc<Pool<SqliteConnectionManager>>) -> Self {
+        Self { pool }
+    }
+
+    /// 创建系列
+    pub async fn create(&self, series: &Series) -> Result<i64, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        conn.execute(
+            "INSERT INTO series (title, description, cover_image, sort_order, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?)",
+            params![
+                &series.title,
+                &series.description,
+                &series.cover_image,
+                &series.sort_order,
+                &series.created_at,
+                &series.updated_at,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// 根据 ID 获取系列
+    pub async fn get_by_id(&self, id: i64) -> Result<Series, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let series = conn.query_row(
+            "SELECT id, title, description, cover_image, sort_order, created_at, updated_at
+             FROM series WHERE id = ?",
+            params![id],
+            |row| {
+                Ok(Series {
+                    id: Some(row.get(0)?),
+                    title: row.get(1)?,
+                    description: row.get(2)?,
+                    cover_image: row.get(3)?,
+                    sort_order: row.get(4)?,
+                    created_at: row.get(5)?,
+                    updated_at: row.get(6)?,
+                })
+            },
+        )?;
+        Ok(series)
+    }
+
+    /// 获取所有系列
+    pub async fn get_all(&self, limit: i64, offset: i64) -> Result<Vec<Series>, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, title, description, cover_image, sort_order, created_at, updated_at
+             FROM series ORDER BY sort_order ASC, created_at DESC LIMIT ? OFFSET ?"
+        )?;
+
+        let series = stmt.query_map(params![limit, offset], |row| {
+            Ok(Series {
+                id: Some(row.get(0)?),
+                title: row.get(1)?,
+                description: row.get(2)?,
+                cover_image: row.get(3)?,
+                sort_order: row.get(4)?,
+                created_at: row.get(5)?,
+                updated_at: row.get(6)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(series)
+    }
+
+    /// 获取文章所属的系列
+    pub async fn get_by_passage_uuid(&self, passage_uuid: &str) -> Result<Option<Series>, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let series = conn.query_row(
+            "SELECT s.id, s.title, s.description, s.cover_image, s.sort_order, s.created_at, s.updated_at
+             FROM series s JOIN series_passages sp ON sp.series_id = s.id
+             WHERE sp.passage_uuid = ?",
+            params![passage_uuid],
+            |row| {
+                Ok(Series {
+                    id: Some(row.get(0)?),
+                    title: row.get(1)?,
+                    description: row.get(2)?,
+                    cover_image: row.get(3)?,
+                    sort_order: row.get(4)?,
+                    created_at: row.get(5)?,
+                    updated_at: row.get(6)?,
+                })
+            },
+        ).optional()?;
+        Ok(series)
+    }
+
+    /// 获取系列中的文章（按顺序）
+    pub async fn get_passages(&self, series_id: i64) -> Result<Vec<Passage>, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT p.id, p.uuid, p.title, p.content, p.original_content, p.summary, p.author, p.tags, p.category, p.status, p.file_path, p.visibility, p.is_scheduled, p.published_at, p.cover_image, p.created_at, p.updated_at, p.expires_at, p.slug, p.word_count, p.reading_minutes, p.author_id, p.pinned, p.featured, p.pin_order
+             FROM series_passages sp JOIN passages p ON p.uuid = sp.passage_uuid
+             WHERE sp.series_id = ? ORDER BY sp.position ASC"
+        )?;
+
+        let passages = stmt.query_map(params![series_id], |row| {
+            Ok(Passage {
+                id: Some(row.get(0)?),
+                uuid: Some(row.get(1)?),
+                title: row.get(2)?,
+                content: row.get(3)?,
+                original_content: row.get(4)?,
+                summary: row.get(5)?,
+                author: row.get(6)?,
+                tags: row.get(7)?,
+                category: row.get(8)?,
+                status: row.get(9)?,
+                file_path: row.get(10)?,
+                visibility: row.get(11)?,
+                is_scheduled: row.get(12)?,
+                published_at: row.get(13)?,
+                cover_image: row.get(14)?,
+                created_at: row.get(15)?,
+                updated_at: row.get(16)?,
+                expires_at: row.get(17)?,
+                slug: row.get(18)?,
+                word_count: row.get(19)?,
+                reading_minutes: row.get(20)?,
+                author_id: row.get(21)?,
+                pinned: row.get(22)?,
+                featured: row.get(23)?,
+                pin_order: row.get(24)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(passages)
+    }
+
+    /// 获取系列中的文章数量
+    pub async fn count_passages(&self, series_id: i64) -> Result<i64, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM series_passages sp JOIN passages p ON p.uuid = sp.passage_uuid WHERE sp.series_id = ?",
+            params![series_id],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    /// 设置系列中的文章及顺序（覆盖原有成员；已属于其他系列的文章会被移入本系列）
+    pub async fn set_passages(&self, series_id: i64, passage_uuids: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM series_passages WHERE series_id = ?", params![series_id])?;
+        for (index, uuid) in passage_uuids.iter().enumerate() {
+            tx.execute("DELETE FROM series_passages WHERE passage_uuid = ?", params![uuid])?;
+            tx.execute(
+                "INSERT INTO series_passages (series_id, passage_uuid, position) VALUES (?, ?, ?)",
+                params![series_id, uuid, index as i64 + 1],
+            )?;
+        }
+        tx.execute("UPDATE series SET updated_at = ? WHERE id = ?", params![chrono::Utc::now(), series_id])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 更新系列
+    pub async fn update(&self, series: &Series) -> Result<(), Box<dyn std::error::Error>> {
+        let id = series.id.ok_or("系列 ID 不能为空")?;
+        let conn = self.pool.get()?;
+        conn.execute(
+            "UPDATE series SET title = ?, description = ?, cover_image = ?, sort_order = ?, updated_at = ?
+             WHERE id = ?",
+            params![
+                &series.title,
+                &series.description,
+                &series.cover_image,
+                &series.sort_order,
+                &series.updated_at,
+                id,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 删除系列（文章本身不受影响）
+    pub async fn delete(&self, id: i64) -> Result<(), Box<dyn std::error::Error>> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM series_passages WHERE series_id = ?", params![id])?;
+        tx.execute("DELETE FROM series WHERE id = ?", params![id])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 获取系列总数
+    pub async fn count(&self) -> Result<i64, Box<dyn std::error::Error>> {
+        let conn = self.pool.get()?;
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM series", [], |row| row.get(0))?;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 创建文章并返回数据库中的完整记录
+    async fn create_passage(repo: &PassageRepository, passage: Passage) -> Passage {
+        let id = repo.create(&passage).await.unwrap();
+        repo.get_by_id(id).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_record_change_snapshots_every_change() {
+        let pool = crate::db::init::test_pool();
+        let passage_repo = PassageRepository::new(pool.clone());
+        let revision_repo = PassageRevisionRepository::new(pool);
+
+        let original = create_passage(&passage_repo, Passage::sample("标题", "正文")).await;
+        let uuid = original.uuid.clone().unwrap();
+
+        // 第一次修改时先保存修改前的 initial 版本
+        let mut edited = original.clone();
+        edited.original_content = Some("正文（修改）".to_string());
+        revision_repo.record_change(Some(&original), &edited, "alice", "api").await.unwrap();
+
+        // 只修改标签、分类、摘要也会记录
+        let mut retagged = edited.clone();
+        retagged.tags = r#"["rust"]"#.to_string();
+        revision_repo.record_change(Some(&edited), &retagged, "alice", "api").await.unwrap();
+        let mut recategorized = retagged.clone();
+        recategorized.category = "技术".to_string();
+        recategorized.summary = Some("摘要".to_string());
+        revision_repo.record_change(Some(&retagged), &recategorized, "bob", "editor").await.unwrap();
+
+        // 没有变化、或与最新修订相同时不重复记录
+        revision_repo.record_change(Some(&recategorized), &recategorized, "bob", "api").await.unwrap();
+        revision_repo.record_change(None, &recategorized, "system", "sync").await.unwrap();
+
+        let revisions = revision_repo.get_by_passage_uuid(&uuid, 10, 0).await.unwrap();
+        let sources: Vec<&str> = revisions.iter().map(|r| r.source.as_str()).collect();
+        assert_eq!(sources, vec!["editor", "api", "api", "initial"]);
+        assert_eq!(revisions[3].original_content, "正文");
+        assert_eq!(revisions[1].tags, r#"["rust"]"#);
+        assert_eq!(revisions[0].category, "技术");
+        assert_eq!(revisions[0].summary.as_deref(), Some("摘要"));
+        assert_eq!(revisions[0].editor, "bob");
+        assert_eq!(revision_repo.count_by_passage_uuid(&uuid).await.unwrap(), 4);
+    }
+
+    /// 创建系列并返回 ID
+    async fn create_series(repo: &SeriesRepository, title: &str) -> i64 {
+        let now = chrono::Utc::now();
+        repo.create(&Series {
+            id: None,
+            title: title.to_string(),
+            description: String::new(),
+            cover_image: None,
+            sort_order: 0,
+            created_at: now,
+            updated_at: now,
+        }).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_series_membership_order() {
+        let pool = crate::db::init::test_pool();
+        let passage_repo = PassageRepository::new(pool.clone());
+        let series_repo = SeriesRepository::new(pool);
+
+        let mut uuids = Vec::new();
+        for title in ["一", "二", "三"] {
+            uuids.push(create_passage(&passage_repo, Passage::sample(title, "正文")).await.uuid.unwrap());
+        }
+        let first = create_series(&series_repo, "系列一").await;
+        let second = create_series(&series_repo, "系列二").await;
+
+        // 按给定顺序保存，而不是创建顺序
+        let order = vec![uuids[2].clone(), uuids[0].clone(), uuids[1].clone()];
+        series_repo.set_passages(first, &order).await.unwrap();
+        let titles: Vec<String> = series_repo.get_passages(first).await.unwrap().into_iter().map(|p| p.title).collect();
+        assert_eq!(titles, vec!["三", "一", "二"]);
+
+        // 加入其他系列的文章会从原系列移出
+        series_repo.set_passages(second, &[uuids[0].clone()]).await.unwrap();
+        assert_eq!(series_repo.count_passages(first).await.unwrap(), 2);
+        assert_eq!(series_repo.get_by_passage_uuid(&uuids[0]).await.unwrap().unwrap().id, Some(second));
+
+        // 删除系列时同时删除成员关系
+        series_repo.delete(second).await.unwrap();
+        assert!(series_repo.get_by_passage_uuid(&uuids[0]).await.unwrap().is_none());
+        assert!(series_repo.get_by_id(second).await.is_err());
+        assert_eq!(series_repo.count_passages(first).await.unwrap(), 2);
+    }
+
+    /// 创建文章，`minutes_ago` 决定创建时间（越小越新）
+    async fn create_at(repo: &PassageRepository, title: &str, minutes_ago: i64, pinned: bool, featured: bool) -> i64 {
+        let mut passage = Passage::sample(title, "正文");
+        passage.created_at = chrono::Utc::now() - chrono::Duration::minutes(minutes_ago);
+        passage.pinned = pinned;
+        passage.featured = featured;
+        repo.create(&passage).await.unwrap()
+    }
+
+    fn titles(passages: Vec<Passage>) -> Vec<String> {
+        passages.into_iter().map(|p| p.title).collect()
+    }
+
+    #[tokio::test]
+    async fn test_pinned_passages_lead_the_first_page_only() {
+        let repo = PassageRepository::new(crate::db::init::test_pool());
+        create_at(&repo, "新", 1, false, false).await;
+        create_at(&repo, "中", 2, false, false).await;
+        create_at(&repo, "旧", 3, false, false).await;
+        create_at(&repo, "置顶", 10, true, false).await;
+
+        assert_eq!(titles(repo.get_published(2, 0).await.unwrap()), vec!["置顶", "新"]);
+        assert_eq!(titles(repo.get_published(2, 2).await.unwrap()), vec!["中", "旧"]);
+
+        // 筛选列表不置顶
+        let filtered = repo.get_published_filtered(&PublishedFilter::default(), 10, 0).await.unwrap();
+        assert_eq!(titles(filtered), vec!["新", "中", "旧", "置顶"]);
+    }
+
+    #[tokio::test]
+    async fn test_published_lists_exclude_non_public_passages() {
+        let repo = PassageRepository::new(crate::db::init::test_pool());
+        create_at(&repo, "公开", 1, false, false).await;
+        for visibility in ["private", "password"] {
+            let mut passage = Passage::sample(visibility, "正文");
+            passage.visibility = visibility.to_string();
+            passage.pinned = true;
+            repo.create(&passage).await.unwrap();
+        }
+
+        let filter = PublishedFilter::default();
+        assert_eq!(titles(repo.get_published(10, 0).await.unwrap()), vec!["公开"]);
+        assert_eq!(titles(repo.get_published_filtered(&filter, 10, 0).await.unwrap()), vec!["公开"]);
+        assert_eq!(repo.count_published_filtered(&filter).await.unwrap(), 1);
+        assert_eq!(titles(repo.get_all_public().await.unwrap()), vec!["公开"]);
+    }
+
+    #[tokio::test]
+    async fn test_featured_list_and_reorder() {
+        let repo = PassageRepository::new(crate::db::init::test_pool());
+        let first = create_at(&repo, "推荐一", 1, false, true).await;
+        let second = create_at(&repo, "推荐二", 2, false, true).await;
+        let pinned = create_at(&repo, "置顶", 3, true, false).await;
+        let plain = create_at(&repo, "普通", 4, false, false).await;
+
+        let mut draft = Passage::sample("推荐草稿", "正文");
+        draft.featured = true;
+        draft.status = "draft".to_string();
+        repo.create(&draft).await.unwrap();
+        let mut private = Passage::sample("推荐私密", "正文");
+        private.featured = true;
+        private.visibility = "private".to_string();
+        repo.create(&private).await.unwrap();
+
+        // 只包含已发布且公开的推荐文章
+        assert_eq!(titles(repo.get_featured(10).await.unwrap()), vec!["推荐一", "推荐二"]);
+
+        repo.reorder_pinned(&[second, pinned, first]).await.unwrap();
+        assert_eq!(titles(repo.get_featured(10).await.unwrap()), vec!["推荐二", "推荐一"]);
+        assert_eq!(repo.get_by_id(pinned).await.unwrap().pin_order, 1);
+
+        // 未置顶、推荐或不存在的文章报错，且不会改变已有顺序
+        assert!(repo.reorder_pinned(&[first, plain]).await.is_err());
+        assert!(repo.reorder_pinned(&[first, 9999]).await.is_err());
+        assert_eq!(titles(repo.get_featured(10).await.unwrap()), vec!["推荐二", "推荐一"]);
+    }
+}
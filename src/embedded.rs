@@ -107,6 +107,29 @@ fn extract_dir(src_dir: &str, dst_dir: &Path) -> Result<(), Box<dyn std::error::
     Ok(())
 }
 
+/// 把嵌入文件系统中的目录完整写出到目标目录（覆盖已存在的文件），返回写出的文件数
+pub fn copy_embedded_dir(src_dir: &str, dst_dir: &Path) -> Result<usize, Box<dyn std::error::Error>> {
+    let prefix = format!("{}/", src_dir.trim_end_matches('/'));
+    let mut count = 0;
+
+    for path in EmbeddedAssets::iter() {
+        let Some(relative_path) = path.strip_prefix(prefix.as_str()) else {
+            continue;
+        };
+        let Some(content) = EmbeddedAssets::get(&path) else {
+            continue;
+        };
+        let dst_path = dst_dir.join(relative_path);
+        if let Some(parent) = dst_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&dst_path, &content.data)?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
 /// 获取嵌入的文件内容
 pub fn get_embedded_file(path: &str) -> Option<Vec<u8>> {
     EmbeddedAssets::get(path).map(|f| f.data.to_vec())
@@ -0,0 +1,537 @@
+//! 静态站点导出
+//!
+//! 在进程内构建应用，逐个请求访客可见的页面、订阅源和站点地图并写成普通文件；
+//! 页面脚本依赖的只读接口保存为 JSON 快照，导出的页面中注入一段脚本，
+//! 把 `/api/` 的 GET 请求改为读取快照，其他请求直接提示静态镜像不支持
+
+use actix_web::dev::Service;
+use actix_web::http::header;
+use actix_web::{web, App};
+use futures_util::future::LocalBoxFuture;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use crate::db::repositories::{AttachmentRepository, AuthorRepository, PassageRepository, Repository};
+use crate::feed::FeedFormat;
+
+/// 需要按原样保存的文件扩展名，其他地址保存为 `<路径>/index.html`
+const FILE_EXTENSIONS: [&str; 3] = ["xml", "json", "txt"];
+
+/// 文章列表接口的分页大小（文章页每页 10 篇，归档页每页 20 篇）
+const LIST_PAGE_SIZES: [i64; 2] = [10, 20];
+
+/// 注入到导出页面中的脚本
+///
+/// 快照文件名的规则与 [`snapshot_file`] 一致：没有查询参数时为 `<路径>.json`，
+/// 否则为 `<路径>.<参数哈希>.json`，参数按键、值排序后以 `k=v&k=v` 计算 FNV-1a 哈希
+const FETCH_SHIM: &str = r#"<script>
+(function () {
+  if (location.pathname.length > 1 && location.pathname.endsWith('/')) {
+    history.replaceState(null, '', location.pathname.replace(/\/+$/, '') + location.search + location.hash);
+  }
+  function fnv1a(text) {
+    let hash = 0x811c9dc5;
+    for (const byte of new TextEncoder().encode(text)) {
+      hash ^= byte;
+      hash = Math.imul(hash, 0x01000193) >>> 0;
+    }
+    return hash.toString(16).padStart(8, '0');
+  }
+  function snapshotUrl(url) {
+    const params = Array.from(url.searchParams.entries())
+      .sort((a, b) => (a[0] < b[0] ? -1 : a[0] > b[0] ? 1 : a[1] < b[1] ? -1 : a[1] > b[1] ? 1 : 0));
+    const path = url.pathname.replace(/\/+$/, '');
+    if (params.length === 0) return path + '.json';
+    return path + '.' + fnv1a(params.map(([k, v]) => k + '=' + v).join('&')) + '.json';
+  }
+  function jsonResponse(status, message) {
+    return new Response(JSON.stringify({ success: false, message: message }), {
+      status: status,
+      headers: { 'Content-Type': 'application/json; charset=utf-8' }
+    });
+  }
+  const originalFetch = window.fetch.bind(window);
+  window.fetch = function (input, init) {
+    const url = new URL(input instanceof Request ? input.url : String(input), location.href);
+    if (url.origin !== location.origin || !url.pathname.startsWith('/api/')) {
+      return originalFetch(input, init);
+    }
+    const method = ((init && init.method) || (input instanceof Request ? input.method : 'GET')).toUpperCase();
+    if (method !== 'GET' && method !== 'HEAD') {
+      return Promise.resolve(jsonResponse(503, '静态镜像不支持该操作'));
+    }
+    return originalFetch(snapshotUrl(url)).then(
+      (response) => (response.ok ? response : jsonResponse(404, '静态镜像中没有该数据')),
+      () => jsonResponse(404, '静态镜像中没有该数据')
+    );
+  };
+})();
+</script>
+"#;
+
+/// 导出结果统计
+#[derive(Debug, Default)]
+pub struct ExportReport {
+    pub pages: usize,
+    pub snapshots: usize,
+    pub assets: usize,
+    /// 请求失败或无法保存的地址
+    pub failed: Vec<String>,
+}
+
+/// 进程内请求函数：返回状态码为 200 的响应体
+type Fetch<'a> = dyn Fn(String) -> LocalBoxFuture<'a, Result<Vec<u8>, String>> + 'a;
+
+/// 导出静态站点到 `out` 目录，`base_url` 为订阅源、站点地图和规范链接中使用的站点地址
+pub async fn run(
+    repository: Arc<dyn Repository>,
+    out: &Path,
+    base_url: &str,
+) -> Result<ExportReport, String> {
+    let (scheme, host) = parse_origin(base_url)?;
+    std::fs::create_dir_all(out).map_err(|e| format!("创建导出目录失败 {}: {}", out.display(), e))?;
+
+    let app_cache = Arc::new(crate::cache::AppCache::new(crate::cache::CacheConfig));
+    let view_batch_processor = Arc::new(crate::view_batch::ViewBatchProcessor::new(
+        repository.get_pool().clone(),
+        crate::view_batch::BatchConfig::default(),
+    ));
+    let app = actix_web::test::init_service(
+        App::new()
+            .app_data(web::Data::new(repository.clone()))
+            .app_data(web::Data::new(app_cache))
+            .app_data(web::Data::new(view_batch_processor))
+            .configure(crate::routes::configure_routes),
+    )
+    .await;
+
+    let fetch = |url: String| -> LocalBoxFuture<'_, Result<Vec<u8>, String>> {
+        let request = actix_web::test::TestRequest::get()
+            .uri(&url)
+            .insert_header((header::HOST, host.as_str()))
+            .insert_header(("X-Forwarded-Proto", scheme.as_str()))
+            .peer_addr(([127, 0, 0, 1], 0).into())
+            .to_request();
+        let app = &app;
+        Box::pin(async move {
+            let response = app.call(request).await.map_err(|e| e.to_string())?;
+            let status = response.status();
+            if status != actix_web::http::StatusCode::OK {
+                return Err(format!("HTTP {}", status.as_u16()));
+            }
+            let body = actix_web::body::to_bytes(response.into_body()).await.map_err(|e| e.to_string())?;
+            Ok(body.to_vec())
+        })
+    };
+
+    let mut exporter = Exporter { fetch: &fetch, out, report: ExportReport::default() };
+    let passage_repo = PassageRepository::new(repository.get_pool().clone());
+    exporter.export_pages(&passage_repo).await?;
+    exporter.export_snapshots(&passage_repo).await?;
+    exporter.copy_assets(&repository).await;
+    Ok(exporter.report)
+}
+
+struct Exporter<'a, 'b> {
+    fetch: &'b Fetch<'a>,
+    out: &'b Path,
+    report: ExportReport,
+}
+
+impl Exporter<'_, '_> {
+    /// 请求页面并保存，HTML 页面注入接口快照脚本
+    async fn page(&mut self, url: &str) -> Option<Vec<u8>> {
+        let Some(file) = page_file(url) else {
+            self.report.failed.push(format!("{} (无法映射为文件)", url));
+            return None;
+        };
+        let body = match (self.fetch)(url.to_string()).await {
+            Ok(body) => body,
+            Err(e) => {
+                self.report.failed.push(format!("{} ({})", url, e));
+                return None;
+            }
+        };
+        let content = if file.ends_with("index.html") {
+            inject_shim(&String::from_utf8_lossy(&body)).into_bytes()
+        } else {
+            body.clone()
+        };
+        if self.write(&file, &content, url) {
+            self.report.pages += 1;
+        }
+        Some(body)
+    }
+
+    /// 请求只读接口并保存为快照，返回解析后的 JSON
+    async fn snapshot(&mut self, url: &str) -> Option<serde_json::Value> {
+        let Some(file) = snapshot_file(url) else {
+            self.report.failed.push(format!("{} (无法映射为文件)", url));
+            return None;
+        };
+        let body = match (self.fetch)(url.to_string()).await {
+            Ok(body) => body,
+            Err(e) => {
+                self.report.failed.push(format!("{} ({})", url, e));
+                return None;
+            }
+        };
+        if self.write(&file, &body, url) {
+            self.report.snapshots += 1;
+        }
+        serde_json::from_slice(&body).ok()
+    }
+
+    fn write(&mut self, relative: &Path, content: &[u8], url: &str) -> bool {
+        let path = self.out.join(relative);
+        let written = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&path, content));
+        match written {
+            Ok(()) => true,
+            Err(e) => {
+                self.report.failed.push(format!("{} ({})", url, e));
+                false
+            }
+        }
+    }
+
+    /// 固定页面、文章页、分类、标签、作者页及其订阅源、站点地图
+    async fn export_pages(&mut self, passage_repo: &PassageRepository) -> Result<(), String> {
+        for url in ["/", "/passage", "/collect", "/about", "/friends", "/robots.txt"] {
+            self.page(url).await;
+        }
+        for format in [FeedFormat::Rss, FeedFormat::Atom, FeedFormat::Json] {
+            self.page(&format!("/{}", format.file_name())).await;
+        }
+
+        // 站点地图超过单个文件上限时是索引文件，其中列出的分页也一并导出
+        if let Some(body) = self.page("/sitemap.xml").await {
+            let pages: BTreeSet<String> = SITEMAP_PAGE_RE
+                .captures_iter(&String::from_utf8_lossy(&body))
+                .map(|c| c[0].to_string())
+                .collect();
+            for url in pages {
+                self.page(&url).await;
+            }
+        }
+
+        let passages = passage_repo.get_all_public().await.map_err(|e| format!("读取文章失败: {}", e))?;
+        let mut categories = BTreeSet::new();
+        let mut tags = BTreeSet::new();
+        let mut author_ids = BTreeSet::new();
+        for passage in &passages {
+            if let Some(permalink) = passage.permalink() {
+                self.page(&permalink).await;
+            }
+            if !passage.category.trim().is_empty() {
+                categories.insert(passage.category.clone());
+            }
+            tags.extend(passage.tag_list());
+            author_ids.extend(passage.author_id);
+        }
+
+        let author_repo = AuthorRepository::new(passage_repo.get_pool().clone());
+        let mut authors = BTreeSet::new();
+        for user_id in author_ids {
+            if let Ok(profile) = author_repo.get_by_user_id(user_id).await {
+                authors.insert(profile.username);
+            }
+        }
+
+        let taxonomies = categories.iter().map(|name| ("category", name))
+            .chain(tags.iter().map(|name| ("tag", name)))
+            .chain(authors.iter().map(|name| ("author", name)));
+        for (kind, name) in taxonomies {
+            let base = format!("/{}/{}", kind, urlencoding::encode(name));
+            self.page(&base).await;
+            for format in [FeedFormat::Rss, FeedFormat::Atom, FeedFormat::Json] {
+                self.page(&format!("{}/{}", base, format.file_name())).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// 页面脚本会请求的只读接口
+    async fn export_snapshots(&mut self, passage_repo: &PassageRepository) -> Result<(), String> {
+        for url in ["/api/settings/appearance", "/api/tags", "/api/archive", "/api/friend-links", "/api/passages/featured"] {
+            self.snapshot(url).await;
+        }
+
+        if let Some(cards) = self.snapshot("/api/about/main-cards").await {
+            for id in json_items(&cards, "id") {
+                self.snapshot(&format!("/api/about/sub-cards?main_card_id={}", id)).await;
+            }
+        }
+
+        if let Some(categories) = self.snapshot("/api/categories").await {
+            for name in json_items(&categories, "name") {
+                self.snapshot(&format!("/api/passages?category={}&limit=1000&offset=0", urlencoding::encode(&name))).await;
+            }
+        }
+
+        for limit in LIST_PAGE_SIZES {
+            let mut page = 1;
+            loop {
+                let list = self.snapshot(&format!("/api/passages?page={}&limit={}", page, limit)).await;
+                let has_more = list
+                    .as_ref()
+                    .and_then(|l| l.pointer("/pagination/has_more"))
+                    .and_then(serde_json::Value::as_bool)
+                    .unwrap_or(false);
+                if !has_more {
+                    break;
+                }
+                page += 1;
+            }
+        }
+
+        let passages = passage_repo.get_all_public().await.map_err(|e| format!("读取文章失败: {}", e))?;
+        for passage in &passages {
+            let (Some(id), Some(uuid)) = (passage.id, passage.uuid.as_deref()) else {
+                continue;
+            };
+            self.snapshot(&format!("/api/passages/{}", id)).await;
+            if let Some(slug) = passage.slug.as_deref().filter(|s| !s.is_empty()) {
+                self.snapshot(&format!("/api/passages/{}", urlencoding::encode(slug))).await;
+            }
+            self.snapshot(&format!("/api/comments?passage_uuid={}", urlencoding::encode(uuid))).await;
+            self.snapshot(&format!("/api/passage/{}/related", id)).await;
+        }
+        Ok(())
+    }
+
+    /// 内嵌的 CSS、JS，图片目录（含 favicon）和公开的附件
+    async fn copy_assets(&mut self, repository: &Arc<dyn Repository>) {
+        for (src, dst) in [("templates/css", "css"), ("templates/js", "js")] {
+            match crate::embedded::copy_embedded_dir(src, &self.out.join(dst)) {
+                Ok(count) => self.report.assets += count,
+                Err(e) => self.report.failed.push(format!("/{} ({})", dst, e)),
+            }
+        }
+
+        // 图片目录在启动时从内嵌资源释放，可能被替换过，优先复制磁盘上的版本
+        let img = Path::new("img");
+        let copied = if img.is_dir() {
+            copy_dir(img, &self.out.join("img")).map_err(|e| e.to_string())
+        } else {
+            crate::embedded::copy_embedded_dir("img", &self.out.join("img")).map_err(|e| e.to_string())
+        };
+        match copied {
+            Ok(count) => self.report.assets += count,
+            Err(e) => self.report.failed.push(format!("/img ({})", e)),
+        }
+        if let Some(favicon) = ["img/favicon.ico", "templates/favicon.ico", "favicon.ico"]
+            .into_iter()
+            .find(|p| Path::new(p).is_file())
+        {
+            match std::fs::copy(favicon, self.out.join("favicon.ico")) {
+                Ok(_) => self.report.assets += 1,
+                Err(e) => self.report.failed.push(format!("/favicon.ico ({})", e)),
+            }
+        }
+
+        let attachment_repo = AttachmentRepository::new(repository.get_pool().clone());
+        let mut offset = 0;
+        loop {
+            let attachments = match attachment_repo.get_all(500, offset).await {
+                Ok(attachments) => attachments,
+                Err(e) => {
+                    self.report.failed.push(format!("/attachments ({})", e));
+                    break;
+                }
+            };
+            if attachments.is_empty() {
+                break;
+            }
+            offset += attachments.len() as i64;
+            for attachment in attachments.iter().filter(|a| a.visibility == "public") {
+                let Some(segments) = path_segments(&attachment.file_path) else {
+                    continue;
+                };
+                let target = self.out.join(segments.iter().collect::<PathBuf>());
+                let copied = target
+                    .parent()
+                    .map_or(Ok(()), std::fs::create_dir_all)
+                    .and_then(|_| std::fs::copy(&attachment.file_path, &target));
+                match copied {
+                    Ok(_) => self.report.assets += 1,
+                    Err(e) => self.report.failed.push(format!("/{} ({})", attachment.file_path, e)),
+                }
+            }
+        }
+    }
+}
+
+static SITEMAP_PAGE_RE: once_cell::sync::Lazy<regex::Regex> =
+    once_cell::sync::Lazy::new(|| regex::Regex::new(r"/sitemap-\d+\.xml").unwrap());
+
+/// 解析站点地址，返回 (协议, 主机)
+fn parse_origin(base_url: &str) -> Result<(String, String), String> {
+    let (scheme, rest) = base_url
+        .trim()
+        .split_once("://")
+        .ok_or_else(|| format!("站点地址无效: {}", base_url))?;
+    let scheme = scheme.to_ascii_lowercase();
+    if scheme != "http" && scheme != "https" {
+        return Err(format!("站点地址只支持 http 或 https: {}", base_url));
+    }
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    if host.is_empty() || !path.is_empty() {
+        return Err(format!("站点地址只能包含协议和域名（页面使用根路径链接）: {}", base_url));
+    }
+    Ok((scheme, host.to_string()))
+}
+
+/// 把地址路径拆成解码后的路径段，拒绝 `.`、`..` 和包含分隔符的路径段
+fn path_segments(path: &str) -> Option<Vec<String>> {
+    path.split('/')
+        .filter(|s| !s.is_empty())
+        .map(|segment| {
+            let decoded = urlencoding::decode(segment).ok()?.into_owned();
+            let invalid = decoded == "." || decoded == ".." || decoded.contains(['/', '\\', '\0']);
+            (!invalid).then_some(decoded)
+        })
+        .collect()
+}
+
+/// 页面地址对应的导出文件（相对导出目录）
+fn page_file(url: &str) -> Option<PathBuf> {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let segments = path_segments(path)?;
+    let mut file: PathBuf = segments.iter().collect();
+    let keep_name = segments
+        .last()
+        .and_then(|name| Path::new(name).extension())
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| FILE_EXTENSIONS.contains(&ext));
+    if !keep_name {
+        file.push("index.html");
+    }
+    Some(file)
+}
+
+/// 接口地址对应的快照文件（相对导出目录），规则与注入脚本中的 `snapshotUrl` 一致
+fn snapshot_file(url: &str) -> Option<PathBuf> {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let mut segments = path_segments(path)?;
+    let last = segments.pop()?;
+
+    let mut params: Vec<(String, String)> = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let decode = |s: &str| urlencoding::decode(&s.replace('+', " ")).map(|d| d.into_owned()).ok();
+            Some((decode(key)?, decode(value)?))
+        })
+        .collect::<Option<_>>()?;
+    params.sort();
+
+    let name = if params.is_empty() {
+        format!("{}.json", last)
+    } else {
+        let canonical = params.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join("&");
+        format!("{}.{:08x}.json", last, fnv1a(&canonical))
+    };
+    let mut file: PathBuf = segments.iter().collect();
+    file.push(name);
+    Some(file)
+}
+
+/// 32 位 FNV-1a 哈希
+fn fnv1a(text: &str) -> u32 {
+    text.bytes().fold(0x811c9dc5u32, |hash, byte| (hash ^ byte as u32).wrapping_mul(0x01000193))
+}
+
+/// 把接口快照脚本插入到页面最前面，保证在页面自身脚本之前执行
+fn inject_shim(html: &str) -> String {
+    let position = html
+        .find("<html")
+        .and_then(|start| html[start..].find('>').map(|end| start + end + 1))
+        .unwrap_or(0);
+    format!("{}{}{}", &html[..position], FETCH_SHIM, &html[position..])
+}
+
+/// 取接口返回的 `data` 数组中每一项的指定字段
+fn json_items(value: &serde_json::Value, field: &str) -> Vec<String> {
+    value
+        .get("data")
+        .and_then(serde_json::Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| match item.get(field)? {
+                    serde_json::Value::String(s) => Some(s.clone()),
+                    serde_json::Value::Number(n) => Some(n.to_string()),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 递归复制目录，返回复制的文件数
+fn copy_dir(src: &Path, dst: &Path) -> std::io::Result<usize> {
+    std::fs::create_dir_all(dst)?;
+    let mut count = 0;
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let target = dst.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            count += copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_file() {
+        assert_eq!(page_file("/"), Some(PathBuf::from("index.html")));
+        assert_eq!(page_file("/about"), Some(PathBuf::from("about/index.html")));
+        assert_eq!(page_file("/passage/2024/05/01/hello"), Some(PathBuf::from("passage/2024/05/01/hello/index.html")));
+        assert_eq!(page_file("/category/%E7%AC%94%E8%AE%B0"), Some(PathBuf::from("category/笔记/index.html")));
+        assert_eq!(page_file("/tag/rust/feed.xml"), Some(PathBuf::from("tag/rust/feed.xml")));
+        assert_eq!(page_file("/tag/%2E%2E/feed.xml"), None);
+        assert_eq!(page_file("/tag/a%2Fb"), None);
+    }
+
+    #[test]
+    fn test_snapshot_file() {
+        assert_eq!(snapshot_file("/api/archive"), Some(PathBuf::from("api/archive.json")));
+        // 参数顺序和编码方式不影响快照文件名
+        let a = snapshot_file("/api/passages?page=1&limit=20").unwrap();
+        let b = snapshot_file("/api/passages?limit=20&page=1").unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a, PathBuf::from(format!("api/passages.{:08x}.json", fnv1a("limit=20&page=1"))));
+        assert_eq!(
+            snapshot_file("/api/passages?category=%E7%AC%94+%E8%AE%B0"),
+            Some(PathBuf::from(format!("api/passages.{:08x}.json", fnv1a("category=笔 记"))))
+        );
+    }
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(""), 0x811c9dc5);
+        assert_eq!(fnv1a("a"), 0xe40c292c);
+    }
+
+    #[test]
+    fn test_parse_origin_and_inject_shim() {
+        assert_eq!(parse_origin("https://blog.example.com"), Ok(("https".to_string(), "blog.example.com".to_string())));
+        assert!(parse_origin("https://blog.example.com/sub").is_err());
+        assert!(parse_origin("ftp://blog.example.com").is_err());
+
+        let html = inject_shim("<!DOCTYPE html><html lang=\"zh-CN\"><head><script>a()</script>");
+        assert!(html.starts_with("<!DOCTYPE html><html lang=\"zh-CN\"><script>"));
+        assert!(html.ends_with("<head><script>a()</script>"));
+    }
+}
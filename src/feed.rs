@@ -0,0 +1,334 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use crate::db::models::Passage;
+
+/// 订阅源格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedFormat {
+    Rss,
+    Atom,
+    Json,
+}
+
+impl FeedFormat {
+    /// 根据文件名（feed.xml / atom.xml / feed.json）识别格式
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        match name {
+            "feed.xml" => Some(FeedFormat::Rss),
+            "atom.xml" => Some(FeedFormat::Atom),
+            "feed.json" => Some(FeedFormat::Json),
+            _ => None,
+        }
+    }
+
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "feed.xml",
+            FeedFormat::Atom => "atom.xml",
+            FeedFormat::Json => "feed.json",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "application/rss+xml; charset=utf-8",
+            FeedFormat::Atom => "application/atom+xml; charset=utf-8",
+            FeedFormat::Json => "application/feed+json; charset=utf-8",
+        }
+    }
+}
+
+/// 订阅源元信息
+#[derive(Debug, Clone)]
+pub struct FeedChannel {
+    pub title: String,
+    pub description: String,
+    pub site_url: String,  // 站点（或分类、标签页）地址
+    pub feed_url: String,  // 订阅源自身的地址
+    pub updated: DateTime<Utc>,
+}
+
+/// 订阅源条目
+#[derive(Debug, Clone)]
+pub struct FeedItem {
+    pub id: String,  // 稳定的条目标识（/passage/{id}，slug 修改后不变）
+    pub url: String,
+    pub title: String,
+    pub summary: Option<String>,
+    pub content_html: Option<String>,  // 仅全文模式下输出
+    pub author: String,
+    pub categories: Vec<String>,
+    pub image: Option<String>,
+    pub published: DateTime<Utc>,
+    pub updated: DateTime<Utc>,
+}
+
+impl FeedItem {
+    /// 根据文章生成条目，`origin` 为站点根地址
+    pub fn from_passage(passage: &Passage, origin: &str, full_content: bool) -> Self {
+        let id = format!("{}/passage/{}", origin, passage.id.unwrap_or(0));
+        let url = match passage.permalink() {
+            Some(permalink) => format!("{}{}", origin, permalink),
+            None => id.clone(),
+        };
+
+        let mut categories = vec![passage.category.clone()];
+        categories.extend(passage.tag_list());
+        categories.retain(|c| !c.trim().is_empty());
+        categories.dedup();
+
+        let image = passage.cover_image.as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(|c| {
+                if c.starts_with("http://") || c.starts_with("https://") {
+                    c.to_string()
+                } else {
+                    format!("{}/{}", origin, c.trim_start_matches('/'))
+                }
+            });
+
+        FeedItem {
+            id,
+            url,
+            title: passage.title.clone(),
+            summary: passage.summary.clone().filter(|s| !s.trim().is_empty()),
+            content_html: full_content.then(|| passage.content.clone()),
+            author: passage.author.clone(),
+            categories,
+            image,
+            published: passage.published_at.unwrap_or(passage.created_at),
+            updated: passage.updated_at,
+        }
+    }
+}
+
+/// 按指定格式生成订阅源
+pub fn render(format: FeedFormat, channel: &FeedChannel, items: &[FeedItem]) -> String {
+    match format {
+        FeedFormat::Rss => render_rss(channel, items),
+        FeedFormat::Atom => render_atom(channel, items),
+        FeedFormat::Json => render_json(channel, items),
+    }
+}
+
+/// RSS 2.0
+fn render_rss(channel: &FeedChannel, items: &[FeedItem]) -> String {
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\" xmlns:content=\"http://purl.org/rss/1.0/modules/content/\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n");
+    xml.push_str("<channel>\n");
+    push_element(&mut xml, "title", &channel.title);
+    push_element(&mut xml, "link", &channel.site_url);
+    push_element(&mut xml, "description", &channel.description);
+    xml.push_str(&format!(
+        "<atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/>\n",
+        escape(&channel.feed_url)
+    ));
+    push_element(&mut xml, "lastBuildDate", &channel.updated.to_rfc2822());
+    push_element(&mut xml, "generator", "RustBlog");
+
+    for item in items {
+        xml.push_str("<item>\n");
+        push_element(&mut xml, "title", &item.title);
+        push_element(&mut xml, "link", &item.url);
+        xml.push_str(&format!("<guid isPermaLink=\"true\">{}</guid>\n", escape(&item.id)));
+        push_element(&mut xml, "pubDate", &item.published.to_rfc2822());
+        push_element(&mut xml, "dc:creator", &item.author);
+        for category in &item.categories {
+            push_element(&mut xml, "category", category);
+        }
+        push_element(&mut xml, "description", item.summary.as_deref().unwrap_or(""));
+        if let Some(content) = &item.content_html {
+            push_element(&mut xml, "content:encoded", content);
+        }
+        if let Some(image) = &item.image {
+            // 封面图片大小未知，按惯例 length 填 0
+            xml.push_str(&format!(
+                "<enclosure url=\"{}\" length=\"0\" type=\"{}\"/>\n",
+                escape(image),
+                image_mime(image)
+            ));
+        }
+        xml.push_str("</item>\n");
+    }
+
+    xml.push_str("</channel>\n</rss>\n");
+    xml
+}
+
+/// Atom 1.0
+fn render_atom(channel: &FeedChannel, items: &[FeedItem]) -> String {
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    push_element(&mut xml, "id", &channel.feed_url);
+    push_element(&mut xml, "title", &channel.title);
+    push_element(&mut xml, "subtitle", &channel.description);
+    xml.push_str(&format!("<link href=\"{}\"/>\n", escape(&channel.site_url)));
+    xml.push_str(&format!("<link rel=\"self\" href=\"{}\"/>\n", escape(&channel.feed_url)));
+    push_element(&mut xml, "updated", &rfc3339(&channel.updated));
+    push_element(&mut xml, "generator", "RustBlog");
+
+    for item in items {
+        xml.push_str("<entry>\n");
+        push_element(&mut xml, "id", &item.id);
+        push_element(&mut xml, "title", &item.title);
+        xml.push_str(&format!("<link rel=\"alternate\" type=\"text/html\" href=\"{}\"/>\n", escape(&item.url)));
+        push_element(&mut xml, "published", &rfc3339(&item.published));
+        push_element(&mut xml, "updated", &rfc3339(&item.updated));
+        xml.push_str(&format!("<author><name>{}</name></author>\n", escape(&item.author)));
+        for category in &item.categories {
+            xml.push_str(&format!("<category term=\"{}\"/>\n", escape(category)));
+        }
+        if let Some(summary) = &item.summary {
+            push_element(&mut xml, "summary", summary);
+        }
+        if let Some(content) = &item.content_html {
+            xml.push_str(&format!("<content type=\"html\">{}</content>\n", escape(content)));
+        }
+        if let Some(image) = &item.image {
+            xml.push_str(&format!(
+                "<link rel=\"enclosure\" type=\"{}\" href=\"{}\"/>\n",
+                image_mime(image),
+                escape(image)
+            ));
+        }
+        xml.push_str("</entry>\n");
+    }
+
+    xml.push_str("</feed>\n");
+    xml
+}
+
+/// JSON Feed 1.1
+fn render_json(channel: &FeedChannel, items: &[FeedItem]) -> String {
+    let items: Vec<serde_json::Value> = items.iter()
+        .map(|item| {
+            let mut value = serde_json::json!({
+                "id": item.id,
+                "url": item.url,
+                "title": item.title,
+                "date_published": rfc3339(&item.published),
+                "date_modified": rfc3339(&item.updated),
+                "authors": [{ "name": item.author }],
+                "tags": item.categories,
+            });
+            if let Some(summary) = &item.summary {
+                value["summary"] = serde_json::Value::from(summary.as_str());
+            }
+            // content_html 和 content_text 至少需要一个
+            match &item.content_html {
+                Some(content) => value["content_html"] = serde_json::Value::from(content.as_str()),
+                None => value["content_text"] = serde_json::Value::from(item.summary.as_deref().unwrap_or("")),
+            }
+            if let Some(image) = &item.image {
+                value["image"] = serde_json::Value::from(image.as_str());
+                value["attachments"] = serde_json::json!([{
+                    "url": image,
+                    "mime_type": image_mime(image),
+                }]);
+            }
+            value
+        })
+        .collect();
+
+    serde_json::json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": channel.title,
+        "description": channel.description,
+        "home_page_url": channel.site_url,
+        "feed_url": channel.feed_url,
+        "items": items,
+    })
+    .to_string()
+}
+
+fn push_element(xml: &mut String, name: &str, text: &str) {
+    xml.push_str(&format!("<{name}>{}</{name}>\n", escape(text)));
+}
+
+fn rfc3339(date: &DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// 根据扩展名推断图片类型
+fn image_mime(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    mime_guess::from_path(path)
+        .first()
+        .filter(|m| m.type_() == mime_guess::mime::IMAGE)
+        .map(|m| m.essence_str().to_string())
+        .unwrap_or_else(|| "image/jpeg".to_string())
+}
+
+/// 转义 XML 特殊字符，并去掉 XML 1.0 不允许的控制字符
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if (c as u32) < 0x20 => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> (FeedChannel, Vec<FeedItem>) {
+        let date = DateTime::parse_from_rfc3339("2024-05-01T08:00:00Z").unwrap().with_timezone(&Utc);
+        let channel = FeedChannel {
+            title: "博客 & 笔记".to_string(),
+            description: "描述".to_string(),
+            site_url: "https://example.com".to_string(),
+            feed_url: "https://example.com/feed.xml".to_string(),
+            updated: date,
+        };
+        let item = FeedItem {
+            id: "https://example.com/passage/1".to_string(),
+            url: "https://example.com/passage/2024/05/01/hello".to_string(),
+            title: "<Hello>".to_string(),
+            summary: Some("摘要".to_string()),
+            content_html: Some("<p>正文</p>".to_string()),
+            author: "Dango".to_string(),
+            categories: vec!["rust".to_string()],
+            image: Some("https://example.com/img/cover.png".to_string()),
+            published: date,
+            updated: date,
+        };
+        (channel, vec![item])
+    }
+
+    #[test]
+    fn test_rss_escapes_and_encloses() {
+        let (channel, items) = sample();
+        let xml = render(FeedFormat::Rss, &channel, &items);
+        assert!(xml.contains("<title>博客 &amp; 笔记</title>"));
+        assert!(xml.contains("<title>&lt;Hello&gt;</title>"));
+        assert!(xml.contains("<content:encoded>&lt;p&gt;正文&lt;/p&gt;</content:encoded>"));
+        assert!(xml.contains("<enclosure url=\"https://example.com/img/cover.png\" length=\"0\" type=\"image/png\"/>"));
+        assert!(xml.contains("<pubDate>Wed, 1 May 2024 08:00:00 +0000</pubDate>"));
+    }
+
+    #[test]
+    fn test_atom_and_json() {
+        let (channel, mut items) = sample();
+        let xml = render(FeedFormat::Atom, &channel, &items);
+        assert!(xml.contains("<updated>2024-05-01T08:00:00Z</updated>"));
+        assert!(xml.contains("<content type=\"html\">"));
+
+        items[0].content_html = None;
+        let json: serde_json::Value = serde_json::from_str(&render(FeedFormat::Json, &channel, &items)).unwrap();
+        assert_eq!(json["items"][0]["content_text"], "摘要");
+        assert_eq!(json["items"][0]["attachments"][0]["mime_type"], "image/png");
+    }
+}
@@ -0,0 +1,230 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::db::models::Passage;
+
+/// Markdown 文件头部的元数据（支持 YAML `---` 和 TOML `+++` 两种格式）
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct FrontMatter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_tags", skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cover_image: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slug: Option<String>,
+}
+
+/// 标签既可以写成列表，也可以写成逗号分隔的字符串
+fn deserialize_tags<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum TagsField {
+        List(Vec<String>),
+        Text(String),
+    }
+
+    let tags = match Option::<TagsField>::deserialize(deserializer)? {
+        Some(TagsField::List(list)) => list,
+        Some(TagsField::Text(text)) => text.split(',').map(|t| t.to_string()).collect(),
+        None => return Ok(None),
+    };
+
+    Ok(Some(
+        tags.into_iter()
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect(),
+    ))
+}
+
+impl FrontMatter {
+    /// 根据文章生成元数据
+    pub fn from_passage(passage: &Passage) -> Self {
+        FrontMatter {
+            title: Some(passage.title.clone()),
+            tags: Some(serde_json::from_str(&passage.tags).unwrap_or_default()),
+            category: Some(passage.category.clone()),
+            status: Some(passage.status.clone()),
+            visibility: Some(passage.visibility.clone()),
+            published_at: passage.published_at.map(|d| d.to_rfc3339_opts(SecondsFormat::Secs, true)),
+            expires_at: passage.expires_at.map(|d| d.to_rfc3339_opts(SecondsFormat::Secs, true)),
+            cover_image: passage.cover_image.clone(),
+            summary: passage.summary.clone(),
+            slug: passage.slug.clone(),
+        }
+    }
+
+    /// 解析发布时间
+    pub fn published_at_utc(&self) -> Option<DateTime<Utc>> {
+        self.published_at.as_deref().and_then(parse_datetime)
+    }
+
+    /// 解析下线时间
+    pub fn expires_at_utc(&self) -> Option<DateTime<Utc>> {
+        self.expires_at.as_deref().and_then(parse_datetime)
+    }
+}
+
+/// 解析常见的日期时间写法（RFC 3339、`YYYY-MM-DD HH:MM:SS [+ZZZZ]`、`YYYY-MM-DD`）
+pub fn parse_datetime(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    // Jekyll、Hexo 常见的带时区写法：2024-05-01 08:00:00 +0800
+    for format in ["%Y-%m-%d %H:%M:%S %z", "%Y-%m-%d %H:%M %z"] {
+        if let Ok(dt) = DateTime::parse_from_str(value, format) {
+            return Some(dt.with_timezone(&Utc));
+        }
+    }
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(naive.and_utc());
+        }
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|naive| naive.and_utc())
+}
+
+/// 查找以 `delimiter` 单独成行的结束位置，返回（元数据内容，正文）
+fn split_block<'a>(content: &'a str, delimiter: &str) -> Option<(&'a str, &'a str)> {
+    let rest = content.strip_prefix(delimiter)?;
+    let rest = rest.strip_prefix("\r\n").or_else(|| rest.strip_prefix('\n'))?;
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == delimiter {
+            let body = &rest[offset + line.len()..];
+            return Some((&rest[..offset], body.trim_start_matches(['\r', '\n'])));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// 拆分 front matter 和正文；没有 front matter 时返回默认值和原文
+pub fn parse(content: &str) -> Result<(FrontMatter, &str), String> {
+    let (value, body) = parse_value(content)?;
+    if value.is_null() {
+        return Ok((FrontMatter::default(), body));
+    }
+    let front_matter = serde_json::from_value(value).map_err(|e| format!("解析 front matter 失败: {}", e))?;
+    Ok((front_matter, body))
+}
+
+/// 拆分 front matter 和正文，元数据解析为通用的 JSON 值（没有 front matter 时为 Null）
+///
+/// 导入其他博客系统的文件时按各自的字段名读取
+pub fn parse_value(content: &str) -> Result<(serde_json::Value, &str), String> {
+    let content = content.strip_prefix('\u{FEFF}').unwrap_or(content);
+
+    if let Some((raw, body)) = split_block(content, "---") {
+        if raw.trim().is_empty() {
+            return Ok((serde_json::Value::Null, body));
+        }
+        let value: serde_yaml::Value = serde_yaml::from_str(raw)
+            .map_err(|e| format!("解析 YAML front matter 失败: {}", e))?;
+        let value = serde_json::to_value(value).map_err(|e| format!("解析 YAML front matter 失败: {}", e))?;
+        return Ok((value, body));
+    }
+
+    if let Some((raw, body)) = split_block(content, "+++") {
+        let mut table: toml::Table = toml::from_str(raw)
+            .map_err(|e| format!("解析 TOML front matter 失败: {}", e))?;
+
+        // TOML 原生日期类型转为字符串，和 YAML 保持一致
+        for (_, value) in table.iter_mut() {
+            if let toml::Value::Datetime(dt) = value {
+                *value = toml::Value::String(dt.to_string());
+            }
+        }
+
+        let value = serde_json::to_value(table).map_err(|e| format!("解析 TOML front matter 失败: {}", e))?;
+        return Ok((value, body));
+    }
+
+    Ok((serde_json::Value::Null, content))
+}
+
+/// 将 front matter（YAML 格式）和正文拼接为完整的文件内容
+pub fn render(front_matter: &FrontMatter, body: &str) -> Result<String, String> {
+    let yaml = serde_yaml::to_string(front_matter)
+        .map_err(|e| format!("生成 front matter 失败: {}", e))?;
+    Ok(format!("---\n{}---\n\n{}", yaml, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_yaml() {
+        let content = "---\ntitle: 你好\ntags: [rust, 博客]\npublished_at: 2024-05-01 08:00:00\n---\n\n# 正文\n";
+        let (fm, body) = parse(content).unwrap();
+        assert_eq!(fm.title.as_deref(), Some("你好"));
+        assert_eq!(fm.tags, Some(vec!["rust".to_string(), "博客".to_string()]));
+        assert_eq!(fm.published_at_utc().unwrap().to_rfc3339(), "2024-05-01T08:00:00+00:00");
+        assert_eq!(body, "# 正文\n");
+    }
+
+    #[test]
+    fn test_parse_toml() {
+        let content = "+++\ntitle = \"Hello\"\ntags = \"a, b\"\npublished_at = 2024-05-01T08:00:00Z\n+++\nbody";
+        let (fm, body) = parse(content).unwrap();
+        assert_eq!(fm.title.as_deref(), Some("Hello"));
+        assert_eq!(fm.tags, Some(vec!["a".to_string(), "b".to_string()]));
+        assert!(fm.published_at_utc().is_some());
+        assert_eq!(body, "body");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let fm = FrontMatter {
+            title: Some("标题: 带冒号".to_string()),
+            tags: Some(vec!["x".to_string()]),
+            slug: Some("hello-world".to_string()),
+            ..Default::default()
+        };
+        let rendered = render(&fm, "正文内容\n").unwrap();
+        let (parsed, body) = parse(&rendered).unwrap();
+        assert_eq!(parsed.title, fm.title);
+        assert_eq!(parsed.tags, fm.tags);
+        assert_eq!(parsed.slug, fm.slug);
+        assert_eq!(body, "正文内容\n");
+    }
+
+    #[test]
+    fn test_parse_datetime_with_offset() {
+        let dt = parse_datetime("2024-05-01 08:00:00 +0800").unwrap();
+        assert_eq!(dt.to_rfc3339(), "2024-05-01T00:00:00+00:00");
+        assert!(parse_datetime("2024-05-01").is_some());
+        assert!(parse_datetime("昨天").is_none());
+    }
+
+    #[test]
+    fn test_no_front_matter() {
+        let (fm, body) = parse("# 标题\n---\n").unwrap();
+        assert!(fm.title.is_none());
+        assert_eq!(body, "# 标题\n---\n");
+    }
+}
@@ -243,7 +243,7 @@ pub async fn create_main_card(
         title: req.title.clone(),
         icon: req.icon.clone(),
         layout_type: req.layout_type.clone(),
-        custom_css: req.custom_css.clone(),
+        custom_css: crate::sanitize::sanitize_css(&req.custom_css),
         sort_order: req.sort_order,
         is_enabled: req.is_enabled,
         created_at: chrono::Utc::now(),
@@ -296,7 +296,7 @@ pub async fn update_main_card(
     card.title = req.title.clone();
     card.icon = req.icon.clone();
     card.layout_type = req.layout_type.clone();
-    card.custom_css = req.custom_css.clone();
+    card.custom_css = crate::sanitize::sanitize_css(&req.custom_css);
     card.sort_order = req.sort_order;
     card.is_enabled = req.is_enabled;
     card.updated_at = chrono::Utc::now();
@@ -363,7 +363,7 @@ pub async fn create_sub_card(
         icon: req.icon.clone(),
         link_url: req.link_url.clone(),
         layout_type: req.layout_type.clone(),
-        custom_css: req.custom_css.clone(),
+        custom_css: crate::sanitize::sanitize_css(&req.custom_css),
         sort_order: req.sort_order,
         is_enabled: req.is_enabled,
         created_at: chrono::Utc::now(),
@@ -419,7 +419,7 @@ pub async fn update_sub_card(
     card.icon = req.icon.clone();
     card.link_url = req.link_url.clone();
     card.layout_type = req.layout_type.clone();
-    card.custom_css = req.custom_css.clone();
+    card.custom_css = crate::sanitize::sanitize_css(&req.custom_css);
     card.sort_order = req.sort_order;
     card.is_enabled = req.is_enabled;
     card.updated_at = chrono::Utc::now();
@@ -16,8 +16,8 @@ pub struct ArchiveResponse {
 pub async fn list(repo: web::Data<Arc<dyn Repository>>) -> HttpResponse {
     let passage_repo = PassageRepository::new(repo.get_pool().clone());
     
-    // 获取所有文章
-    let passages = match passage_repo.get_all(1000, 0).await {
+    // 获取已发布的文章（与列表使用同一规则，不包含草稿、未到时间和已下线的文章）
+    let passages = match passage_repo.get_published(1000, 0).await {
         Ok(p) => p,
         Err(_) => {
             return HttpResponse::InternalServerError().json(serde_json::json!({
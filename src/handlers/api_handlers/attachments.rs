@@ -427,7 +427,7 @@ pub async fn update(
 }
 
 /// 确定文件类型
-fn determine_file_type(filename: &str, content_type: &str) -> String {
+pub(crate) fn determine_file_type(filename: &str, content_type: &str) -> String {
     let ext = std::path::Path::new(filename)
         .extension()
         .and_then(|e| e.to_str())
@@ -0,0 +1,145 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+use crate::db::models::AuthorProfile;
+use crate::db::repositories::{AuthorRepository, PassageRepository, PublishedFilter, Repository};
+use std::sync::Arc;
+
+/// 显示名最大字符数
+const MAX_DISPLAY_NAME_CHARS: usize = 50;
+
+/// 简介最大字符数
+const MAX_BIO_CHARS: usize = 500;
+
+/// 公开的作者资料
+#[derive(Debug, Serialize)]
+pub struct AuthorResponse {
+    #[serde(flatten)]
+    pub profile: AuthorProfile,
+    pub url: String,
+    pub passage_count: i64,
+}
+
+/// 更新作者资料请求（未提供的字段保持不变）
+#[derive(Debug, Deserialize)]
+pub struct UpdateProfileRequest {
+    pub display_name: Option<String>,
+    pub avatar: Option<String>,
+    pub bio: Option<String>,
+}
+
+/// 获取作者公开资料及已发布文章数
+pub async fn get(
+    repo: web::Data<Arc<dyn Repository>>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let username = path.into_inner();
+    let author_repo = AuthorRepository::new(repo.get_pool().clone());
+    let profile = match author_repo.get_by_username(&username).await {
+        Ok(profile) => profile,
+        Err(_) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "success": false,
+                "message": "作者不存在"
+            }));
+        }
+    };
+
+    let passage_repo = PassageRepository::new(repo.get_pool().clone());
+    let filter = PublishedFilter { author: Some(&profile.username), ..Default::default() };
+    let passage_count = passage_repo.count_published_filtered(&filter).await.unwrap_or(0);
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "data": AuthorResponse {
+            url: format!("/author/{}", profile.username),
+            profile,
+            passage_count,
+        }
+    }))
+}
+
+/// 获取当前登录用户的作者资料
+pub async fn get_profile(
+    repo: web::Data<Arc<dyn Repository>>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let Some((user_id, _, _)) = crate::middleware::auth::current_user(&req) else {
+        return crate::middleware::auth::missing_token_response();
+    };
+
+    let author_repo = AuthorRepository::new(repo.get_pool().clone());
+    match author_repo.get_by_user_id(user_id).await {
+        Ok(profile) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": profile
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "success": false,
+            "message": format!("获取作者资料失败: {}", e)
+        })),
+    }
+}
+
+/// 更新当前登录用户的作者资料，显示名变化时同步更新其文章的作者名
+pub async fn update_profile(
+    repo: web::Data<Arc<dyn Repository>>,
+    req: HttpRequest,
+    body: web::Json<UpdateProfileRequest>,
+) -> HttpResponse {
+    let Some((user_id, _, _)) = crate::middleware::auth::current_user(&req) else {
+        return crate::middleware::auth::missing_token_response();
+    };
+
+    let author_repo = AuthorRepository::new(repo.get_pool().clone());
+    let mut profile = match author_repo.get_by_user_id(user_id).await {
+        Ok(profile) => profile,
+        Err(_) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "success": false,
+                "message": "用户不存在"
+            }));
+        }
+    };
+
+    let body = body.into_inner();
+    if let Some(display_name) = body.display_name {
+        let display_name = display_name.trim();
+        if display_name.chars().count() > MAX_DISPLAY_NAME_CHARS {
+            return bad_request(&format!("显示名不能超过 {} 个字符", MAX_DISPLAY_NAME_CHARS));
+        }
+        // 留空表示恢复为用户名
+        profile.display_name = if display_name.is_empty() { profile.username.clone() } else { display_name.to_string() };
+    }
+    if let Some(avatar) = body.avatar {
+        let avatar = avatar.trim();
+        if !avatar.is_empty() && !crate::sanitize::safe_url(avatar) {
+            return bad_request("头像地址无效");
+        }
+        profile.avatar = avatar.to_string();
+    }
+    if let Some(bio) = body.bio {
+        if bio.chars().count() > MAX_BIO_CHARS {
+            return bad_request(&format!("简介不能超过 {} 个字符", MAX_BIO_CHARS));
+        }
+        profile.bio = bio.trim().to_string();
+    }
+
+    match author_repo.save(&profile).await {
+        Ok(_) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "message": "作者资料已更新",
+            "data": profile
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "success": false,
+            "message": format!("更新作者资料失败: {}", e)
+        })),
+    }
+}
+
+fn bad_request(message: &str) -> HttpResponse {
+    HttpResponse::BadRequest().json(serde_json::json!({
+        "success": false,
+        "message": message
+    }))
+}
@@ -0,0 +1,242 @@
+use actix_files::NamedFile;
+use actix_multipart::Multipart;
+use actix_web::http::header::{ContentDisposition, DispositionParam, DispositionType};
+use actix_web::{web, HttpRequest, HttpResponse};
+use futures_util::stream::StreamExt;
+use std::io::Write;
+use std::sync::Arc;
+use crate::backup::{self, BackupConfig, BackupKind};
+use crate::db::repositories::Repository;
+
+/// 上传备份文件的大小上限（4GB）
+const MAX_UPLOAD_BYTES: u64 = 4 * 1024 * 1024 * 1024;
+
+/// 列出备份文件
+pub async fn list(config: web::Data<Arc<BackupConfig>>, req: HttpRequest) -> HttpResponse {
+    if req.cookie("auth_token").is_none() {
+        return crate::middleware::auth::missing_token_response();
+    }
+    if crate::middleware::auth::check_admin_auth(&req).is_none() {
+        return crate::middleware::auth::forbidden_response();
+    }
+
+    match backup::list(&config) {
+        Ok(backups) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": {
+                "backups": backups,
+                "interval_hours": config.interval_hours,
+                "keep": config.keep
+            }
+        })),
+        Err(message) => internal_error(&message),
+    }
+}
+
+/// 立即创建一份备份
+pub async fn create(
+    repo: web::Data<Arc<dyn Repository>>,
+    config: web::Data<Arc<BackupConfig>>,
+    req: HttpRequest,
+) -> HttpResponse {
+    if req.cookie("auth_token").is_none() {
+        return crate::middleware::auth::missing_token_response();
+    }
+    if crate::middleware::auth::check_admin_auth(&req).is_none() {
+        return crate::middleware::auth::forbidden_response();
+    }
+
+    let pool = repo.get_pool().clone();
+    let config = config.get_ref().clone();
+    let result = tokio::task::spawn_blocking(move || backup::create(&pool, &config, BackupKind::Manual)).await;
+    match result {
+        Ok(Ok(info)) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "message": "备份完成",
+            "data": info
+        })),
+        Ok(Err(message)) => internal_error(&message),
+        Err(e) => internal_error(&format!("备份任务异常: {}", e)),
+    }
+}
+
+/// 上传备份文件（用于在新服务器上恢复），保存到备份目录
+pub async fn upload(
+    config: web::Data<Arc<BackupConfig>>,
+    mut payload: Multipart,
+    req: HttpRequest,
+) -> HttpResponse {
+    if req.cookie("auth_token").is_none() {
+        return crate::middleware::auth::missing_token_response();
+    }
+    if crate::middleware::auth::check_admin_auth(&req).is_none() {
+        return crate::middleware::auth::forbidden_response();
+    }
+
+    if let Err(e) = std::fs::create_dir_all(&config.dir) {
+        return internal_error(&format!("创建备份目录失败: {}", e));
+    }
+    let name = backup::upload_name();
+    let path = config.dir.join(&name);
+
+    while let Some(field) = payload.next().await {
+        let mut field = match field {
+            Ok(field) => field,
+            Err(e) => return bad_request(&format!("读取上传内容失败: {}", e)),
+        };
+        if field.content_disposition().and_then(|cd| cd.get_filename()).is_none() {
+            continue;
+        }
+
+        let mut file = match std::fs::File::create(&path) {
+            Ok(file) => file,
+            Err(e) => return internal_error(&format!("保存备份文件失败: {}", e)),
+        };
+        let mut total = 0u64;
+        while let Some(chunk) = field.next().await {
+            let written = match chunk {
+                Ok(chunk) => {
+                    total += chunk.len() as u64;
+                    if total > MAX_UPLOAD_BYTES {
+                        Err("备份文件超过 4GB".to_string())
+                    } else {
+                        file.write_all(&chunk).map_err(|e| format!("保存备份文件失败: {}", e))
+                    }
+                }
+                Err(e) => Err(format!("读取上传内容失败: {}", e)),
+            };
+            if let Err(message) = written {
+                drop(file);
+                let _ = std::fs::remove_file(&path);
+                return bad_request(&message);
+            }
+        }
+
+        return HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "message": "备份文件已上传，确认后调用 restore 执行恢复",
+            "data": { "name": name, "size": total }
+        }));
+    }
+
+    bad_request("没有上传文件")
+}
+
+/// 下载备份文件
+pub async fn download(
+    config: web::Data<Arc<BackupConfig>>,
+    path: web::Path<String>,
+    req: HttpRequest,
+) -> HttpResponse {
+    if req.cookie("auth_token").is_none() {
+        return crate::middleware::auth::missing_token_response();
+    }
+    if crate::middleware::auth::check_admin_auth(&req).is_none() {
+        return crate::middleware::auth::forbidden_response();
+    }
+
+    let name = path.into_inner();
+    let Some(file_path) = backup::find(&config, &name) else {
+        return not_found();
+    };
+    match NamedFile::open(file_path) {
+        Ok(file) => file
+            .set_content_disposition(ContentDisposition {
+                disposition: DispositionType::Attachment,
+                parameters: vec![DispositionParam::Filename(name)],
+            })
+            .into_response(&req),
+        Err(e) => internal_error(&format!("打开备份文件失败: {}", e)),
+    }
+}
+
+/// 删除备份文件
+pub async fn delete(
+    config: web::Data<Arc<BackupConfig>>,
+    path: web::Path<String>,
+    req: HttpRequest,
+) -> HttpResponse {
+    if req.cookie("auth_token").is_none() {
+        return crate::middleware::auth::missing_token_response();
+    }
+    if crate::middleware::auth::check_admin_auth(&req).is_none() {
+        return crate::middleware::auth::forbidden_response();
+    }
+
+    let Some(file_path) = backup::find(&config, &path.into_inner()) else {
+        return not_found();
+    };
+    match std::fs::remove_file(file_path) {
+        Ok(()) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "message": "备份已删除"
+        })),
+        Err(e) => internal_error(&format!("删除备份失败: {}", e)),
+    }
+}
+
+/// 从备份文件恢复全部数据
+pub async fn restore(
+    repo: web::Data<Arc<dyn Repository>>,
+    config: web::Data<Arc<BackupConfig>>,
+    path: web::Path<String>,
+    req: HttpRequest,
+) -> HttpResponse {
+    if req.cookie("auth_token").is_none() {
+        return crate::middleware::auth::missing_token_response();
+    }
+    if crate::middleware::auth::check_admin_auth(&req).is_none() {
+        return crate::middleware::auth::forbidden_response();
+    }
+
+    let Some(file_path) = backup::find(&config, &path.into_inner()) else {
+        return not_found();
+    };
+    let pool = repo.get_pool().clone();
+    let config = config.get_ref().clone();
+    let restore_config = config.clone();
+    let result = tokio::task::spawn_blocking(move || backup::restore(&pool, &restore_config, &file_path)).await;
+    match result {
+        Ok(Ok(report)) => {
+            crate::cache::invalidate_passages();
+            // 恢复的 JWT 密钥立即生效，旧密钥签发的登录令牌随之失效
+            let message = if !report.jwt_secret {
+                "恢复完成".to_string()
+            } else if let Err(e) = crate::jwt::reload_jwt_service(&config.jwt_secret_path) {
+                eprintln!("❌ 重新加载JWT密钥失败: {}", e);
+                format!("恢复完成，但重新加载 JWT 密钥失败，请重启服务: {}", e)
+            } else {
+                "恢复完成，JWT 密钥已重新加载，请重新登录".to_string()
+            };
+            HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "message": message,
+                "data": report
+            }))
+        }
+        Ok(Err(message)) => bad_request(&message),
+        Err(e) => internal_error(&format!("恢复任务异常: {}", e)),
+    }
+}
+
+fn bad_request(message: &str) -> HttpResponse {
+    HttpResponse::BadRequest().json(serde_json::json!({
+        "success": false,
+        "message": message
+    }))
+}
+
+fn internal_error(message: &str) -> HttpResponse {
+    eprintln!("❌ {}", message);
+    HttpResponse::InternalServerError().json(serde_json::json!({
+        "success": false,
+        "message": message
+    }))
+}
+
+fn not_found() -> HttpResponse {
+    HttpResponse::NotFound().json(serde_json::json!({
+        "success": false,
+        "message": "备份不存在"
+    }))
+}
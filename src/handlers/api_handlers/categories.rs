@@ -9,6 +9,7 @@ use chrono::Utc;
 #[derive(Debug, Serialize)]
 pub struct CategoryResponse {
     pub id: i64,
+    pub parent_id: Option<i64>,
     pub name: String,
     pub description: String,
     pub icon: String,
@@ -22,6 +23,7 @@ pub struct CategoryResponse {
 #[derive(Debug, Deserialize)]
 pub struct CreateCategoryRequest {
     pub name: String,
+    pub parent_id: Option<i64>,
     pub description: Option<String>,
     pub icon: Option<String>,
     pub sort_order: Option<i32>,
@@ -38,6 +40,20 @@ pub struct UpdateCategoryRequest {
     pub is_enabled: Option<bool>,
 }
 
+/// 移动分类请求（parent_id 为空时移到顶级）
+#[derive(Debug, Deserialize)]
+pub struct MoveCategoryRequest {
+    pub parent_id: Option<i64>,
+    pub sort_order: Option<i32>,
+}
+
+/// 重排同级分类请求（按 ids 顺序重新设置 sort_order）
+#[derive(Debug, Deserialize)]
+pub struct ReorderCategoriesRequest {
+    pub parent_id: Option<i64>,
+    pub ids: Vec<i64>,
+}
+
 /// 获取所有分类（管理员）
 pub async fn admin_list(
     repo: web::Data<Arc<dyn Repository>>,
@@ -79,6 +95,7 @@ pub async fn admin_list(
     let data: Vec<CategoryResponse> = categories.into_iter()
         .map(|cat| CategoryResponse {
             id: cat.id.unwrap_or(0),
+            parent_id: cat.parent_id,
             name: cat.name,
             description: cat.description,
             icon: cat.icon,
@@ -99,6 +116,14 @@ pub async fn admin_list(
 /// 获取所有分类（公开）
 pub async fn list(repo: web::Data<Arc<dyn Repository>>) -> HttpResponse {
     let passage_repo = PassageRepository::new(repo.get_pool().clone());
+    let category_repo = CategoryRepository::new(repo.get_pool().clone());
+    
+    // 分类表中的记录提供 ID 和父分类，只存在于文章中的分类作为顶级分类返回
+    let known: std::collections::HashMap<String, Category> = category_repo.get_all(10000, 0).await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|c| (c.name.clone(), c))
+        .collect();
     
     // 从数据库获取所有分类
     match passage_repo.get_all_categories().await {
@@ -106,15 +131,29 @@ pub async fn list(repo: web::Data<Arc<dyn Repository>>) -> HttpResponse {
             // 转换为API响应格式
             let data: Vec<CategoryResponse> = categories.into_iter()
                 .enumerate()
-                .map(|(i, name)| CategoryResponse {
-                    id: (i + 1) as i64,
-                    name,
-                    description: String::new(),
-                    icon: String::new(),
-                    sort_order: (i + 1) as i32,
-                    is_enabled: true,
-                    created_at: Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
-                    updated_at: Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                .map(|(i, name)| match known.get(&name) {
+                    Some(cat) => CategoryResponse {
+                        id: cat.id.unwrap_or(0),
+                        parent_id: cat.parent_id,
+                        name,
+                        description: cat.description.clone(),
+                        icon: cat.icon.clone(),
+                        sort_order: cat.sort_order,
+                        is_enabled: cat.is_enabled,
+                        created_at: cat.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                        updated_at: cat.updated_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                    },
+                    None => CategoryResponse {
+                        id: 0,
+                        parent_id: None,
+                        name,
+                        description: String::new(),
+                        icon: String::new(),
+                        sort_order: (i + 1) as i32,
+                        is_enabled: true,
+                        created_at: Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                        updated_at: Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                    },
                 })
                 .collect();
             
@@ -153,6 +192,7 @@ pub async fn get(
         Ok(category) => {
             let response = CategoryResponse {
                 id: category.id.unwrap_or(0),
+                parent_id: category.parent_id,
                 name: category.name,
                 description: category.description,
                 icon: category.icon,
@@ -190,9 +230,19 @@ pub async fn create(
     }
     let category_repo = CategoryRepository::new(repo.get_pool().clone());
     
+    if let Some(parent_id) = req.parent_id {
+        if category_repo.get_by_id(parent_id).await.is_err() {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "success": false,
+                "message": "父分类不存在"
+            }));
+        }
+    }
+    
     let now = Utc::now();
     let category = Category {
         id: None,
+        parent_id: req.parent_id,
         name: req.name.clone(),
         description: req.description.clone().unwrap_or_default(),
         icon: req.icon.clone().unwrap_or_default(),
@@ -283,7 +333,95 @@ pub async fn update(
     }
 }
 
-/// 删除分类
+/// 移动分类到其他父分类下
+pub async fn move_category(
+    repo: web::Data<Arc<dyn Repository>>,
+    path: web::Path<i64>,
+    req: web::Json<MoveCategoryRequest>,
+    http_req: actix_web::HttpRequest,
+) -> HttpResponse {
+    // 鉴权检查
+    if http_req.cookie("auth_token").is_none() {
+        return crate::middleware::auth::missing_token_response();
+    }
+    if crate::middleware::auth::check_admin_auth(&http_req).is_none() {
+        return crate::middleware::auth::forbidden_response();
+    }
+    let id = path.into_inner();
+    let category_repo = CategoryRepository::new(repo.get_pool().clone());
+    
+    if category_repo.get_by_id(id).await.is_err() {
+        return HttpResponse::NotFound().json(serde_json::json!({
+            "success": false,
+            "message": "分类不存在"
+        }));
+    }
+    if let Some(parent_id) = req.parent_id {
+        if category_repo.get_by_id(parent_id).await.is_err() {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "success": false,
+                "message": "父分类不存在"
+            }));
+        }
+    }
+    
+    match category_repo.move_to(id, req.parent_id, req.sort_order).await {
+        Ok(_) => {
+            HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "message": "分类移动成功"
+            }))
+        }
+        Err(e) => {
+            // 移动到自身或子分类下会形成环
+            HttpResponse::BadRequest().json(serde_json::json!({
+                "success": false,
+                "message": e.to_string()
+            }))
+        }
+    }
+}
+
+/// 重排同一父分类下的子分类
+pub async fn reorder(
+    repo: web::Data<Arc<dyn Repository>>,
+    req: web::Json<ReorderCategoriesRequest>,
+    http_req: actix_web::HttpRequest,
+) -> HttpResponse {
+    // 鉴权检查
+    if http_req.cookie("auth_token").is_none() {
+        return crate::middleware::auth::missing_token_response();
+    }
+    if crate::middleware::auth::check_admin_auth(&http_req).is_none() {
+        return crate::middleware::auth::forbidden_response();
+    }
+    if req.ids.is_empty() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "success": false,
+            "message": "分类ID列表不能为空"
+        }));
+    }
+    
+    let category_repo = CategoryRepository::new(repo.get_pool().clone());
+    
+    match category_repo.reorder(req.parent_id, &req.ids).await {
+        Ok(count) => {
+            HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "message": format!("已重排 {} 个分类", count)
+            }))
+        }
+        Err(e) => {
+            eprintln!("重排分类失败: {}", e);
+            HttpResponse::BadRequest().json(serde_json::json!({
+                "success": false,
+                "message": e.to_string()
+            }))
+        }
+    }
+}
+
+/// 删除分类（子分类移到其父分类下）
 pub async fn delete(
     repo: web::Data<Arc<dyn Repository>>,
     path: web::Path<i64>,
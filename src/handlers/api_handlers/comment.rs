@@ -3,22 +3,6 @@ use serde::{Deserialize, Serialize};
 use crate::db::repositories::{CommentRepository, Repository};
 use std::sync::Arc;
 
-/// 将 Markdown 转换为 HTML
-fn convert_markdown_to_html(markdown: &str) -> String {
-    use pulldown_cmark::{Parser, html, Options};
-
-    let mut options = Options::empty();
-    options.insert(Options::ENABLE_TABLES);
-    options.insert(Options::ENABLE_STRIKETHROUGH);
-    options.insert(Options::ENABLE_TASKLISTS);
-
-    let parser = Parser::new_ext(markdown, options);
-    let mut html_output = String::new();
-    html::push_html(&mut html_output, parser);
-
-    html_output
-}
-
 /// 评论列表请求参数
 #[derive(Debug, Deserialize)]
 pub struct CommentListQuery {
@@ -118,7 +102,7 @@ pub async fn create(
     let comment_repo = CommentRepository::new(repo.get_pool().clone());
 
     // 将 Markdown 转换为 HTML
-    let html_content = convert_markdown_to_html(&req.content);
+    let html_content = crate::markdown::render(&req.content, crate::markdown::Profile::Comment);
 
     let comment = crate::db::models::Comment {
         id: None,
@@ -0,0 +1,216 @@
+use actix_multipart::Multipart;
+use actix_web::{web, HttpRequest, HttpResponse};
+use futures_util::stream::StreamExt;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use crate::db::repositories::{PassageRepository, Repository};
+
+/// 上传的导入源暂存目录，确认导入或放弃后删除
+const IMPORT_DIR: &str = "data/imports";
+
+/// 单次上传的总大小上限（512MB）
+const MAX_UPLOAD_BYTES: usize = 512 * 1024 * 1024;
+
+/// 上传导入源并返回预览报告
+///
+/// 可以上传 WordPress 导出的 XML 文件（可附带 uploads 目录中的文件），或整个 Hexo、Hugo、Jekyll 站点目录；
+/// 文件名中的相对路径（浏览器上传文件夹时的 webkitRelativePath）会按原样还原
+pub async fn upload(
+    repo: web::Data<Arc<dyn Repository>>,
+    mut payload: Multipart,
+    req: HttpRequest,
+) -> HttpResponse {
+    if req.cookie("auth_token").is_none() {
+        return crate::middleware::auth::missing_token_response();
+    }
+    if crate::middleware::auth::check_admin_auth(&req).is_none() {
+        return crate::middleware::auth::forbidden_response();
+    }
+
+    let import_id = crate::id_generator::generate_unique_id();
+    let dir = import_dir(&import_id);
+    let mut total = 0usize;
+    let mut files = 0usize;
+
+    while let Some(field) = payload.next().await {
+        let mut field = match field {
+            Ok(field) => field,
+            Err(e) => {
+                remove_dir(&dir);
+                return bad_request(&format!("读取上传内容失败: {}", e));
+            }
+        };
+        let Some(filename) = field.content_disposition().and_then(|cd| cd.get_filename().map(str::to_string)) else {
+            continue;
+        };
+        let Some(relative) = safe_relative_path(&filename) else {
+            remove_dir(&dir);
+            return bad_request(&format!("文件路径无效: {}", filename));
+        };
+
+        let mut data = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    remove_dir(&dir);
+                    return bad_request(&format!("读取上传内容失败: {}", e));
+                }
+            };
+            total += chunk.len();
+            if total > MAX_UPLOAD_BYTES {
+                remove_dir(&dir);
+                return bad_request("上传内容超过 512MB");
+            }
+            data.extend_from_slice(&chunk);
+        }
+
+        let path = dir.join(relative);
+        let written = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&path, &data));
+        if let Err(e) = written {
+            eprintln!("保存导入文件失败 {}: {}", path.display(), e);
+            remove_dir(&dir);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "message": "保存上传文件失败"
+            }));
+        }
+        files += 1;
+    }
+
+    if files == 0 {
+        return bad_request("没有上传文件");
+    }
+
+    let passage_repo = PassageRepository::new(repo.get_pool().clone());
+    match preview_or_apply(&dir, &passage_repo, "", true).await {
+        Ok(report) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "message": "已生成导入预览，确认后调用 apply 执行导入",
+            "data": {
+                "import_id": import_id,
+                "report": report
+            }
+        })),
+        Err(message) => {
+            remove_dir(&dir);
+            bad_request(&message)
+        }
+    }
+}
+
+/// 按预览时上传的导入源执行导入
+pub async fn apply(
+    repo: web::Data<Arc<dyn Repository>>,
+    path: web::Path<String>,
+    req: HttpRequest,
+) -> HttpResponse {
+    if req.cookie("auth_token").is_none() {
+        return crate::middleware::auth::missing_token_response();
+    }
+    let Some((_, username, _)) = crate::middleware::auth::check_admin_auth(&req) else {
+        return crate::middleware::auth::forbidden_response();
+    };
+
+    let Some(dir) = existing_import_dir(&path.into_inner()) else {
+        return not_found();
+    };
+
+    let passage_repo = PassageRepository::new(repo.get_pool().clone());
+    match preview_or_apply(&dir, &passage_repo, &username, false).await {
+        Ok(report) => {
+            remove_dir(&dir);
+            HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "message": format!("导入完成: {} 篇文章", report.created),
+                "data": report
+            }))
+        }
+        Err(message) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "success": false,
+            "message": message
+        })),
+    }
+}
+
+/// 放弃导入，删除上传的导入源
+pub async fn discard(path: web::Path<String>, req: HttpRequest) -> HttpResponse {
+    if req.cookie("auth_token").is_none() {
+        return crate::middleware::auth::missing_token_response();
+    }
+    if crate::middleware::auth::check_admin_auth(&req).is_none() {
+        return crate::middleware::auth::forbidden_response();
+    }
+
+    let Some(dir) = existing_import_dir(&path.into_inner()) else {
+        return not_found();
+    };
+    remove_dir(&dir);
+    HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "message": "已放弃导入"
+    }))
+}
+
+async fn preview_or_apply(
+    dir: &Path,
+    passage_repo: &PassageRepository,
+    editor: &str,
+    dry_run: bool,
+) -> Result<crate::import::ImportReport, String> {
+    let plan = crate::import::scan(dir)?;
+    crate::import::run(&plan, passage_repo, editor, dry_run).await
+}
+
+fn import_dir(import_id: &str) -> PathBuf {
+    Path::new(IMPORT_DIR).join(import_id)
+}
+
+/// 导入 ID 只能是数字，避免拼接出其他目录
+fn existing_import_dir(import_id: &str) -> Option<PathBuf> {
+    if import_id.is_empty() || !import_id.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let dir = import_dir(import_id);
+    dir.is_dir().then_some(dir)
+}
+
+/// 上传文件名中的相对路径：只允许普通的路径段，拒绝绝对路径和 `..`
+fn safe_relative_path(filename: &str) -> Option<PathBuf> {
+    let normalized = filename.replace('\\', "/");
+    let path = Path::new(&normalized);
+    let mut relative = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!relative.as_os_str().is_empty()).then_some(relative)
+}
+
+fn remove_dir(dir: &Path) {
+    if let Err(e) = std::fs::remove_dir_all(dir) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            eprintln!("删除导入目录失败 {}: {}", dir.display(), e);
+        }
+    }
+}
+
+fn bad_request(message: &str) -> HttpResponse {
+    HttpResponse::BadRequest().json(serde_json::json!({
+        "success": false,
+        "message": message
+    }))
+}
+
+fn not_found() -> HttpResponse {
+    HttpResponse::NotFound().json(serde_json::json!({
+        "success": false,
+        "message": "导入任务不存在或已完成"
+    }))
+}
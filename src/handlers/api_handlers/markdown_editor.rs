@@ -1,9 +1,8 @@
 use actix_web::{web, HttpResponse, HttpRequest};
 use serde::{Deserialize, Serialize};
-use crate::db::repositories::{PassageRepository, Repository};
+use crate::db::repositories::{AuthorRepository, PassageRepository, PassageRevisionRepository, Repository};
 use std::sync::Arc;
 use std::fs;
-use std::path::Path;
 use chrono::Utc;
 
 /// 保存文章请求
@@ -72,22 +71,19 @@ pub async fn save(
         });
     }
     
-    // 转换 Markdown 为 HTML
-    let html_content = convert_markdown_to_html(&req_data.content);
-    
     // 构建文件路径（按日期组织）
     let now = Utc::now();
     let date_dir = now.format("%Y/%m/%d").to_string();
-    let file_path = format!("markdown/{}/{}.md", date_dir, req_data.title);
-    
-    // 保存 Markdown 文件到磁盘
-    if let Err(e) = save_markdown_file(&file_path, &req_data.title, &req_data.content) {
-        return HttpResponse::Ok().json(SaveArticleResponse {
-            success: false,
-            message: format!("保存Markdown文件失败: {}", e),
-            data: None,
-        });
-    }
+    let file_path = match super::passage::resolve_markdown_path(&format!("markdown/{}/{}.md", date_dir, req_data.title)) {
+        Ok((_, file_path)) => file_path,
+        Err(e) => {
+            return HttpResponse::Ok().json(SaveArticleResponse {
+                success: false,
+                message: e,
+                data: None,
+            });
+        }
+    };
     
     // 处理标签（转换为JSON格式）
     
@@ -152,14 +148,24 @@ pub async fn save(
         
     
         // 创建文章记录
-    let passage = crate::db::models::Passage {
+    let passage_repo = PassageRepository::new(repo.get_pool().clone());
+    // 编辑器保存的文章归属当前登录用户
+    let author = match crate::middleware::auth::current_user(&http_req) {
+        Some((user_id, _, _)) => AuthorRepository::new(repo.get_pool().clone()).get_by_user_id(user_id).await.ok(),
+        None => None,
+    };
+    // 转换 Markdown 为 HTML，作者不是管理员时需要清理
+    let trusted = passage_repo.is_trusted_author(author.as_ref().map(|a| a.user_id)).await;
+    let html_content = crate::markdown::render_passage(&req_data.content, trusted);
+    let reading = crate::reading::stats(&html_content);
+    let mut passage = crate::db::models::Passage {
         id: None,
         uuid: None,
         title: req_data.title.clone(),
         content: html_content,
-        original_content: Some(req_data.content),
+        original_content: Some(req_data.content.clone()),
         summary: Some(summary),
-        author: "admin".to_string(),
+        author: author.as_ref().map_or_else(|| "admin".to_string(), |a| a.display_name.clone()),
         tags: tags_json,
         category,
         status: "published".to_string(),
@@ -170,21 +176,55 @@ pub async fn save(
         cover_image: Some("/img/passage-cover.webp".to_string()),
         created_at: now,
         updated_at: now,
+        expires_at: None,
+        slug: None,
+        word_count: reading.word_count,
+        reading_minutes: reading.reading_minutes,
+        author_id: author.as_ref().map(|a| a.user_id),
+        pinned: false,
+        featured: false,
+        pin_order: 0,
     };
     
-    let passage_repo = PassageRepository::new(repo.get_pool().clone());
+    // 根据标题生成 slug，写入 front matter
+    match passage_repo.unique_slug(&passage.title, None).await {
+        Ok(slug) => passage.slug = Some(slug),
+        Err(e) => eprintln!("生成 slug 失败: {}", e),
+    }
+    
+    // 保存 Markdown 文件到磁盘（带 front matter）
+    if let Err(e) = save_markdown_file(&file_path, &passage, req_data.summary.is_some()) {
+        return HttpResponse::Ok().json(SaveArticleResponse {
+            success: false,
+            message: format!("保存Markdown文件失败: {}", e),
+            data: None,
+        });
+    }
     
     match passage_repo.create(&passage).await {
-        Ok(_) => HttpResponse::Ok().json(SaveArticleResponse {
-            success: true,
-            message: "文章保存成功".to_string(),
-            data: Some(ArticleData {
-                id: passage.id.unwrap_or(0),
-                title: passage.title,
-                file_path,
-                created_at: passage.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
-            }),
-        }),
+        Ok(id) => {
+            // 记录首个修订
+            if let Ok(created) = passage_repo.get_by_id(id).await {
+                let editor = crate::middleware::auth::current_user(&http_req)
+                    .map(|(_, username, _)| username)
+                    .unwrap_or_else(|| passage.author.clone());
+                let revision_repo = PassageRevisionRepository::new(repo.get_pool().clone());
+                if let Err(e) = revision_repo.record_change(None, &created, &editor, "editor").await {
+                    eprintln!("记录文章修订失败: {}", e);
+                }
+            }
+
+            HttpResponse::Ok().json(SaveArticleResponse {
+                success: true,
+                message: "文章保存成功".to_string(),
+                data: Some(ArticleData {
+                    id,
+                    title: passage.title,
+                    file_path,
+                    created_at: passage.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                }),
+            })
+        }
         Err(e) => HttpResponse::Ok().json(SaveArticleResponse {
             success: false,
             message: format!("保存到数据库失败: {}", e),
@@ -193,38 +233,32 @@ pub async fn save(
     }
 }
 
-/// 保存 Markdown 文件
-fn save_markdown_file(file_path: &str, _title: &str, content: &str) -> Result<(), String> {
+/// 保存 Markdown 文件（正文前写入 front matter）
+fn save_markdown_file(file_path: &str, passage: &crate::db::models::Passage, keep_summary: bool) -> Result<(), String> {
+    use crate::front_matter::{self, FrontMatter};
+
+    let mut meta = FrontMatter::from_passage(passage);
+    // 未填写摘要时不写入默认摘要
+    if !keep_summary {
+        meta.summary = None;
+    }
+    let content = front_matter::render(&meta, passage.original_content.as_deref().unwrap_or(""))?;
+
     // 创建目录
-    if let Some(parent) = Path::new(file_path).parent() {
+    let (path, stored) = super::passage::resolve_markdown_path(file_path)?;
+    if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)
             .map_err(|e| format!("创建目录失败: {}", e))?;
     }
     
-    // 写入文件
-    fs::write(file_path, content)
+    // 写入文件（数据库由调用方保存，文件监听不再重复同步）
+    fs::write(&path, content)
         .map_err(|e| format!("写入文件失败: {}", e))?;
+    crate::watcher::record_own_write(&stored);
     
     Ok(())
 }
 
-/// 将 Markdown 转换为 HTML
-fn convert_markdown_to_html(markdown: &str) -> String {
-    use pulldown_cmark::{Parser, html, Options};
-
-    // 配置选项，启用表格和其他扩展
-    let mut options = Options::empty();
-    options.insert(Options::ENABLE_TABLES);
-    options.insert(Options::ENABLE_STRIKETHROUGH);
-    options.insert(Options::ENABLE_TASKLISTS);
-
-    let parser = Parser::new_ext(markdown, options);
-    let mut html_output = String::new();
-    html::push_html(&mut html_output, parser);
-
-    html_output
-}
-
 /// 确保标签存在于 tags 表中
 async fn ensure_tags_exist(tag_names: &[String]) -> Result<(), String> {
     use crate::db::get_db_pool_sync;
@@ -19,4 +19,13 @@ pub mod stats;
 pub mod db_stats;
 pub mod markdown_preview;
 pub mod friend_link;
-pub mod admin_friend_link;
\ No newline at end of file
+pub mod admin_friend_link;
+pub mod search;
+pub mod revision;
+pub mod preview;
+pub mod series;
+pub mod related;
+pub mod authors;
+pub mod import;
+pub mod backup;
+
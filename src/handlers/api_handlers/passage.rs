@@ -1,6 +1,6 @@
 use actix_web::{web, HttpResponse, HttpRequest, HttpMessage};
 use serde::{Deserialize, Serialize};
-use crate::db::repositories::{PassageRepository, AttachmentRepository, Repository};
+use crate::db::repositories::{AuthorRepository, PassageRepository, PassageRevisionRepository, AttachmentRepository, PublishedFilter, Repository};
 use crate::db::models::Passage;
 use crate::view_batch::{ViewBatchProcessor, ViewRecord, is_local_ip};
 use std::sync::Arc;
@@ -26,6 +26,60 @@ pub struct PassageResponse {
     pub cover_image: Option<String>,  // 封面图片路径
     pub created_at: String,
     pub updated_at: String,
+    pub expires_at: Option<String>,  // 定时下线时间
+    pub slug: Option<String>,
+    pub word_count: i64,  // 正文字数
+    pub reading_minutes: i64,  // 预计阅读分钟数
+    pub author_id: Option<i64>,  // 作者对应的用户 ID
+    pub pinned: bool,  // 是否置顶
+    pub featured: bool,  // 是否推荐
+    pub pin_order: i64,  // 置顶、推荐文章的手动排序
+    pub permalink: Option<String>,  // 规范链接 /passage/{yyyy}/{mm}/{dd}/{slug}
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub series: Option<super::series::SeriesNavigation>,  // 所属系列及上一篇、下一篇（仅详情返回）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub toc: Option<Vec<crate::markdown::TocEntry>>,  // 文章目录（仅详情返回）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub breadcrumb: Option<Vec<BreadcrumbItem>>,  // 从顶级分类到所属分类的路径（仅详情返回）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author_profile: Option<crate::db::models::AuthorProfile>,  // 作者公开资料（仅详情返回）
+}
+
+/// 未解锁的需要密码的文章（只返回标题和摘要）
+#[derive(Debug, Serialize)]
+pub struct LockedPassageResponse {
+    pub id: i64,
+    pub uuid: String,
+    pub title: String,
+    pub summary: Option<String>,
+    pub permalink: Option<String>,
+}
+
+/// 面包屑中的一级分类
+#[derive(Debug, Serialize)]
+pub struct BreadcrumbItem {
+    pub id: Option<i64>,  // 只存在于文章中的分类没有 ID
+    pub name: String,
+    pub url: String,
+}
+
+/// 生成文章所属分类的面包屑，分类表中没有记录时只包含分类本身
+async fn breadcrumb_for(repo: &Arc<dyn Repository>, category: &str) -> Vec<BreadcrumbItem> {
+    if category.is_empty() {
+        return Vec::new();
+    }
+    let category_repo = crate::db::repositories::CategoryRepository::new(repo.get_pool().clone());
+    let path = match category_repo.get_path(category).await {
+        Ok(path) if !path.is_empty() => path.into_iter().map(|(id, name)| (Some(id), name)).collect(),
+        _ => vec![(None, category.to_string())],
+    };
+    path.into_iter()
+        .map(|(id, name)| BreadcrumbItem {
+            url: format!("/category/{}", urlencoding::encode(&name)),
+            id,
+            name,
+        })
+        .collect()
 }
 
 /// 创建文章请求
@@ -44,6 +98,12 @@ pub struct CreatePassageRequest {
     pub published_at: Option<String>,
     pub cover_image: Option<String>,  // 封面图片路径
     pub created_at: Option<String>,  // 创建时间（可选，用于上传老文件时指定）
+    pub expires_at: Option<String>,  // 定时下线时间（可选）
+    pub slug: Option<String>,  // 永久链接 slug（可选，默认根据标题生成）
+    pub author_id: Option<i64>,  // 作者用户 ID（仅管理员可以指定其他用户）
+    pub pinned: Option<bool>,  // 置顶（仅管理员）
+    pub featured: Option<bool>,  // 推荐到首页头图区（仅管理员）
+    pub password: Option<String>,  // 访问密码（visibility 为 password 时必填，空字符串清除）
 }
 
 /// 更新文章请求
@@ -62,6 +122,139 @@ pub struct UpdatePassageRequest {
     pub is_scheduled: Option<bool>,
     pub published_at: Option<String>,
     pub cover_image: Option<String>,  // 封面图片路径
+    pub expires_at: Option<String>,  // 定时下线时间，传空字符串取消
+    pub slug: Option<String>,  // 修改后旧 slug 保留为跳转
+    pub author_id: Option<i64>,  // 更换作者（仅管理员）
+    pub pinned: Option<bool>,  // 置顶（仅管理员）
+    pub featured: Option<bool>,  // 推荐到首页头图区（仅管理员）
+    pub password: Option<String>,  // 访问密码（visibility 为 password 时必填，空字符串清除）
+}
+
+/// 确定新文章的作者：登录用户默认为自己，管理员可以通过 author_id 指定其他用户，未登录时拒绝
+async fn resolve_author(
+    repo: &Arc<dyn Repository>,
+    http_req: &HttpRequest,
+    requested: Option<i64>,
+) -> Result<crate::db::models::AuthorProfile, HttpResponse> {
+    let Some((user_id, _, role)) = crate::middleware::auth::current_user(http_req) else {
+        return Err(crate::middleware::auth::missing_token_response());
+    };
+    let author_id = requested.unwrap_or(user_id);
+    if author_id != user_id && role != "admin" {
+        return Err(HttpResponse::Forbidden().json(serde_json::json!({
+            "success": false,
+            "message": "只有管理员可以指定其他作者"
+        })));
+    }
+
+    match AuthorRepository::new(repo.get_pool().clone()).get_by_user_id(author_id).await {
+        Ok(profile) => Ok(profile),
+        Err(_) => Err(HttpResponse::BadRequest().json(serde_json::json!({
+            "success": false,
+            "message": "作者不存在"
+        }))),
+    }
+}
+
+/// 检查当前用户能否编辑文章：管理员可以编辑全部文章，其他作者只能编辑自己的文章
+fn check_edit_permission(http_req: &HttpRequest, passage: &Passage) -> Result<(), HttpResponse> {
+    let Some((user_id, _, role)) = crate::middleware::auth::current_user(http_req) else {
+        return Err(crate::middleware::auth::missing_token_response());
+    };
+    if role == "admin" || passage.author_id == Some(user_id) {
+        Ok(())
+    } else {
+        Err(HttpResponse::Forbidden().json(serde_json::json!({
+            "success": false,
+            "message": "只能编辑自己的文章"
+        })))
+    }
+}
+
+/// 列表中的文章（返回原始 Markdown 和摘要，不返回 HTML 内容）
+fn list_item(p: Passage) -> PassageResponse {
+    let locked = p.is_password_protected();
+    let summary = if locked { p.locked_summary() } else { p.summary.clone() };
+    PassageResponse {
+        permalink: p.permalink(),
+        id: p.id.unwrap_or(0),
+        uuid: p.uuid.unwrap_or_default(),
+        title: p.title,
+        // 返回原始 Markdown 内容，需要密码的文章不返回正文
+        content: if locked { String::new() } else { p.original_content.unwrap_or_default() },
+        html_content: None, // 列表不返回 HTML 内容
+        summary,
+        author: p.author,
+        tags: p.tags,
+        category: p.category,
+        status: p.status,
+        file_path: p.file_path,
+        visibility: p.visibility,
+        is_scheduled: p.is_scheduled,
+        published_at: p.published_at.map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string()),
+        cover_image: p.cover_image,
+        created_at: p.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        updated_at: p.updated_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        expires_at: p.expires_at.map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string()),
+        slug: p.slug,
+        word_count: p.word_count,
+        reading_minutes: p.reading_minutes,
+        author_id: p.author_id,
+        pinned: p.pinned,
+        featured: p.featured,
+        pin_order: p.pin_order,
+        series: None,
+        toc: None,
+        breadcrumb: None,
+        author_profile: None,
+    }
+}
+
+/// 解锁需要密码的文章后，解锁 Cookie 的有效期（分钟）
+const UNLOCK_MINUTES: i64 = 30;
+
+/// 解锁 Cookie 名称，每篇文章单独一个
+fn unlock_cookie_name(uuid: &str) -> String {
+    format!("passage_unlock_{}", uuid)
+}
+
+/// 当前请求是否持有该文章有效的解锁令牌
+pub(crate) fn is_unlocked(req: &HttpRequest, uuid: &str) -> bool {
+    req.cookie(&unlock_cookie_name(uuid))
+        .and_then(|cookie| crate::jwt::validate_unlock_token(cookie.value()).ok())
+        .is_some_and(|claims| claims.sub == uuid)
+}
+
+/// 当前登录用户是否为文章作者（作者本人可以查看自己未发布或不公开的文章）
+pub(crate) fn is_owner(req: &HttpRequest, passage: &crate::db::models::Passage) -> bool {
+    passage.author_id.is_some()
+        && crate::middleware::auth::current_user(req).map(|(id, _, _)| id) == passage.author_id
+}
+
+/// 计算要保存的访问密码哈希：提供密码时重新哈希，空字符串清除，未提供时保留原值
+///
+/// 需要密码访问的文章必须设置访问密码
+fn resolve_password_hash(visibility: &str, password: Option<&str>, existing: Option<String>) -> Result<Option<String>, HttpResponse> {
+    let hash = match password {
+        Some("") => None,
+        Some(password) => match super::user::hash_password(password) {
+            Ok(hash) => Some(hash),
+            Err(e) => {
+                return Err(HttpResponse::InternalServerError().json(serde_json::json!({
+                    "success": false,
+                    "message": e
+                })));
+            }
+        },
+        None => existing,
+    };
+    if visibility == "password" && hash.is_none() {
+        return Err(HttpResponse::BadRequest().json(serde_json::json!({
+            "success": false,
+            "message": "需要密码访问的文章必须设置访问密码"
+        })));
+    }
+    Ok(hash)
 }
 
 /// 获取文章列表（公开）
@@ -75,36 +268,30 @@ pub async fn list(
     let limit: i64 = query.get("limit").and_then(|l| l.parse().ok()).unwrap_or(10);
     let page: i64 = query.get("page").and_then(|p| p.parse().ok()).unwrap_or(1);
     let offset = (page - 1) * limit;
+    // 按标签、分类筛选（recursive=1 时包含子分类）
+    let filter = PublishedFilter {
+        tag: query.get("tag").map(|t| t.trim()).filter(|t| !t.is_empty()),
+        category: query.get("category").map(|c| c.trim()).filter(|c| !c.is_empty()),
+        recursive: matches!(query.get("recursive").map(String::as_str), Some("1" | "true")),
+        author: query.get("author").map(|a| a.trim()).filter(|a| !a.is_empty()),
+    };
     
-    // 获取已发布的文章（不包含完整内容，只返回摘要）
-    match passage_repo.get_published(limit, offset).await {
+    // 获取已发布的文章（不包含完整内容，只返回摘要），不筛选时置顶文章排在最前
+    let passages = if filter == PublishedFilter::default() {
+        passage_repo.get_published(limit, offset).await
+    } else {
+        passage_repo.get_published_filtered(&filter, limit, offset).await
+    };
+    match passages {
         Ok(passages) => {
             // 获取总数
-            let total = match passage_repo.count_published().await {
+            let total = match passage_repo.count_published_filtered(&filter).await {
                 Ok(c) => c,
                 Err(_) => passages.len() as i64,
             };
             
             let data: Vec<PassageResponse> = passages.into_iter()
-                .map(|p| PassageResponse {
-                    id: p.id.unwrap_or(0),
-                    uuid: p.uuid.unwrap_or_default(),
-                    title: p.title,
-                    content: p.original_content.unwrap_or_default(), // 返回原始 Markdown 内容
-                    html_content: None, // 列表不返回 HTML 内容
-                    summary: p.summary,
-                    author: p.author,
-                    tags: p.tags,
-                    category: p.category,
-                    status: p.status,
-                    file_path: p.file_path,
-                    visibility: p.visibility,
-                    is_scheduled: p.is_scheduled,
-                    published_at: p.published_at.map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string()),
-                    cover_image: p.cover_image,
-                    created_at: p.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
-                    updated_at: p.updated_at.format("%Y-%m-%d %H:%M:%S").to_string(),
-                })
+                .map(list_item)
                 .collect();
             
             let total_pages = (total + limit - 1) / limit;
@@ -133,6 +320,75 @@ pub async fn list(
     }
 }
 
+/// 默认返回的推荐文章数量
+const DEFAULT_FEATURED_LIMIT: i64 = 5;
+
+/// 获取首页头图区的推荐文章（公开）
+pub async fn featured(
+    repo: web::Data<Arc<dyn Repository>>,
+    query: web::Query<std::collections::HashMap<String, String>>,
+) -> HttpResponse {
+    let passage_repo = PassageRepository::new(repo.get_pool().clone());
+    let limit: i64 = query.get("limit").and_then(|l| l.parse().ok()).unwrap_or(DEFAULT_FEATURED_LIMIT).clamp(1, 50);
+
+    match passage_repo.get_featured(limit).await {
+        Ok(passages) => {
+            let data: Vec<PassageResponse> = passages.into_iter().map(list_item).collect();
+            HttpResponse::Ok()
+                .insert_header(("Cache-Control", "public, max-age=60"))
+                .json(serde_json::json!({
+                    "success": true,
+                    "data": data
+                }))
+        }
+        Err(e) => {
+            eprintln!("获取推荐文章失败: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "message": "获取推荐文章失败"
+            }))
+        }
+    }
+}
+
+/// 调整置顶、推荐文章顺序请求
+#[derive(Debug, Deserialize)]
+pub struct ReorderPinnedRequest {
+    pub ids: Vec<i64>,  // 按显示顺序排列的文章 ID
+}
+
+/// 调整置顶、推荐文章的顺序（管理员）
+pub async fn reorder_pinned(
+    repo: web::Data<Arc<dyn Repository>>,
+    req: web::Json<ReorderPinnedRequest>,
+    http_req: HttpRequest,
+) -> HttpResponse {
+    if http_req.cookie("auth_token").is_none() {
+        return crate::middleware::auth::missing_token_response();
+    }
+    if crate::middleware::auth::check_admin_auth(&http_req).is_none() {
+        return crate::middleware::auth::forbidden_response();
+    }
+    if req.ids.is_empty() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "success": false,
+            "message": "文章ID列表不能为空"
+        }));
+    }
+
+    let passage_repo = PassageRepository::new(repo.get_pool().clone());
+    match passage_repo.reorder_pinned(&req.ids).await {
+        Ok(_) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "message": "排序已更新"
+        })),
+        Err(e) => HttpResponse::BadRequest().json(serde_json::json!({
+            "success": false,
+            "message": e.to_string()
+        })),
+    }
+}
+
 /// 获取单篇文章
 pub async fn get(
     repo: web::Data<Arc<dyn Repository>>,
@@ -148,10 +404,15 @@ pub async fn get(
         .map(|r| r.0.clone())
         .unwrap_or_else(|| String::new());
     
-    // 智能识别：如果是纯数字，则按 ID 查询；否则按 UUID 查询
+    // 智能识别：如果是纯数字，则按 ID 查询；否则按 slug 或 UUID 查询
     let passage = if let Ok(id) = param.parse::<i64>() {
-        // 数字 ID 查询
-        match passage_repo.get_by_id(id).await {
+        // 数字 ID 查询，找不到时按 slug 查询（兼容之前生成的纯数字 slug）
+        let by_id = passage_repo.get_by_id(id).await.ok();
+        let result = match by_id {
+            Some(p) => Ok(p),
+            None => passage_repo.get_by_slug(&param).await,
+        };
+        match result {
             Ok(p) => p,
             Err(e) => {
                 eprintln!("获取文章失败: {}", e);
@@ -162,8 +423,13 @@ pub async fn get(
             }
         }
     } else {
-        // UUID 查询
-        match passage_repo.get_by_uuid(&param).await {
+        // slug 查询，找不到时按 UUID 查询
+        let by_slug = passage_repo.get_by_slug(&param).await.ok();
+        let result = match by_slug {
+            Some(p) => Ok(p),
+            None => passage_repo.get_by_uuid(&param).await,
+        };
+        match result {
             Ok(p) => p,
             Err(e) => {
                 eprintln!("获取文章失败: {}", e);
@@ -175,9 +441,20 @@ pub async fn get(
         }
     };
     
+    // 预览链接：持有该文章的有效预览令牌时，和管理员一样跳过状态和可见性检查
+    let preview_token = web::Query::<std::collections::HashMap<String, String>>::from_query(req.query_string())
+        .ok()
+        .and_then(|q| q.get("preview").cloned());
+    let is_preview = match (preview_token, passage.uuid.as_deref()) {
+        (Some(token), Some(uuid)) => super::preview::verify_token(&repo, &token, uuid).await,
+        _ => false,
+    };
+    // 作者本人也可以查看自己未发布的文章
+    let can_bypass = role == "admin" || is_preview || is_owner(&req, &passage);
+
     // 检查文章状态和可见性
     if passage.status != "published" {
-        if role != "admin" {
+        if !can_bypass {
             return HttpResponse::Ok().json(serde_json::json!({
                 "success": false,
                 "message": "文章未发布",
@@ -186,8 +463,8 @@ pub async fn get(
         }
     }
     
-    if passage.visibility != "public" {
-        if role != "admin" {
+    if passage.visibility != "public" && !passage.is_password_protected() {
+        if !can_bypass {
             return HttpResponse::Ok().json(serde_json::json!({
                 "success": false,
                 "message": "文章不可见",
@@ -198,7 +475,7 @@ pub async fn get(
     
     if passage.is_scheduled {
         if let Some(published_at) = passage.published_at {
-            if published_at > Utc::now() && role != "admin" {
+            if published_at > Utc::now() && !can_bypass {
                 return HttpResponse::Ok().json(serde_json::json!({
                     "success": false,
                     "message": "文章尚未发布",
@@ -208,7 +485,36 @@ pub async fn get(
             }
         }
     }
+
+    if let Some(expires_at) = passage.expires_at {
+        if expires_at <= Utc::now() && !can_bypass {
+            return HttpResponse::Ok().json(serde_json::json!({
+                "success": false,
+                "message": "文章已下线",
+                "expires_at": expires_at.format("%Y-%m-%d %H:%M:%S").to_string()
+            }));
+        }
+    }
     
+    // 需要密码的文章：解锁前只返回标题和摘要
+    let is_protected = passage.is_password_protected();
+    if is_protected && !can_bypass && !passage.uuid.as_deref().is_some_and(|uuid| is_unlocked(&req, uuid)) {
+        return HttpResponse::Ok()
+            .insert_header(("Cache-Control", "private, no-store"))
+            .json(serde_json::json!({
+                "success": false,
+                "message": "文章需要密码访问",
+                "locked": true,
+                "data": LockedPassageResponse {
+                    permalink: passage.permalink(),
+                    summary: passage.locked_summary(),
+                    id: passage.id.unwrap_or(0),
+                    uuid: passage.uuid.unwrap_or_default(),
+                    title: passage.title,
+                }
+            }));
+    }
+
     // 使用批量处理器记录文章阅读（不阻塞响应）
     let passage_uuid = passage.uuid.clone().unwrap_or_default();
     let user_agent = req.headers().get("user-agent")
@@ -219,8 +525,8 @@ pub async fn get(
     // 获取客户端IP（简化版）
     let ip = "127.0.0.1".to_string(); // TODO: 从请求中获取真实IP
 
-    // 过滤本地IP，不记录
-    if !is_local_ip(&ip) {
+    // 过滤本地IP和预览访问，不记录
+    if !is_local_ip(&ip) && !is_preview {
         // 使用 GeoIP 获取地理位置信息
         let geo_location = crate::geoip::lookup_ip(&ip);
         let country = geo_location.country;
@@ -243,7 +549,19 @@ pub async fn get(
         }
     }
     
+    let series = super::series::navigation_for(&repo, &passage).await;
+    let breadcrumb = breadcrumb_for(&repo, &passage.category).await;
+    let author_profile = match passage.author_id {
+        Some(user_id) => AuthorRepository::new(repo.get_pool().clone()).get_by_user_id(user_id).await.ok(),
+        None => None,
+    };
+
     let response = PassageResponse {
+        series,
+        toc: Some(crate::markdown::toc(&passage.content)),
+        breadcrumb: Some(breadcrumb),
+        author_profile,
+        permalink: passage.permalink(),
         id: passage.id.unwrap_or(0),
         uuid: passage.uuid.unwrap_or_default(),
         title: passage.title,
@@ -261,11 +579,22 @@ pub async fn get(
         cover_image: passage.cover_image,
         created_at: passage.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
         updated_at: passage.updated_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        expires_at: passage.expires_at.map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string()),
+        slug: passage.slug,
+        word_count: passage.word_count,
+        reading_minutes: passage.reading_minutes,
+        author_id: passage.author_id,
+        pinned: passage.pinned,
+        featured: passage.featured,
+        pin_order: passage.pin_order,
     };
     
     // 生成 ETag
     use md5::{Md5, Digest};
-    let etag_data = format!("{}:{}", response.id, response.updated_at);
+    // 系列信息（上一篇、下一篇）和分类路径变化时也需要刷新
+    let series_data = serde_json::to_string(&response.series).unwrap_or_default();
+    let breadcrumb_data = serde_json::to_string(&response.breadcrumb).unwrap_or_default();
+    let etag_data = format!("{}:{}:{}:{}", response.id, response.updated_at, series_data, breadcrumb_data);
     let etag = format!("\"{:x}\"", Md5::digest(etag_data.as_bytes()));
     
     // 检查 If-None-Match
@@ -279,24 +608,117 @@ pub async fn get(
         }
     }
     
+    // 预览内容和需要密码的文章不能进入共享缓存
+    let cache_control = if is_preview || is_protected { "private, no-store" } else { "public, max-age=300" }; // 文章缓存 5 分钟
+
     HttpResponse::Ok()
         .insert_header(("ETag", etag))
-        .insert_header(("Cache-Control", "public, max-age=300")) // 文章缓存 5 分钟
+        .insert_header(("Cache-Control", cache_control))
         .json(serde_json::json!({
             "success": true,
             "data": response
         }))
 }
 
+/// 解锁文章请求：访问密码通过 ECC 会话加密传输（与登录相同）
+#[derive(Debug, Deserialize)]
+pub struct UnlockPassageRequest {
+    pub encrypted_password: String,
+    pub session_id: String,
+    pub client_public_key: String,
+}
+
+/// 解锁需要密码的文章，成功后下发只对该文章有效的短期 Cookie
+pub async fn unlock(
+    _rate_limit: crate::middleware::ratelimit::RateLimitCheck,
+    repo: web::Data<Arc<dyn Repository>>,
+    path: web::Path<String>,
+    req: web::Json<UnlockPassageRequest>,
+) -> HttpResponse {
+    let param = path.into_inner();
+    let passage_repo = PassageRepository::new(repo.get_pool().clone());
+    let passage = match passage_repo.get_by_uuid(&param).await {
+        Ok(p) => Some(p),
+        Err(_) => passage_repo.get_by_slug(&param).await.ok(),
+    };
+    let Some(passage) = passage.filter(|p| p.is_password_protected() && p.is_live()) else {
+        return HttpResponse::NotFound().json(serde_json::json!({
+            "success": false,
+            "message": "文章不存在"
+        }));
+    };
+    let (Some(id), Some(uuid)) = (passage.id, passage.uuid.as_deref()) else {
+        return HttpResponse::NotFound().json(serde_json::json!({
+            "success": false,
+            "message": "文章不存在"
+        }));
+    };
+
+    let password = match super::auth::decrypt_password(&req.encrypted_password, &req.session_id, &req.client_public_key) {
+        Ok(password) => password,
+        Err(e) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "success": false,
+                "message": format!("密码解密失败: {}", e)
+            }));
+        }
+    };
+
+    let hash = passage_repo.get_password_hash(id).await.ok().flatten();
+    let verified = hash.is_some_and(|hash| super::auth::verify_password(&password, &hash).unwrap_or(false));
+    if !verified {
+        return HttpResponse::Unauthorized().json(serde_json::json!({
+            "success": false,
+            "message": "访问密码错误"
+        }));
+    }
+
+    let expires_at = Utc::now() + chrono::Duration::minutes(UNLOCK_MINUTES);
+    let token = match crate::jwt::generate_unlock_token(uuid, expires_at) {
+        Ok(token) => token,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "message": format!("生成解锁令牌失败: {}", e)
+            }));
+        }
+    };
+
+    HttpResponse::Ok()
+        .cookie(
+            actix_web::cookie::Cookie::build(unlock_cookie_name(uuid), token)
+                .path("/")
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Lax)
+                .max_age(actix_web::cookie::time::Duration::minutes(UNLOCK_MINUTES))
+                .finish(),
+        )
+        .json(serde_json::json!({
+            "success": true,
+            "message": "文章已解锁",
+            "data": {
+                "uuid": uuid,
+                "expires_at": expires_at.timestamp()
+            }
+        }))
+}
+
 /// 创建文章
 pub async fn create(
     repo: web::Data<Arc<dyn Repository>>,
     req: web::Json<CreatePassageRequest>,
+    http_req: HttpRequest,
 ) -> HttpResponse {
     let passage_repo = PassageRepository::new(repo.get_pool().clone());
     
-    // 转换 Markdown 为 HTML
-    let html_content = convert_markdown_to_html(&req.content);
+    let trusted = crate::middleware::auth::check_admin_auth(&http_req).is_some();
+    let author = match resolve_author(&repo, &http_req, req.author_id).await {
+        Ok(author) => author,
+        Err(response) => return response,
+    };
+    // 转换 Markdown 为 HTML，作者不是管理员时需要清理
+    let author_trusted = passage_repo.is_trusted_author(Some(author.user_id)).await;
+    let html_content = crate::markdown::render_passage(&req.content, author_trusted);
 
     // 处理分类，确保分类存在
     let category_name = req.category.as_deref().unwrap_or("未分类");
@@ -329,8 +751,8 @@ pub async fn create(
     
     let now = Utc::now();
     
-    // 如果没有提供 file_path，则自动生成
-    let file_path = if let Some(ref path) = req.file_path {
+    // 如果没有提供 file_path，则自动生成（只有管理员可以指定文件路径）
+    let file_path = if let Some(path) = req.file_path.as_ref().filter(|_| trusted) {
         path.clone()
     } else {
         // 自动生成文件路径：markdown/YYYY/MM/DD/title.md
@@ -341,18 +763,36 @@ pub async fn create(
             .replace(' ', "-");
         format!("markdown/{}/{}.md", date, safe_title)
     };
-    
-    // 创建 Markdown 文件
-    if let Err(e) = update_markdown_file(&file_path, &req.content) {
-        eprintln!("创建 Markdown 文件失败: {}", e);
-        return HttpResponse::InternalServerError().json(serde_json::json!({
-            "success": false,
-            "message": format!("创建 Markdown 文件失败: {}", e)
-        }));
-    }
+    let file_path = match resolve_markdown_path(&file_path) {
+        Ok((_, file_path)) => file_path,
+        Err(e) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "success": false,
+                "message": e
+            }));
+        }
+    };
     
     // 如果没有提供摘要，则自动生成
-    let summary = req.summary.clone().or_else(|| Some(extract_summary(&html_content)));
+    let summary = req.summary.clone().or_else(|| Some(crate::markdown::extract_summary(&html_content)));
+    
+    // slug：指定时检查是否可用，否则根据标题生成
+    let slug = match &req.slug {
+        Some(requested) => match resolve_requested_slug(&passage_repo, requested, None).await {
+            Ok(slug) => slug,
+            Err(response) => return response,
+        },
+        None => match passage_repo.unique_slug(&req.title, None).await {
+            Ok(slug) => slug,
+            Err(e) => {
+                eprintln!("生成 slug 失败: {}", e);
+                return HttpResponse::InternalServerError().json(serde_json::json!({
+                    "success": false,
+                    "message": "生成 slug 失败"
+                }));
+            }
+        },
+    };
     
     // 如果提供了创建时间，使用指定的；否则使用当前时间
     let created_at = req.created_at.as_ref()
@@ -360,6 +800,13 @@ pub async fn create(
         .map(|dt| dt.with_timezone(&Utc))
         .unwrap_or(now);
     
+    let visibility = req.visibility.clone().unwrap_or_else(|| "public".to_string());
+    let password_hash = match resolve_password_hash(&visibility, req.password.as_deref(), None) {
+        Ok(hash) => hash,
+        Err(response) => return response,
+    };
+
+    let reading = crate::reading::stats(&html_content);
     let passage = Passage {
         id: None,
         uuid: None,  // UUID 将在 Repository 中生成
@@ -367,29 +814,66 @@ pub async fn create(
         content: html_content,
         original_content: Some(req.content.clone()),
         summary: summary,
-        author: req.author.clone().unwrap_or_else(|| "Anonymous".to_string()),
+        // 管理员可以自定义署名，其余登录用户使用自己的显示名
+        author: match &req.author {
+            Some(name) if trusted => name.clone(),
+            _ => author.display_name.clone(),
+        },
+        author_id: Some(author.user_id),
         tags: tags_json,
         category: req.category.clone().unwrap_or_else(|| "未分类".to_string()),
         status: req.status.clone().unwrap_or_else(|| "draft".to_string()),
-        file_path: Some(file_path),
-        visibility: req.visibility.clone().unwrap_or_else(|| "public".to_string()),
+        file_path: Some(file_path.clone()),
+        visibility,
         is_scheduled: req.is_scheduled.unwrap_or(false),
         published_at: req.published_at.as_ref().and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok()).map(|dt| dt.with_timezone(&Utc)),
         cover_image: req.cover_image.clone().or_else(|| Some("/img/passage-cover.webp".to_string())),
         created_at,
         updated_at: now,
+        expires_at: req.expires_at.as_ref().and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok()).map(|dt| dt.with_timezone(&Utc)),
+        slug: Some(slug),
+        word_count: reading.word_count,
+        reading_minutes: reading.reading_minutes,
+        pinned: trusted && req.pinned.unwrap_or(false),
+        featured: trusted && req.featured.unwrap_or(false),
+        pin_order: 0,
     };
     
+    // 创建 Markdown 文件（带 front matter）
+    if let Err(e) = update_markdown_file(&file_path, &passage) {
+        eprintln!("创建 Markdown 文件失败: {}", e);
+        return HttpResponse::InternalServerError().json(serde_json::json!({
+            "success": false,
+            "message": format!("创建 Markdown 文件失败: {}", e)
+        }));
+    }
+    
     match passage_repo.create(&passage).await {
         Ok(id) => {
+            if let Some(hash) = &password_hash {
+                if let Err(e) = passage_repo.set_password_hash(id, Some(hash)).await {
+                    eprintln!("保存访问密码失败: {}", e);
+                }
+            }
             // 获取刚创建的文章信息
             match passage_repo.get_by_id(id).await {
                 Ok(created_passage) => {
+                    // 记录首个修订
+                    let editor = crate::middleware::auth::current_user(&http_req)
+                        .map(|(_, username, _)| username)
+                        .unwrap_or_else(|| created_passage.author.clone());
+                    let revision_repo = PassageRevisionRepository::new(repo.get_pool().clone());
+                    if let Err(e) = revision_repo.record_change(None, &created_passage, &editor, "api").await {
+                        eprintln!("记录文章修订失败: {}", e);
+                    }
+                    
                     HttpResponse::Ok().json(serde_json::json!({
                         "success": true,
                         "message": "文章创建成功",
                         "data": {
                             "id": id,
+                            "permalink": created_passage.permalink(),
+                            "slug": created_passage.slug,
                             "uuid": created_passage.uuid.unwrap_or_else(|| String::new())
                         }
                     }))
@@ -413,11 +897,41 @@ pub async fn create(
     }
 }
 
+/// 规范化请求中的 slug，并检查是否已被其他文章占用
+async fn resolve_requested_slug(
+    passage_repo: &PassageRepository,
+    requested: &str,
+    uuid: Option<&str>,
+) -> Result<String, HttpResponse> {
+    let slug = crate::slug::normalize(requested).ok_or_else(|| {
+        HttpResponse::BadRequest().json(serde_json::json!({
+            "success": false,
+            "message": "slug 至少需要包含一个字母或数字"
+        }))
+    })?;
+
+    match passage_repo.is_slug_available(&slug, uuid).await {
+        Ok(true) => Ok(slug),
+        Ok(false) => Err(HttpResponse::Conflict().json(serde_json::json!({
+            "success": false,
+            "message": format!("slug \"{}\" 已被其他文章使用", slug)
+        }))),
+        Err(e) => {
+            eprintln!("检查 slug 失败: {}", e);
+            Err(HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "message": "检查 slug 失败"
+            })))
+        }
+    }
+}
+
 /// 更新文章
 pub async fn update(
     repo: web::Data<Arc<dyn Repository>>,
     path: web::Path<i64>,
     req: web::Json<UpdatePassageRequest>,
+    http_req: HttpRequest,
 ) -> HttpResponse {
     let id = path.into_inner();
     let passage_repo = PassageRepository::new(repo.get_pool().clone());
@@ -433,52 +947,52 @@ pub async fn update(
             }));
         }
     };
+    if let Err(response) = check_edit_permission(&http_req, &passage) {
+        return response;
+    }
+    let is_admin = crate::middleware::auth::check_admin_auth(&http_req).is_some();
+    
+    // 保存修改前的状态，用于记录修订
+    let previous = passage.clone();
+    
+    // 更换作者（仅管理员），未同时指定署名时使用新作者的显示名
+    if let Some(author_id) = req.author_id {
+        if author_id != passage.author_id.unwrap_or_default() {
+            let profile = match resolve_author(&repo, &http_req, Some(author_id)).await {
+                Ok(profile) => profile,
+                Err(response) => return response,
+            };
+            passage.author_id = Some(profile.user_id);
+            passage.author = profile.display_name;
+        }
+    }
     
     // 更新字段
-    let mut file_updated = false;
     if let Some(ref title) = req.title {
         passage.title = title.clone();
-        file_updated = true;
     }
     if let Some(ref content) = req.content {
-        // 转换 Markdown 为 HTML
-        let html_content = convert_markdown_to_html(content);
-        passage.content = html_content;
+        // 转换 Markdown 为 HTML，作者不是管理员时需要清理
+        let trusted = passage_repo.is_trusted_author(passage.author_id).await;
+        passage.content = crate::markdown::render_passage(content, trusted);
         passage.original_content = Some(content.clone());
-        file_updated = true;
     }
     if let Some(ref original_content) = req.original_content {
         passage.original_content = Some(original_content.clone());
-        file_updated = true;
-    }
-    
-    // 如果内容或标题更新了，同时更新 Markdown 文件
-    if file_updated {
-        if let Some(ref file_path) = passage.file_path {
-            let content_to_save = passage.original_content.as_ref().unwrap_or_else(|| {
-                // 如果没有原始内容，从 HTML 逆向生成（不推荐，但作为后备方案）
-                &passage.content
-            });
-            
-            // 更新文件名（如果标题改变了）
-            if let Some(ref title) = req.title {
-                let new_file_path = update_markdown_file_name(file_path, title, content_to_save);
-                if new_file_path != *file_path {
-                    passage.file_path = Some(new_file_path);
-                }
-            } else {
-                // 标题没变，只更新内容
-                if let Err(e) = update_markdown_file(file_path, content_to_save) {
-                    eprintln!("更新Markdown文件失败: {}", e);
-                }
-            }
-        }
     }
     if let Some(ref summary) = req.summary {
         passage.summary = Some(summary.clone());
     }
-    if let Some(ref author) = req.author {
-        passage.author = author.clone();
+    // 只有管理员可以自定义署名
+    if let Some(ref author) = req.author.as_ref().filter(|_| is_admin) {
+        passage.author = author.to_string();
+    }
+    // 只有管理员可以置顶、推荐文章
+    if let Some(pinned) = req.pinned.filter(|_| is_admin) {
+        passage.pinned = pinned;
+    }
+    if let Some(featured) = req.featured.filter(|_| is_admin) {
+        passage.featured = featured;
     }
     if let Some(ref category) = req.category {
         // 确保分类存在
@@ -509,8 +1023,17 @@ pub async fn update(
     if let Some(ref status) = req.status {
         passage.status = status.clone();
     }
-    if let Some(ref file_path) = req.file_path {
-        passage.file_path = Some(file_path.clone());
+    // 只有管理员可以修改文件路径
+    if let Some(file_path) = req.file_path.as_ref().filter(|_| is_admin) {
+        match resolve_markdown_path(file_path) {
+            Ok((_, file_path)) => passage.file_path = Some(file_path),
+            Err(e) => {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "success": false,
+                    "message": e
+                }));
+            }
+        }
     }
     if let Some(ref visibility) = req.visibility {
         passage.visibility = visibility.clone();
@@ -526,10 +1049,59 @@ pub async fn update(
     if let Some(ref cover_image) = req.cover_image {
         passage.cover_image = Some(cover_image.clone());
     }
+    if let Some(ref expires_at) = req.expires_at {
+        passage.expires_at = chrono::DateTime::parse_from_rfc3339(expires_at)
+            .ok()
+            .map(|dt| dt.with_timezone(&chrono::Utc));
+    }
+    if let Some(ref requested) = req.slug {
+        match resolve_requested_slug(&passage_repo, requested, passage.uuid.as_deref()).await {
+            Ok(slug) => passage.slug = Some(slug),
+            Err(response) => return response,
+        }
+    }
+    
+    // 访问密码：需要密码访问的文章必须已有或同时设置密码
+    let password_hash = match passage_repo.get_password_hash(id).await {
+        Ok(existing) => match resolve_password_hash(&passage.visibility, req.password.as_deref(), existing) {
+            Ok(hash) => hash,
+            Err(response) => return response,
+        },
+        Err(e) => {
+            eprintln!("获取访问密码失败: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "message": "获取访问密码失败"
+            }));
+        }
+    };
+    
+    // 同步 Markdown 文件（正文和 front matter），标题改变时同时重命名文件
+    if let Some(file_path) = passage.file_path.clone() {
+        if req.title.is_some() {
+            passage.file_path = Some(update_markdown_file_name(&file_path, &passage));
+        } else if let Err(e) = update_markdown_file(&file_path, &passage) {
+            eprintln!("更新Markdown文件失败: {}", e);
+        }
+    }
     passage.updated_at = chrono::Utc::now();
     
     match passage_repo.update(&passage).await {
         Ok(_) => {
+            if req.password.is_some() {
+                if let Err(e) = passage_repo.set_password_hash(id, password_hash.as_deref()).await {
+                    eprintln!("保存访问密码失败: {}", e);
+                }
+            }
+            // 记录修订
+            let editor = crate::middleware::auth::current_user(&http_req)
+                .map(|(_, username, _)| username)
+                .unwrap_or_default();
+            let revision_repo = PassageRevisionRepository::new(repo.get_pool().clone());
+            if let Err(e) = revision_repo.record_change(Some(&previous), &passage, &editor, "api").await {
+                eprintln!("记录文章修订失败: {}", e);
+            }
+            
             HttpResponse::Ok().json(serde_json::json!({
                 "success": true,
                 "message": "文章更新成功"
@@ -549,6 +1121,7 @@ pub async fn update(
 pub async fn delete(
     repo: web::Data<Arc<dyn Repository>>,
     path: web::Path<String>,
+    http_req: HttpRequest,
 ) -> HttpResponse {
     let uuid = path.into_inner();
     let passage_repo = PassageRepository::new(repo.get_pool().clone());
@@ -565,11 +1138,14 @@ pub async fn delete(
             }));
         }
     };
+    if let Err(response) = check_edit_permission(&http_req, &passage) {
+        return response;
+    }
 
     // 2. 删除 Markdown 文件
     let mut deleted_markdown = false;
     if let Some(file_path) = &passage.file_path {
-        if let Err(e) = std::fs::remove_file(file_path) {
+        if let Err(e) = remove_markdown_file(file_path) {
             eprintln!("删除 Markdown 文件失败 {}: {}", file_path, e);
         } else {
             deleted_markdown = true;
@@ -624,6 +1200,7 @@ pub struct BatchDeleteRequest {
 pub async fn delete_batch(
     repo: web::Data<Arc<dyn Repository>>,
     req: web::Json<BatchDeleteRequest>,
+    http_req: HttpRequest,
 ) -> HttpResponse {
     if req.ids.is_empty() {
         return HttpResponse::BadRequest().json(serde_json::json!({
@@ -642,6 +1219,9 @@ pub async fn delete_batch(
     for id in &req.ids {
         match passage_repo.get_by_id(*id).await {
             Ok(passage) => {
+                if let Err(response) = check_edit_permission(&http_req, &passage) {
+                    return response;
+                }
                 if let Some(uuid) = &passage.uuid {
                     uuids.push(uuid.clone());
                 }
@@ -658,7 +1238,7 @@ pub async fn delete_batch(
     // 2. 删除 Markdown 文件
     let mut deleted_markdown_files = 0;
     for file_path in &file_paths {
-        if let Err(e) = std::fs::remove_file(file_path) {
+        if let Err(e) = remove_markdown_file(file_path) {
             eprintln!("删除 Markdown 文件失败 {}: {}", file_path, e);
         } else {
             deleted_markdown_files += 1;
@@ -706,78 +1286,8 @@ pub async fn delete_batch(
     }
 }
 
-/// 从 HTML 内容中提取摘要
-fn extract_summary(html_content: &str) -> String {
-    // 移除 HTML 标签
-    let re = regex::Regex::new(r"<[^>]*>").unwrap();
-    let text = re.replace_all(html_content, "");
-    
-    // 移除多余的空白字符
-    let text: String = text.split_whitespace().collect::<Vec<&str>>().join(" ");
-    
-    // 按字符截取前 200 个字符（支持中文）
-    let chars: Vec<char> = text.chars().collect();
-    if chars.len() > 200 {
-        format!("{}...", chars[..200].iter().collect::<String>())
-    } else {
-        text
-    }
-}
-
-/// 将 Markdown 转换为 HTML（带缓存）
-fn convert_markdown_to_html(markdown: &str) -> String {
-    use pulldown_cmark::{Parser, html, Options};
-    use std::collections::HashMap;
-    use std::sync::Mutex;
-    use once_cell::sync::Lazy;
-
-    // 使用内容哈希作为缓存键
-    let mut hasher = md5::Md5::default();
-    md5::Digest::update(&mut hasher, markdown.as_bytes());
-    let content_hash = format!("{:x}", md5::Digest::finalize(hasher));
-
-    // 静态缓存：内容哈希 -> HTML
-    static RENDER_CACHE: Lazy<Mutex<HashMap<String, String>>> = Lazy::new(|| {
-        Mutex::new(HashMap::new())
-    });
-
-    // 检查缓存
-    {
-        let cache = RENDER_CACHE.lock().unwrap();
-        if let Some(cached_html) = cache.get(&content_hash) {
-            return cached_html.clone();
-        }
-    }
-
-    // 缓存未命中，执行渲染
-    let mut options = Options::empty();
-    options.insert(Options::ENABLE_TABLES);
-    options.insert(Options::ENABLE_STRIKETHROUGH);
-    options.insert(Options::ENABLE_TASKLISTS);
-
-    let parser = Parser::new_ext(markdown, options);
-    let mut html_output = String::new();
-    html::push_html(&mut html_output, parser);
-
-    // 存入缓存
-    {
-        let mut cache = RENDER_CACHE.lock().unwrap();
-        // 限制缓存大小为 1000 条
-        if cache.len() >= 1000 {
-            // 简单策略：清空一半缓存
-            let keys_to_remove: Vec<_> = cache.keys().take(500).cloned().collect();
-            for key in keys_to_remove {
-                cache.remove(&key);
-            }
-        }
-        cache.insert(content_hash, html_output.clone());
-    }
-
-    html_output
-}
-
 /// 确保标签存在于 tags 表中
-async fn ensure_tags_exist(tag_names: &[String]) -> Result<(), String> {
+pub(crate) async fn ensure_tags_exist(tag_names: &[String]) -> Result<(), String> {
     use crate::db::get_db_pool_sync;
     use crate::db::repositories::TagRepository;
     use std::sync::Arc;
@@ -810,7 +1320,7 @@ async fn ensure_tags_exist(tag_names: &[String]) -> Result<(), String> {
 }
 
 /// 确保分类存在于 categories 表中
-async fn ensure_category_exist(category_name: &str) -> Result<(), String> {
+pub(crate) async fn ensure_category_exist(category_name: &str) -> Result<(), String> {
     use crate::db::get_db_pool_sync;
     use crate::db::repositories::CategoryRepository;
     use std::sync::Arc;
@@ -828,6 +1338,7 @@ async fn ensure_category_exist(category_name: &str) -> Result<(), String> {
         let now = chrono::Utc::now();
         let new_category = crate::db::models::Category {
             id: None,
+            parent_id: None,
             name: category_name.to_string(),
             description: format!("用户创建的分类: {}", category_name),
             icon: "📁".to_string(),
@@ -844,46 +1355,129 @@ async fn ensure_category_exist(category_name: &str) -> Result<(), String> {
     Ok(())
 }
 
-/// 更新 Markdown 文件
-fn update_markdown_file(file_path: &str, content: &str) -> Result<(), String> {
+/// 文章 Markdown 文件所在目录（相对于工作目录）
+const MARKDOWN_DIR: &str = "markdown";
+
+/// 把文章文件路径解析为 markdown 目录中的 .md 文件
+///
+/// 已存在的部分经过规范化（解析 `..` 和符号链接），不存在的部分不能包含 `..`，
+/// 结果不在 markdown 目录中时返回错误。返回 (规范化后的路径, 存储用的 `markdown/…` 相对路径)
+pub(crate) fn resolve_markdown_path(file_path: &str) -> Result<(std::path::PathBuf, String), String> {
+    let base = std::env::current_dir().map_err(|e| format!("读取工作目录失败: {}", e))?;
+    resolve_markdown_path_in(&base, file_path)
+}
+
+/// 以 `base` 为工作目录解析文章文件路径
+fn resolve_markdown_path_in(base: &std::path::Path, file_path: &str) -> Result<(std::path::PathBuf, String), String> {
     use std::fs;
     use std::path::Path;
+
+    let invalid = || format!("文件路径不在 {} 目录中: {}", MARKDOWN_DIR, file_path);
+
+    let path = Path::new(file_path);
+    if path.extension().and_then(|e| e.to_str()) != Some("md") {
+        return Err(format!("文件路径必须是 .md 文件: {}", file_path));
+    }
+    let root = base.join(MARKDOWN_DIR);
+    fs::create_dir_all(&root).map_err(|e| format!("创建目录失败: {}", e))?;
+    let root = fs::canonicalize(&root).map_err(|e| format!("读取 {} 目录失败: {}", MARKDOWN_DIR, e))?;
+    let absolute = base.join(path);
+
+    // 找到最近的已存在的上级目录，其余部分逐级拼接（file_name 遇到 .. 时为 None）
+    let mut existing = absolute.as_path();
+    let mut missing = Vec::new();
+    while fs::symlink_metadata(existing).is_err() {
+        missing.push(existing.file_name().ok_or_else(invalid)?);
+        existing = existing.parent().ok_or_else(invalid)?;
+    }
+    let mut resolved = fs::canonicalize(existing).map_err(|_| invalid())?;
+    for name in missing.into_iter().rev() {
+        resolved.push(name);
+    }
+
+    let relative = resolved.strip_prefix(&root).map_err(|_| invalid())?;
+    let parts: Vec<String> = relative.components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    if parts.is_empty() {
+        return Err(invalid());
+    }
+    Ok((resolved, format!("{}/{}", MARKDOWN_DIR, parts.join("/"))))
+}
+
+/// 删除文章的 Markdown 文件（只删除 markdown 目录中的文件）
+pub(crate) fn remove_markdown_file(file_path: &str) -> Result<(), String> {
+    let (path, stored) = resolve_markdown_path(file_path)?;
+    std::fs::remove_file(path).map_err(|e| e.to_string())?;
+    crate::watcher::record_own_write(&stored);
+    Ok(())
+}
+
+/// 更新 Markdown 文件（正文前写入 front matter）
+pub(crate) fn update_markdown_file(file_path: &str, passage: &Passage) -> Result<(), String> {
+    use std::fs;
+    use crate::front_matter::{self, FrontMatter};
+    
+    let (path, stored) = resolve_markdown_path(file_path)?;
+    
+    let mut meta = FrontMatter::from_passage(passage);
+    
+    // 自动生成的摘要不写入文件，避免正文修改后摘要过期
+    if passage.summary.as_deref() == Some(crate::markdown::extract_summary(&passage.content).as_str()) {
+        meta.summary = None;
+    }
+    
+    // 如果没有原始内容，使用 HTML 作为后备
+    let body = passage.original_content.as_deref().unwrap_or(&passage.content);
+    let content = front_matter::render(&meta, body)?;
     
     // 创建目录
-    if let Some(parent) = Path::new(file_path).parent() {
+    if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)
             .map_err(|e| format!("创建目录失败: {}", e))?;
     }
     
-    // 写入文件
-    fs::write(file_path, content)
+    // 写入文件（数据库由调用方更新，文件监听不再重复同步）
+    fs::write(&path, content)
         .map_err(|e| format!("写入文件失败: {}", e))?;
+    crate::watcher::record_own_write(&stored);
     
     Ok(())
 }
 
 /// 更新 Markdown 文件名（如果标题改变）
-fn update_markdown_file_name(old_path: &str, new_title: &str, content: &str) -> String {
+pub(crate) fn update_markdown_file_name(old_path: &str, passage: &Passage) -> String {
     use std::fs;
     use std::path::Path;
     
-    // 构建新文件路径
-    if let Some(parent) = Path::new(old_path).parent() {
-        let new_path = parent.join(format!("{}.md", new_title));
-        
-        // 删除旧文件
-        let _ = fs::remove_file(old_path);
-        
-        // 创建新文件
-        if let Err(e) = update_markdown_file(new_path.to_str().unwrap(), content) {
+    // 构建新文件路径（新旧路径都必须在 markdown 目录中）
+    let resolved = resolve_markdown_path(old_path).and_then(|(old, old_stored)| {
+        let parent = Path::new(old_path).parent().ok_or_else(|| format!("无效的文件路径: {}", old_path))?;
+        let new_path = parent.join(format!("{}.md", passage.title)).to_string_lossy().to_string();
+        let (new, new_path) = resolve_markdown_path(&new_path)?;
+        Ok((old, old_stored, new, new_path))
+    });
+    let (old, old_stored, new, new_path) = match resolved {
+        Ok(paths) => paths,
+        Err(e) => {
             eprintln!("更新文件名失败: {}", e);
             return old_path.to_string();
         }
-        
-        new_path.to_str().map(|s| s.to_string()).unwrap_or_else(|| old_path.to_string())
-    } else {
-        old_path.to_string()
+    };
+    
+    // 先移动旧文件，保留其中的 front matter
+    if new != old {
+        let _ = fs::rename(&old, &new);
+        crate::watcher::record_own_write(&old_stored);
+        crate::watcher::record_own_write(&new_path);
     }
+    
+    if let Err(e) = update_markdown_file(&new_path, passage) {
+        eprintln!("更新文件名失败: {}", e);
+        return old_path.to_string();
+    }
+    
+    new_path
 }
 
 /// 通过查询参数更新文章（用于管理后台）
@@ -926,45 +1520,21 @@ pub async fn update_by_query(
         }
     };
     
+    // 保存修改前的状态，用于记录修订
+    let previous = passage.clone();
+    
     // 更新字段
-    let mut file_updated = false;
     if let Some(ref title) = req_json.title {
         passage.title = title.clone();
-        file_updated = true;
     }
     if let Some(ref content) = req_json.content {
-        // 转换 Markdown 为 HTML
-        let html_content = convert_markdown_to_html(content);
-        passage.content = html_content;
+        // 转换 Markdown 为 HTML，作者不是管理员时需要清理
+        let trusted = passage_repo.is_trusted_author(passage.author_id).await;
+        passage.content = crate::markdown::render_passage(content, trusted);
         passage.original_content = Some(content.clone());
-        file_updated = true;
     }
     if let Some(ref original_content) = req_json.original_content {
         passage.original_content = Some(original_content.clone());
-        file_updated = true;
-    }
-    
-    // 如果内容或标题更新了，同时更新 Markdown 文件
-    if file_updated {
-        if let Some(ref file_path) = passage.file_path {
-            let content_to_save = passage.original_content.as_ref().unwrap_or_else(|| {
-                // 如果没有原始内容，从 HTML 逆向生成（不推荐，但作为后备方案）
-                &passage.content
-            });
-            
-            // 更新文件名（如果标题改变了）
-            if let Some(ref title) = req_json.title {
-                let new_file_path = update_markdown_file_name(file_path, title, content_to_save);
-                if new_file_path != *file_path {
-                    passage.file_path = Some(new_file_path);
-                }
-            } else {
-                // 标题没变，只更新内容
-                if let Err(e) = update_markdown_file(file_path, content_to_save) {
-                    eprintln!("更新Markdown文件失败: {}", e);
-                }
-            }
-        }
     }
     if let Some(ref summary) = req_json.summary {
         passage.summary = Some(summary.clone());
@@ -972,6 +1542,12 @@ pub async fn update_by_query(
     if let Some(ref author) = req_json.author {
         passage.author = author.clone();
     }
+    if let Some(pinned) = req_json.pinned {
+        passage.pinned = pinned;
+    }
+    if let Some(featured) = req_json.featured {
+        passage.featured = featured;
+    }
     if let Some(ref tags) = req_json.tags {
         // 解析标签：支持 JSON 数组和逗号分隔的字符串
         let tag_list: Vec<String> = if tags.trim().starts_with('[') {
@@ -1002,7 +1578,15 @@ pub async fn update_by_query(
         passage.status = status.clone();
     }
     if let Some(ref file_path) = req_json.file_path {
-        passage.file_path = Some(file_path.clone());
+        match resolve_markdown_path(file_path) {
+            Ok((_, file_path)) => passage.file_path = Some(file_path),
+            Err(e) => {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "success": false,
+                    "message": e
+                }));
+            }
+        }
     }
     if let Some(ref visibility) = req_json.visibility {
         passage.visibility = visibility.clone();
@@ -1018,10 +1602,59 @@ pub async fn update_by_query(
     if let Some(ref cover_image) = req_json.cover_image {
         passage.cover_image = Some(cover_image.clone());
     }
+    if let Some(ref expires_at) = req_json.expires_at {
+        passage.expires_at = chrono::DateTime::parse_from_rfc3339(expires_at)
+            .ok()
+            .map(|dt| dt.with_timezone(&chrono::Utc));
+    }
+    if let Some(ref requested) = req_json.slug {
+        match resolve_requested_slug(&passage_repo, requested, passage.uuid.as_deref()).await {
+            Ok(slug) => passage.slug = Some(slug),
+            Err(response) => return response,
+        }
+    }
+    
+    // 访问密码：需要密码访问的文章必须已有或同时设置密码
+    let password_hash = match passage_repo.get_password_hash(id).await {
+        Ok(existing) => match resolve_password_hash(&passage.visibility, req_json.password.as_deref(), existing) {
+            Ok(hash) => hash,
+            Err(response) => return response,
+        },
+        Err(e) => {
+            eprintln!("获取访问密码失败: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "message": "获取访问密码失败"
+            }));
+        }
+    };
+    
+    // 同步 Markdown 文件（正文和 front matter），标题改变时同时重命名文件
+    if let Some(file_path) = passage.file_path.clone() {
+        if req_json.title.is_some() {
+            passage.file_path = Some(update_markdown_file_name(&file_path, &passage));
+        } else if let Err(e) = update_markdown_file(&file_path, &passage) {
+            eprintln!("更新Markdown文件失败: {}", e);
+        }
+    }
     passage.updated_at = chrono::Utc::now();
     
     match passage_repo.update(&passage).await {
         Ok(_) => {
+            if req_json.password.is_some() {
+                if let Err(e) = passage_repo.set_password_hash(id, password_hash.as_deref()).await {
+                    eprintln!("保存访问密码失败: {}", e);
+                }
+            }
+            // 记录修订
+            let editor = crate::middleware::auth::current_user(&http_req)
+                .map(|(_, username, _)| username)
+                .unwrap_or_default();
+            let revision_repo = PassageRevisionRepository::new(repo.get_pool().clone());
+            if let Err(e) = revision_repo.record_change(Some(&previous), &passage, &editor, "api").await {
+                eprintln!("记录文章修订失败: {}", e);
+            }
+            
             HttpResponse::Ok().json(serde_json::json!({
                 "success": true,
                 "message": "文章更新成功"
@@ -1087,7 +1720,7 @@ pub async fn delete_by_query(
     
     // 删除 Markdown 文件
     if let Some(file_path) = &passage.file_path {
-        if let Err(e) = std::fs::remove_file(file_path) {
+        if let Err(e) = remove_markdown_file(file_path) {
             eprintln!("删除 Markdown 文件失败 {}: {}", file_path, e);
         }
     }
@@ -1129,19 +1762,17 @@ pub async fn delete_by_query(
     }
 }
 
-/// 通过查询参数获取单篇文章或文章列表（用于管理后台）
+/// 通过查询参数获取单篇文章或文章列表（用于管理后台，非管理员作者只能看到自己的文章）
 pub async fn get_by_query(
     repo: web::Data<Arc<dyn Repository>>,
     query: web::Query<std::collections::HashMap<String, String>>,
     http_req: HttpRequest,
 ) -> HttpResponse {
     // 鉴权检查
-    if http_req.cookie("auth_token").is_none() {
+    let Some((user_id, _, role)) = crate::middleware::auth::current_user(&http_req) else {
         return crate::middleware::auth::missing_token_response();
-    }
-    if crate::middleware::auth::check_admin_auth(&http_req).is_none() {
-        return crate::middleware::auth::forbidden_response();
-    }
+    };
+    let is_admin = role == "admin";
     let passage_repo = PassageRepository::new(repo.get_pool().clone());
     
     // 检查是否有 id 查询参数
@@ -1159,7 +1790,11 @@ pub async fn get_by_query(
         
         match passage_repo.get_by_id(id).await {
             Ok(passage) => {
+                if let Err(response) = check_edit_permission(&http_req, &passage) {
+                    return response;
+                }
                 let response = PassageResponse {
+                    permalink: passage.permalink(),
                     id: passage.id.unwrap_or(0),
                     uuid: passage.uuid.unwrap_or_default(),
                     title: passage.title,
@@ -1177,6 +1812,18 @@ pub async fn get_by_query(
                     cover_image: passage.cover_image,
                     created_at: passage.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
                     updated_at: passage.updated_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                    expires_at: passage.expires_at.map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string()),
+                    slug: passage.slug,
+                    word_count: passage.word_count,
+                    reading_minutes: passage.reading_minutes,
+                    author_id: passage.author_id,
+                    pinned: passage.pinned,
+                    featured: passage.featured,
+                    pin_order: passage.pin_order,
+                    series: None,
+                    toc: None,
+                    breadcrumb: None,
+                    author_profile: None,
                 };
                 
                 HttpResponse::Ok().json(serde_json::json!({
@@ -1199,15 +1846,24 @@ pub async fn get_by_query(
         let page: i64 = query.get("page").and_then(|p| p.parse().ok()).unwrap_or(1);
         let calculated_offset = (page - 1) * limit;
         
-        match passage_repo.get_all(limit, calculated_offset).await {
+        // 按字数筛选和排序
+        let filter = crate::db::repositories::PassageListFilter {
+            min_words: query.get("min_words").and_then(|v| v.parse().ok()),
+            max_words: query.get("max_words").and_then(|v| v.parse().ok()),
+            sort: query.get("sort").map(|v| crate::db::repositories::PassageSort::parse(v)).unwrap_or_default(),
+            author_id: if is_admin { query.get("author_id").and_then(|v| v.parse().ok()) } else { Some(user_id) },
+        };
+        
+        match passage_repo.get_all_filtered(&filter, limit, calculated_offset).await {
             Ok(passages) => {
-                let total = match passage_repo.count().await {
+                let total = match passage_repo.count_filtered(&filter).await {
                     Ok(c) => c,
                     Err(_) => passages.len() as i64,
                 };
                 
                 let data: Vec<PassageResponse> = passages.into_iter()
                     .map(|p| PassageResponse {
+                        permalink: p.permalink(),
                         id: p.id.unwrap_or(0),
                         uuid: p.uuid.unwrap_or_default(),
                         title: p.title,
@@ -1225,6 +1881,18 @@ pub async fn get_by_query(
                         cover_image: p.cover_image,
                         created_at: p.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
                         updated_at: p.updated_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                        expires_at: p.expires_at.map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string()),
+                        slug: p.slug,
+                        word_count: p.word_count,
+                        reading_minutes: p.reading_minutes,
+                        author_id: p.author_id,
+                        pinned: p.pinned,
+                        featured: p.featured,
+                        pin_order: p.pin_order,
+                        series: None,
+                        toc: None,
+                        breadcrumb: None,
+                        author_profile: None,
                     })
                     .collect();
                 
@@ -1245,4 +1913,54 @@ pub async fn get_by_query(
             }
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_create_requires_login() {
+        crate::jwt::init_test_jwt_service();
+        let pool = crate::db::init::test_pool();
+        let repo: Arc<dyn Repository> = Arc::new(PassageRepository::new(pool.clone()));
+        let req: CreatePassageRequest = serde_json::from_value(serde_json::json!({
+            "title": "匿名投稿",
+            "content": "正文",
+            "status": "published"
+        }))
+        .unwrap();
+
+        let response = create(web::Data::new(repo), web::Json(req), actix_web::test::TestRequest::default().to_http_request()).await;
+        assert_eq!(response.status(), actix_web::http::StatusCode::UNAUTHORIZED);
+        let count: i64 = pool.get().unwrap().query_row("SELECT COUNT(*) FROM passages", [], |row| row.get(0)).unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn test_resolve_markdown_path_stays_in_markdown_dir() {
+        let base = std::env::temp_dir().join(format!("rustblog-paths-{}", crate::id_generator::generate_unique_id()));
+        std::fs::create_dir_all(base.join("markdown/2024")).unwrap();
+        std::fs::write(base.join("secret.md"), "").unwrap();
+        let resolve = |path: &str| resolve_markdown_path_in(&base, path).map(|(_, stored)| stored);
+
+        assert_eq!(resolve("markdown/2024/a.md").unwrap(), "markdown/2024/a.md");
+        assert_eq!(resolve("./markdown/2024/../new/b.md").unwrap(), "markdown/new/b.md");
+        let absolute = base.join("markdown/c.md").to_string_lossy().to_string();
+        assert_eq!(resolve(&absolute).unwrap(), "markdown/c.md");
+
+        assert!(resolve("secret.md").is_err());
+        assert!(resolve("markdown/../secret.md").is_err());
+        assert!(resolve("markdown/new/../../secret.md").is_err());
+        assert!(resolve("/etc/passwd.md").is_err());
+        assert!(resolve("markdown/2024/a.txt").is_err());
+
+        // 指向目录外的符号链接
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(base.join("secret.md"), base.join("markdown/link.md")).unwrap();
+            assert!(resolve("markdown/link.md").is_err());
+        }
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+}
@@ -0,0 +1,328 @@
+use actix_web::{web, HttpResponse, HttpRequest};
+use serde::{Deserialize, Serialize};
+use crate::db::repositories::{PassageRepository, PreviewLinkRepository, Repository};
+use crate::db::models::PreviewLink;
+use std::sync::Arc;
+use chrono::{Duration, Utc};
+use rand::Rng;
+
+/// 默认有效期（小时）
+const DEFAULT_EXPIRES_IN_HOURS: i64 = 72;
+/// 最长有效期（小时）
+const MAX_EXPIRES_IN_HOURS: i64 = 24 * 30;
+
+/// 创建预览链接请求
+#[derive(Debug, Deserialize)]
+pub struct CreatePreviewLinkRequest {
+    pub expires_in_hours: Option<i64>,
+    pub note: Option<String>,
+}
+
+/// 预览链接响应
+#[derive(Debug, Serialize)]
+pub struct PreviewLinkResponse {
+    pub id: i64,
+    pub passage_uuid: String,
+    pub note: Option<String>,
+    pub created_by: String,
+    pub expires_at: String,
+    pub revoked_at: Option<String>,
+    pub last_used_at: Option<String>,
+    pub created_at: String,
+    pub active: bool,  // 未吊销且未过期
+}
+
+impl From<PreviewLink> for PreviewLinkResponse {
+    fn from(link: PreviewLink) -> Self {
+        PreviewLinkResponse {
+            id: link.id.unwrap_or(0),
+            active: link.revoked_at.is_none() && link.expires_at > Utc::now(),
+            passage_uuid: link.passage_uuid,
+            note: link.note,
+            created_by: link.created_by,
+            expires_at: link.expires_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            revoked_at: link.revoked_at.map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string()),
+            last_used_at: link.last_used_at.map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string()),
+            created_at: link.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+}
+
+/// 校验预览令牌是否可以访问指定文章（签名有效、未过期、未吊销、文章一致）
+pub(crate) async fn verify_token(repo: &Arc<dyn Repository>, token: &str, passage_uuid: &str) -> bool {
+    let claims = match crate::jwt::validate_preview_token(token) {
+        Ok(c) => c,
+        Err(_) => return false,
+    };
+    if claims.sub != passage_uuid {
+        return false;
+    }
+
+    let link_repo = PreviewLinkRepository::new(repo.get_pool().clone());
+    let link = match link_repo.get_by_token_id(&claims.jti).await {
+        Ok(Some(link)) => link,
+        _ => return false,
+    };
+    if link.passage_uuid != passage_uuid || link.revoked_at.is_some() || link.expires_at <= Utc::now() {
+        return false;
+    }
+
+    if let Some(id) = link.id {
+        if let Err(e) = link_repo.touch(id).await {
+            eprintln!("更新预览链接使用时间失败: {}", e);
+        }
+    }
+    true
+}
+
+/// 为文章创建预览链接（管理员）
+pub async fn create(
+    repo: web::Data<Arc<dyn Repository>>,
+    path: web::Path<String>,
+    req: Option<web::Json<CreatePreviewLinkRequest>>,
+    http_req: HttpRequest,
+) -> HttpResponse {
+    // 鉴权检查
+    if http_req.cookie("auth_token").is_none() {
+        return crate::middleware::auth::missing_token_response();
+    }
+    let username = match crate::middleware::auth::check_admin_auth(&http_req) {
+        Some((_, username, _)) => username,
+        None => return crate::middleware::auth::forbidden_response(),
+    };
+
+    let uuid = path.into_inner();
+    let passage_repo = PassageRepository::new(repo.get_pool().clone());
+    let passage = match passage_repo.get_by_uuid(&uuid).await {
+        Ok(p) => p,
+        Err(_) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "success": false,
+                "message": "文章不存在"
+            }));
+        }
+    };
+
+    let (expires_in_hours, note) = match req {
+        Some(req) => {
+            let req = req.into_inner();
+            (req.expires_in_hours, req.note.filter(|n| !n.trim().is_empty()))
+        }
+        None => (None, None),
+    };
+    let expires_in_hours = expires_in_hours.unwrap_or(DEFAULT_EXPIRES_IN_HOURS);
+    if !(1..=MAX_EXPIRES_IN_HOURS).contains(&expires_in_hours) {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "success": false,
+            "message": format!("有效期必须在 1 到 {} 小时之间", MAX_EXPIRES_IN_HOURS)
+        }));
+    }
+
+    let now = Utc::now();
+    let link = PreviewLink {
+        id: None,
+        token_id: hex::encode(rand::thread_rng().r#gen::<[u8; 16]>()),
+        passage_uuid: uuid.clone(),
+        note,
+        created_by: username,
+        expires_at: now + Duration::hours(expires_in_hours),
+        revoked_at: None,
+        last_used_at: None,
+        created_at: now,
+    };
+
+    let token = match crate::jwt::generate_preview_token(&uuid, &link.token_id, link.expires_at) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("生成预览令牌失败: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "message": "生成预览链接失败"
+            }));
+        }
+    };
+
+    let id = match PreviewLinkRepository::new(repo.get_pool().clone()).create(&link).await {
+        Ok(id) => id,
+        Err(e) => {
+            eprintln!("保存预览链接失败: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "message": "生成预览链接失败"
+            }));
+        }
+    };
+
+    let path = passage.permalink()
+        .unwrap_or_else(|| format!("/passage/{}", passage.id.unwrap_or(0)));
+    let url = format!("{}?preview={}", path, token);
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "message": "预览链接已生成",
+        "data": {
+            "token": token,
+            "url": url,
+            "link": PreviewLinkResponse::from(PreviewLink { id: Some(id), ..link })
+        }
+    }))
+}
+
+/// 获取全部预览链接（管理员），可用 passage_uuid 参数过滤
+pub async fn list(
+    repo: web::Data<Arc<dyn Repository>>,
+    query: web::Query<std::collections::HashMap<String, String>>,
+    http_req: HttpRequest,
+) -> HttpResponse {
+    list_links(&repo, query.get("passage_uuid").map(|s| s.as_str()), &http_req).await
+}
+
+/// 获取文章的预览链接（管理员）
+pub async fn list_for_passage(
+    repo: web::Data<Arc<dyn Repository>>,
+    path: web::Path<String>,
+    http_req: HttpRequest,
+) -> HttpResponse {
+    let uuid = path.into_inner();
+    list_links(&repo, Some(&uuid), &http_req).await
+}
+
+async fn list_links(repo: &Arc<dyn Repository>, passage_uuid: Option<&str>, http_req: &HttpRequest) -> HttpResponse {
+    // 鉴权检查
+    if http_req.cookie("auth_token").is_none() {
+        return crate::middleware::auth::missing_token_response();
+    }
+    if crate::middleware::auth::check_admin_auth(http_req).is_none() {
+        return crate::middleware::auth::forbidden_response();
+    }
+
+    let link_repo = PreviewLinkRepository::new(repo.get_pool().clone());
+    match link_repo.get_all(passage_uuid).await {
+        Ok(links) => {
+            let data: Vec<PreviewLinkResponse> = links.into_iter().map(PreviewLinkResponse::from).collect();
+            HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "total": data.len(),
+                "data": data
+            }))
+        }
+        Err(e) => {
+            eprintln!("获取预览链接失败: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "message": "获取预览链接失败"
+            }))
+        }
+    }
+}
+
+/// 吊销预览链接（管理员）
+pub async fn revoke(
+    repo: web::Data<Arc<dyn Repository>>,
+    path: web::Path<i64>,
+    http_req: HttpRequest,
+) -> HttpResponse {
+    // 鉴权检查
+    if http_req.cookie("auth_token").is_none() {
+        return crate::middleware::auth::missing_token_response();
+    }
+    if crate::middleware::auth::check_admin_auth(&http_req).is_none() {
+        return crate::middleware::auth::forbidden_response();
+    }
+
+    let id = path.into_inner();
+    let link_repo = PreviewLinkRepository::new(repo.get_pool().clone());
+    match link_repo.revoke(id).await {
+        Ok(true) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "message": "预览链接已吊销"
+        })),
+        Ok(false) => HttpResponse::NotFound().json(serde_json::json!({
+            "success": false,
+            "message": "预览链接不存在或已吊销"
+        })),
+        Err(e) => {
+            eprintln!("吊销预览链接失败: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "message": "吊销预览链接失败"
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::Passage;
+
+    /// 创建文章，返回仓库和文章 UUID
+    async fn setup() -> (Arc<dyn Repository>, String) {
+        crate::jwt::init_test_jwt_service();
+        let pool = crate::db::init::test_pool();
+        let passage_repo = PassageRepository::new(pool.clone());
+        let id = passage_repo.create(&Passage::sample("草稿", "正文")).await.unwrap();
+        let uuid = passage_repo.get_by_id(id).await.unwrap().uuid.unwrap();
+        let repo: Arc<dyn Repository> = Arc::new(passage_repo);
+        (repo, uuid)
+    }
+
+    /// 保存预览链接并签发令牌，返回链接 ID 和令牌
+    async fn issue(repo: &Arc<dyn Repository>, passage_uuid: &str, expires_at: chrono::DateTime<Utc>) -> (i64, String) {
+        let now = Utc::now();
+        let link = PreviewLink {
+            id: None,
+            token_id: hex::encode(rand::thread_rng().r#gen::<[u8; 16]>()),
+            passage_uuid: passage_uuid.to_string(),
+            note: None,
+            created_by: "admin".to_string(),
+            expires_at,
+            revoked_at: None,
+            last_used_at: None,
+            created_at: now,
+        };
+        let token = crate::jwt::generate_preview_token(passage_uuid, &link.token_id, expires_at).unwrap();
+        let id = PreviewLinkRepository::new(repo.get_pool().clone()).create(&link).await.unwrap();
+        (id, token)
+    }
+
+    #[tokio::test]
+    async fn test_verify_token_accepts_valid_token() {
+        let (repo, uuid) = setup().await;
+        let (id, token) = issue(&repo, &uuid, Utc::now() + Duration::hours(1)).await;
+
+        assert!(verify_token(&repo, &token, &uuid).await);
+        let link = PreviewLinkRepository::new(repo.get_pool().clone())
+            .get_by_token_id(&crate::jwt::validate_preview_token(&token).unwrap().jti)
+            .await.unwrap().unwrap();
+        assert_eq!(link.id, Some(id));
+        assert!(link.last_used_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_verify_token_rejects_expired_token() {
+        let (repo, uuid) = setup().await;
+        let (_, token) = issue(&repo, &uuid, Utc::now() - Duration::hours(1)).await;
+        assert!(!verify_token(&repo, &token, &uuid).await);
+    }
+
+    #[tokio::test]
+    async fn test_verify_token_rejects_revoked_token() {
+        let (repo, uuid) = setup().await;
+        let (id, token) = issue(&repo, &uuid, Utc::now() + Duration::hours(1)).await;
+        assert!(PreviewLinkRepository::new(repo.get_pool().clone()).revoke(id).await.unwrap());
+        assert!(!verify_token(&repo, &token, &uuid).await);
+    }
+
+    #[tokio::test]
+    async fn test_verify_token_rejects_other_passage_and_unknown_link() {
+        let (repo, uuid) = setup().await;
+        let (_, token) = issue(&repo, &uuid, Utc::now() + Duration::hours(1)).await;
+        assert!(!verify_token(&repo, &token, "other-passage").await);
+
+        // 签名有效但数据库中没有对应的预览链接
+        let orphan = crate::jwt::generate_preview_token(&uuid, "missing", Utc::now() + Duration::hours(1)).unwrap();
+        assert!(!verify_token(&repo, &orphan, &uuid).await);
+        assert!(!verify_token(&repo, "not-a-token", &uuid).await);
+    }
+}
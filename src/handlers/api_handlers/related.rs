@@ -0,0 +1,108 @@
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+use crate::db::repositories::{PassageRepository, Repository};
+use std::sync::Arc;
+
+/// 默认返回的相关文章数量
+const DEFAULT_LIMIT: i64 = 5;
+
+/// 相关文章查询参数
+#[derive(Debug, Deserialize)]
+pub struct RelatedQuery {
+    pub limit: Option<i64>,
+    pub preview: Option<String>,
+}
+
+/// 相关文章
+#[derive(Debug, Serialize)]
+pub struct RelatedPassageResponse {
+    pub id: i64,
+    pub uuid: String,
+    pub title: String,
+    pub summary: Option<String>,
+    pub cover_image: Option<String>,
+    pub category: String,
+    pub tags: Vec<String>,
+    pub permalink: Option<String>,
+    pub published_at: Option<String>,
+    pub score: f64,
+}
+
+/// 获取文章的相关文章（后台预先计算，按共同标签、分类和正文相似度排序）
+pub async fn list(
+    repo: web::Data<Arc<dyn Repository>>,
+    path: web::Path<String>,
+    query: web::Query<RelatedQuery>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let param = path.into_inner();
+    let passage_repo = PassageRepository::new(repo.get_pool().clone());
+
+    // 与文章详情一致：纯数字按 ID 查询，否则按 slug 或 UUID 查询
+    let passage = if let Ok(id) = param.parse::<i64>() {
+        passage_repo.get_by_id(id).await.ok()
+    } else {
+        match passage_repo.get_by_slug(&param).await {
+            Ok(p) => Some(p),
+            Err(_) => passage_repo.get_by_uuid(&param).await.ok(),
+        }
+    };
+    let Some(passage) = passage else {
+        return not_found();
+    };
+    let uuid = passage.uuid.clone().unwrap_or_default();
+
+    // 不可见的文章只有管理员或持有预览令牌时才能获取相关文章
+    if !passage.is_publicly_visible() {
+        let is_admin = req.extensions().get::<crate::middleware::auth::RoleKey>()
+            .is_some_and(|r| r.0 == "admin");
+        let is_preview = match &query.preview {
+            Some(token) => super::preview::verify_token(&repo, token, &uuid).await,
+            None => false,
+        };
+        if !is_admin && !is_preview {
+            return not_found();
+        }
+    }
+
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, crate::db::related::MAX_RELATED as i64);
+    let related = match passage_repo.get_related(&uuid, limit).await {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("获取相关文章失败: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "message": "获取相关文章失败"
+            }));
+        }
+    };
+
+    let data: Vec<RelatedPassageResponse> = related.into_iter()
+        .map(|(p, score)| RelatedPassageResponse {
+            tags: p.tag_list(),
+            permalink: p.permalink(),
+            id: p.id.unwrap_or(0),
+            uuid: p.uuid.unwrap_or_default(),
+            title: p.title,
+            summary: p.summary,
+            cover_image: p.cover_image,
+            category: p.category,
+            published_at: p.published_at.map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string()),
+            score,
+        })
+        .collect();
+
+    HttpResponse::Ok()
+        .insert_header((actix_web::http::header::CACHE_CONTROL, "public, max-age=300"))
+        .json(serde_json::json!({
+            "success": true,
+            "data": data
+        }))
+}
+
+fn not_found() -> HttpResponse {
+    HttpResponse::NotFound().json(serde_json::json!({
+        "success": false,
+        "message": "文章不存在"
+    }))
+}
@@ -0,0 +1,427 @@
+use actix_web::{web, HttpResponse, HttpRequest};
+use serde::Serialize;
+use crate::db::repositories::{PassageRepository, PassageRevisionRepository, Repository};
+use crate::db::models::PassageRevision;
+use std::sync::Arc;
+use chrono::Utc;
+
+/// 修订列表每页的最大条数
+const MAX_LIMIT: i64 = 100;
+
+/// 修订列表项（不含正文）
+#[derive(Debug, Serialize)]
+pub struct RevisionSummaryResponse {
+    pub id: i64,
+    pub passage_uuid: String,
+    pub title: String,
+    pub editor: String,
+    pub source: String,
+    pub content_length: usize,
+    pub created_at: String,
+}
+
+/// 修订详情
+#[derive(Debug, Serialize)]
+pub struct RevisionResponse {
+    pub id: i64,
+    pub passage_uuid: String,
+    pub title: String,
+    pub content: String,  // Markdown 内容
+    pub summary: Option<String>,
+    pub tags: String,
+    pub category: String,
+    pub editor: String,
+    pub source: String,
+    pub created_at: String,
+}
+
+/// 差异行
+#[derive(Debug, Serialize)]
+pub struct DiffLine {
+    pub r#type: String,  // equal / insert / delete
+    pub old_line: Option<usize>,
+    pub new_line: Option<usize>,
+    pub content: String,
+}
+
+impl From<PassageRevision> for RevisionResponse {
+    fn from(r: PassageRevision) -> Self {
+        RevisionResponse {
+            id: r.id.unwrap_or(0),
+            passage_uuid: r.passage_uuid,
+            title: r.title,
+            content: r.original_content,
+            summary: r.summary,
+            tags: r.tags,
+            category: r.category,
+            editor: r.editor,
+            source: r.source,
+            created_at: r.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+}
+
+/// 获取属于指定文章的修订
+async fn load_revision(
+    revision_repo: &PassageRevisionRepository,
+    uuid: &str,
+    id: i64,
+) -> Result<PassageRevision, HttpResponse> {
+    match revision_repo.get_by_id(id).await {
+        Ok(r) if r.passage_uuid == uuid => Ok(r),
+        _ => Err(HttpResponse::NotFound().json(serde_json::json!({
+            "success": false,
+            "message": format!("修订 {} 不存在", id)
+        }))),
+    }
+}
+
+/// 获取文章的修订列表（管理员）
+pub async fn list(
+    repo: web::Data<Arc<dyn Repository>>,
+    path: web::Path<String>,
+    query: web::Query<std::collections::HashMap<String, String>>,
+    http_req: HttpRequest,
+) -> HttpResponse {
+    // 鉴权检查
+    if http_req.cookie("auth_token").is_none() {
+        return crate::middleware::auth::missing_token_response();
+    }
+    if crate::middleware::auth::check_admin_auth(&http_req).is_none() {
+        return crate::middleware::auth::forbidden_response();
+    }
+
+    let uuid = path.into_inner();
+    let revision_repo = PassageRevisionRepository::new(repo.get_pool().clone());
+
+    // 解析分页参数
+    let limit: i64 = query.get("limit").and_then(|l| l.parse().ok()).unwrap_or(20).clamp(1, MAX_LIMIT);
+    let page: i64 = query.get("page").and_then(|p| p.parse().ok()).unwrap_or(1).max(1);
+    let offset = (page - 1) * limit;
+
+    match revision_repo.get_by_passage_uuid(&uuid, limit, offset).await {
+        Ok(revisions) => {
+            let total = match revision_repo.count_by_passage_uuid(&uuid).await {
+                Ok(c) => c,
+                Err(_) => revisions.len() as i64,
+            };
+
+            let data: Vec<RevisionSummaryResponse> = revisions.into_iter()
+                .map(|r| RevisionSummaryResponse {
+                    id: r.id.unwrap_or(0),
+                    passage_uuid: r.passage_uuid,
+                    title: r.title,
+                    editor: r.editor,
+                    source: r.source,
+                    content_length: r.original_content.chars().count(),
+                    created_at: r.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                })
+                .collect();
+
+            HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "data": data,
+                "total": total
+            }))
+        }
+        Err(e) => {
+            eprintln!("获取修订列表失败: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "message": "获取修订列表失败"
+            }))
+        }
+    }
+}
+
+/// 获取单个修订（管理员）
+pub async fn get(
+    repo: web::Data<Arc<dyn Repository>>,
+    path: web::Path<(String, i64)>,
+    http_req: HttpRequest,
+) -> HttpResponse {
+    // 鉴权检查
+    if http_req.cookie("auth_token").is_none() {
+        return crate::middleware::auth::missing_token_response();
+    }
+    if crate::middleware::auth::check_admin_auth(&http_req).is_none() {
+        return crate::middleware::auth::forbidden_response();
+    }
+
+    let (uuid, id) = path.into_inner();
+    let revision_repo = PassageRevisionRepository::new(repo.get_pool().clone());
+
+    match load_revision(&revision_repo, &uuid, id).await {
+        Ok(revision) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": RevisionResponse::from(revision)
+        })),
+        Err(response) => response,
+    }
+}
+
+/// 比较两个修订的差异（管理员）
+///
+/// 查询参数：from（修订 ID）、to（修订 ID，省略时与文章当前内容比较）
+pub async fn diff(
+    repo: web::Data<Arc<dyn Repository>>,
+    path: web::Path<String>,
+    query: web::Query<std::collections::HashMap<String, String>>,
+    http_req: HttpRequest,
+) -> HttpResponse {
+    use similar::TextDiff;
+
+    // 鉴权检查
+    if http_req.cookie("auth_token").is_none() {
+        return crate::middleware::auth::missing_token_response();
+    }
+    if crate::middleware::auth::check_admin_auth(&http_req).is_none() {
+        return crate::middleware::auth::forbidden_response();
+    }
+
+    let uuid = path.into_inner();
+    let revision_repo = PassageRevisionRepository::new(repo.get_pool().clone());
+
+    let from_id: i64 = match query.get("from").and_then(|s| s.parse().ok()) {
+        Some(id) => id,
+        None => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "success": false,
+                "message": "缺少 from 参数"
+            }));
+        }
+    };
+
+    let from = match load_revision(&revision_repo, &uuid, from_id).await {
+        Ok(r) => r,
+        Err(response) => return response,
+    };
+
+    // 目标版本：指定修订或文章当前内容
+    let (to_label, to_title, to_content) = match query.get("to").filter(|s| s.as_str() != "current") {
+        Some(to) => {
+            let to_id: i64 = match to.parse() {
+                Ok(id) => id,
+                Err(_) => {
+                    return HttpResponse::BadRequest().json(serde_json::json!({
+                        "success": false,
+                        "message": "to 参数无效"
+                    }));
+                }
+            };
+            match load_revision(&revision_repo, &uuid, to_id).await {
+                Ok(r) => (format!("revision #{}", to_id), r.title, r.original_content),
+                Err(response) => return response,
+            }
+        }
+        None => {
+            let passage_repo = PassageRepository::new(repo.get_pool().clone());
+            match passage_repo.get_by_uuid(&uuid).await {
+                Ok(p) => ("current".to_string(), p.title, p.original_content.unwrap_or_default()),
+                Err(_) => {
+                    return HttpResponse::NotFound().json(serde_json::json!({
+                        "success": false,
+                        "message": "文章不存在"
+                    }));
+                }
+            }
+        }
+    };
+
+    let text_diff = TextDiff::from_lines(&from.original_content, &to_content);
+    let (lines, insertions, deletions) = diff_lines(&text_diff);
+
+    let from_label = format!("revision #{}", from_id);
+    let unified = text_diff.unified_diff()
+        .context_radius(3)
+        .header(&from_label, &to_label)
+        .to_string();
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "data": {
+            "from": from_id,
+            "to": to_label,
+            "from_title": from.title,
+            "to_title": to_title,
+            "insertions": insertions,
+            "deletions": deletions,
+            "lines": lines,
+            "unified": unified
+        }
+    }))
+}
+
+/// 恢复到指定修订（管理员），同时重写 Markdown 文件
+pub async fn restore(
+    repo: web::Data<Arc<dyn Repository>>,
+    path: web::Path<(String, i64)>,
+    http_req: HttpRequest,
+) -> HttpResponse {
+    use super::passage::{update_markdown_file, update_markdown_file_name};
+
+    // 鉴权检查
+    if http_req.cookie("auth_token").is_none() {
+        return crate::middleware::auth::missing_token_response();
+    }
+    let username = match crate::middleware::auth::check_admin_auth(&http_req) {
+        Some((_, username, _)) => username,
+        None => return crate::middleware::auth::forbidden_response(),
+    };
+
+    let (uuid, id) = path.into_inner();
+    let passage_repo = PassageRepository::new(repo.get_pool().clone());
+    let revision_repo = PassageRevisionRepository::new(repo.get_pool().clone());
+
+    let revision = match load_revision(&revision_repo, &uuid, id).await {
+        Ok(r) => r,
+        Err(response) => return response,
+    };
+
+    let mut passage = match passage_repo.get_by_uuid(&uuid).await {
+        Ok(p) => p,
+        Err(_) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "success": false,
+                "message": "文章不存在"
+            }));
+        }
+    };
+    let previous = passage.clone();
+
+    let trusted = passage_repo.is_trusted_author(passage.author_id).await;
+    apply_revision(&mut passage, revision, trusted);
+
+    // 重写 Markdown 文件，标题不同时同时重命名
+    if let Some(file_path) = passage.file_path.clone() {
+        if passage.title != previous.title {
+            passage.file_path = Some(update_markdown_file_name(&file_path, &passage));
+        } else if let Err(e) = update_markdown_file(&file_path, &passage) {
+            eprintln!("恢复修订时写入 Markdown 文件失败: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "message": format!("写入 Markdown 文件失败: {}", e)
+            }));
+        }
+    }
+
+    if let Err(e) = passage_repo.update(&passage).await {
+        eprintln!("恢复修订失败: {}", e);
+        return HttpResponse::InternalServerError().json(serde_json::json!({
+            "success": false,
+            "message": "恢复修订失败"
+        }));
+    }
+
+    if let Err(e) = revision_repo.record_change(Some(&previous), &passage, &username, "restore").await {
+        eprintln!("记录文章修订失败: {}", e);
+    }
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "message": format!("已恢复到修订 {}", id)
+    }))
+}
+
+/// 把修订中的内容和元数据恢复到文章上，`trusted` 为作者是否可信
+fn apply_revision(passage: &mut crate::db::models::Passage, revision: PassageRevision, trusted: bool) {
+    passage.title = revision.title;
+    passage.content = crate::markdown::render_passage(&revision.original_content, trusted);
+    passage.original_content = Some(revision.original_content);
+    passage.summary = revision.summary;
+    passage.tags = revision.tags;
+    passage.category = revision.category;
+    passage.updated_at = Utc::now();
+}
+
+/// 整理按行比较两段 Markdown 的结果，返回差异行以及新增、删除的行数
+fn diff_lines<'a>(text_diff: &similar::TextDiff<'a, 'a, '_, str>) -> (Vec<DiffLine>, usize, usize) {
+    use similar::ChangeTag;
+
+    let mut insertions = 0;
+    let mut deletions = 0;
+    let lines = text_diff.iter_all_changes()
+        .map(|change| {
+            let r#type = match change.tag() {
+                ChangeTag::Equal => "equal",
+                ChangeTag::Insert => {
+                    insertions += 1;
+                    "insert"
+                }
+                ChangeTag::Delete => {
+                    deletions += 1;
+                    "delete"
+                }
+            };
+            DiffLine {
+                r#type: r#type.to_string(),
+                old_line: change.old_index().map(|i| i + 1),
+                new_line: change.new_index().map(|i| i + 1),
+                content: change.value().trim_end_matches(['\r', '\n']).to_string(),
+            }
+        })
+        .collect();
+    (lines, insertions, deletions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::Passage;
+
+    #[test]
+    fn test_diff_lines() {
+        let (lines, insertions, deletions) = diff_lines(&similar::TextDiff::from_lines("a\nb\nc\n", "a\nB\nc\nd\n"));
+        let summary: Vec<(&str, Option<usize>, Option<usize>, &str)> = lines
+            .iter()
+            .map(|l| (l.r#type.as_str(), l.old_line, l.new_line, l.content.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("equal", Some(1), Some(1), "a"),
+                ("delete", Some(2), None, "b"),
+                ("insert", None, Some(2), "B"),
+                ("equal", Some(3), Some(3), "c"),
+                ("insert", None, Some(4), "d"),
+            ]
+        );
+        assert_eq!((insertions, deletions), (2, 1));
+
+        let (lines, insertions, deletions) = diff_lines(&similar::TextDiff::from_lines("same\n", "same\n"));
+        assert_eq!(lines.len(), 1);
+        assert_eq!((insertions, deletions), (0, 0));
+    }
+
+    #[test]
+    fn test_apply_revision_restores_content_and_metadata() {
+        let mut passage = Passage::sample("新标题", "新正文");
+        passage.status = "draft".to_string();
+        let revision = PassageRevision {
+            id: Some(1),
+            passage_uuid: "1".to_string(),
+            title: "旧标题".to_string(),
+            original_content: "# 旧正文".to_string(),
+            summary: Some("旧摘要".to_string()),
+            tags: r#"["rust"]"#.to_string(),
+            category: "技术".to_string(),
+            editor: "admin".to_string(),
+            source: "api".to_string(),
+            created_at: Utc::now(),
+        };
+
+        let mut untrusted = passage.clone();
+        apply_revision(&mut untrusted, PassageRevision { original_content: "<script>x()</script>旧正文".to_string(), ..revision.clone() }, false);
+        assert!(!untrusted.content.contains("<script>"));
+
+        apply_revision(&mut passage, revision, true);
+        assert_eq!(passage.title, "旧标题");
+        assert_eq!(passage.original_content.as_deref(), Some("# 旧正文"));
+        assert!(passage.content.contains("旧正文</h1>"));
+        assert_eq!(passage.summary.as_deref(), Some("旧摘要"));
+        assert_eq!(passage.tags, r#"["rust"]"#);
+        assert_eq!(passage.category, "技术");
+        // 修订中没有保存的字段保持不变
+        assert_eq!(passage.status, "draft");
+    }
+}
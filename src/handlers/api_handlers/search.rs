@@ -0,0 +1,124 @@
+use actix_web::{web, HttpResponse};
+use serde::Serialize;
+use crate::db::repositories::Repository;
+use crate::db::fts;
+use std::sync::Arc;
+
+/// 单页最多返回的结果数
+const MAX_LIMIT: i64 = 50;
+
+/// 搜索结果响应
+#[derive(Debug, Serialize)]
+pub struct SearchResultResponse {
+    pub id: i64,
+    pub uuid: String,
+    pub title: String,
+    pub title_highlight: String,  // 带 <mark> 高亮的标题（已转义）
+    pub snippet: String,          // 带 <mark> 高亮的正文片段（已转义）
+    pub summary: Option<String>,
+    pub author: String,
+    pub tags: String,
+    pub category: String,
+    pub cover_image: Option<String>,
+    pub published_at: Option<String>,
+    pub created_at: String,
+    pub score: f64,
+}
+
+/// 全文搜索（公开）
+pub async fn search(
+    repo: web::Data<Arc<dyn Repository>>,
+    query: web::Query<std::collections::HashMap<String, String>>,
+) -> HttpResponse {
+    let keyword = query.get("q").map(|q| q.trim()).unwrap_or("");
+    if keyword.is_empty() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "success": false,
+            "message": "搜索关键词不能为空"
+        }));
+    }
+
+    // 解析分页参数
+    let limit: i64 = query.get("limit").and_then(|l| l.parse().ok()).unwrap_or(10).clamp(1, MAX_LIMIT);
+    let page: i64 = query.get("page").and_then(|p| p.parse().ok()).unwrap_or(1).max(1);
+    let offset = (page - 1) * limit;
+
+    // 关键词中没有可搜索的词（例如只有标点）时直接返回空结果
+    let match_query = match fts::build_match_query(keyword) {
+        Some(q) => q,
+        None => {
+            return HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "data": Vec::<SearchResultResponse>::new(),
+                "pagination": {
+                    "page": page,
+                    "limit": limit,
+                    "total": 0,
+                    "total_pages": 0,
+                    "has_more": false
+                }
+            }));
+        }
+    };
+
+    let conn = match repo.get_pool().get() {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("获取数据库连接失败: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "message": "搜索失败"
+            }));
+        }
+    };
+
+    let hits = match fts::search(&conn, &match_query, limit, offset) {
+        Ok(h) => h,
+        Err(e) => {
+            eprintln!("全文搜索失败: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "message": "搜索失败"
+            }));
+        }
+    };
+
+    let total = match fts::count(&conn, &match_query) {
+        Ok(c) => c,
+        Err(_) => hits.len() as i64,
+    };
+
+    let data: Vec<SearchResultResponse> = hits.into_iter()
+        .map(|h| SearchResultResponse {
+            id: h.id,
+            uuid: h.uuid,
+            title: h.title,
+            title_highlight: h.title_highlight,
+            snippet: h.snippet,
+            summary: h.summary,
+            author: h.author,
+            tags: h.tags,
+            category: h.category,
+            cover_image: h.cover_image,
+            published_at: h.published_at.map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string()),
+            created_at: h.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            score: h.score,
+        })
+        .collect();
+
+    let total_pages = (total + limit - 1) / limit;
+
+    HttpResponse::Ok()
+        .insert_header(("Cache-Control", "public, max-age=60"))
+        .json(serde_json::json!({
+            "success": true,
+            "data": data,
+            "pagination": {
+                "page": page,
+                "limit": limit,
+                "total": total,
+                "total_pages": total_pages,
+                "has_more": page < total_pages
+            }
+        }))
+}
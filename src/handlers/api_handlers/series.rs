@@ -0,0 +1,543 @@
+use actix_web::{web, HttpResponse};
+use serde::{Deserialize, Serialize};
+use crate::db::repositories::{PassageRepository, Repository, SeriesRepository};
+use crate::db::models::{Passage, Series};
+use std::sync::Arc;
+use chrono::Utc;
+
+/// 系列响应
+#[derive(Debug, Serialize)]
+pub struct SeriesResponse {
+    pub id: i64,
+    pub title: String,
+    pub description: String,
+    pub cover_image: Option<String>,
+    pub sort_order: i32,
+    pub passage_count: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// 系列中的文章
+#[derive(Debug, Serialize)]
+pub struct SeriesPassageResponse {
+    pub position: usize,  // 从 1 开始
+    pub id: i64,
+    pub uuid: String,
+    pub title: String,
+    pub summary: Option<String>,
+    pub status: String,
+    pub slug: Option<String>,
+    pub permalink: Option<String>,
+    pub published_at: Option<String>,
+}
+
+/// 文章详情中的系列信息（当前位置及上一篇、下一篇）
+#[derive(Debug, Serialize)]
+pub struct SeriesNavigation {
+    pub id: i64,
+    pub title: String,
+    pub description: String,
+    pub cover_image: Option<String>,
+    pub position: usize,  // 从 1 开始
+    pub total: usize,
+    pub previous: Option<SeriesPassageLink>,
+    pub next: Option<SeriesPassageLink>,
+}
+
+/// 上一篇、下一篇链接
+#[derive(Debug, Serialize)]
+pub struct SeriesPassageLink {
+    pub id: i64,
+    pub uuid: String,
+    pub title: String,
+    pub permalink: Option<String>,
+}
+
+/// 创建系列请求
+#[derive(Debug, Deserialize)]
+pub struct CreateSeriesRequest {
+    pub title: String,
+    pub description: Option<String>,
+    pub cover_image: Option<String>,
+    pub sort_order: Option<i32>,
+    pub passages: Option<Vec<String>>,  // 按顺序排列的文章 UUID
+}
+
+/// 更新系列请求
+#[derive(Debug, Deserialize)]
+pub struct UpdateSeriesRequest {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub cover_image: Option<String>,
+    pub sort_order: Option<i32>,
+    pub passages: Option<Vec<String>>,  // 提供时覆盖原有文章及顺序
+}
+
+fn series_response(series: Series, passage_count: i64) -> SeriesResponse {
+    SeriesResponse {
+        id: series.id.unwrap_or(0),
+        title: series.title,
+        description: series.description,
+        cover_image: series.cover_image,
+        sort_order: series.sort_order,
+        passage_count,
+        created_at: series.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        updated_at: series.updated_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+    }
+}
+
+fn passage_responses(passages: Vec<Passage>) -> Vec<SeriesPassageResponse> {
+    passages.into_iter()
+        .enumerate()
+        .map(|(i, p)| SeriesPassageResponse {
+            position: i + 1,
+            permalink: p.permalink(),
+            id: p.id.unwrap_or(0),
+            uuid: p.uuid.unwrap_or_default(),
+            title: p.title,
+            summary: p.summary,
+            status: p.status,
+            slug: p.slug,
+            published_at: p.published_at.map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string()),
+        })
+        .collect()
+}
+
+fn passage_link(passage: &Passage) -> SeriesPassageLink {
+    SeriesPassageLink {
+        id: passage.id.unwrap_or(0),
+        uuid: passage.uuid.clone().unwrap_or_default(),
+        title: passage.title.clone(),
+        permalink: passage.permalink(),
+    }
+}
+
+/// 获取文章所在系列的导航信息
+///
+/// 只计算访客可见的文章（当前文章除外，以便预览时也能看到所在位置）
+pub(crate) async fn navigation_for(repo: &Arc<dyn Repository>, passage: &Passage) -> Option<SeriesNavigation> {
+    let uuid = passage.uuid.as_deref()?;
+    let series_repo = SeriesRepository::new(repo.get_pool().clone());
+
+    let series = series_repo.get_by_passage_uuid(uuid).await.ok()??;
+    let members: Vec<Passage> = series_repo.get_passages(series.id?).await.ok()?
+        .into_iter()
+        .filter(|p| p.uuid.as_deref() == Some(uuid) || p.is_publicly_visible())
+        .collect();
+    let index = members.iter().position(|p| p.uuid.as_deref() == Some(uuid))?;
+
+    Some(SeriesNavigation {
+        id: series.id.unwrap_or(0),
+        title: series.title,
+        description: series.description,
+        cover_image: series.cover_image,
+        position: index + 1,
+        total: members.len(),
+        previous: index.checked_sub(1).map(|i| passage_link(&members[i])),
+        next: members.get(index + 1).map(passage_link),
+    })
+}
+
+/// 校验文章 UUID 列表（去重，且文章必须存在）
+async fn validate_passages(repo: &Arc<dyn Repository>, uuids: &[String]) -> Result<Vec<String>, HttpResponse> {
+    let passage_repo = PassageRepository::new(repo.get_pool().clone());
+    let mut result: Vec<String> = Vec::with_capacity(uuids.len());
+
+    for uuid in uuids {
+        let uuid = uuid.trim();
+        if result.iter().any(|u| u == uuid) {
+            continue;
+        }
+        if passage_repo.get_by_uuid(uuid).await.is_err() {
+            return Err(HttpResponse::BadRequest().json(serde_json::json!({
+                "success": false,
+                "message": format!("文章 {} 不存在", uuid)
+            })));
+        }
+        result.push(uuid.to_string());
+    }
+
+    Ok(result)
+}
+
+/// 获取系列详情（公开，只包含访客可见的文章）
+pub async fn get(
+    repo: web::Data<Arc<dyn Repository>>,
+    path: web::Path<i64>,
+) -> HttpResponse {
+    let id = path.into_inner();
+    let series_repo = SeriesRepository::new(repo.get_pool().clone());
+
+    let series = match series_repo.get_by_id(id).await {
+        Ok(s) => s,
+        Err(_) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "success": false,
+                "message": "系列不存在"
+            }));
+        }
+    };
+
+    let passages: Vec<Passage> = match series_repo.get_passages(id).await {
+        Ok(p) => p.into_iter().filter(|p| p.is_publicly_visible()).collect(),
+        Err(e) => {
+            eprintln!("获取系列文章失败: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "message": "获取系列失败"
+            }));
+        }
+    };
+
+    // 没有公开文章的系列对访客不可见
+    if passages.is_empty() {
+        return HttpResponse::NotFound().json(serde_json::json!({
+            "success": false,
+            "message": "系列不存在"
+        }));
+    }
+
+    let count = passages.len() as i64;
+    HttpResponse::Ok()
+        .insert_header(("Cache-Control", "public, max-age=300"))
+        .json(serde_json::json!({
+            "success": true,
+            "data": {
+                "series": series_response(series, count),
+                "passages": passage_responses(passages)
+            }
+        }))
+}
+
+/// 获取所有系列（管理员）
+pub async fn admin_list(
+    repo: web::Data<Arc<dyn Repository>>,
+    query: web::Query<std::collections::HashMap<String, String>>,
+    http_req: actix_web::HttpRequest,
+) -> HttpResponse {
+    // 鉴权检查
+    if http_req.cookie("auth_token").is_none() {
+        return crate::middleware::auth::missing_token_response();
+    }
+    if crate::middleware::auth::check_admin_auth(&http_req).is_none() {
+        return crate::middleware::auth::forbidden_response();
+    }
+    let series_repo = SeriesRepository::new(repo.get_pool().clone());
+
+    // 解析分页参数
+    let limit: i64 = query.get("limit").and_then(|l| l.parse().ok()).unwrap_or(100);
+    let offset: i64 = query.get("offset").and_then(|o| o.parse().ok()).unwrap_or(0);
+
+    let series_list = match series_repo.get_all(limit, offset).await {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("获取系列失败: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "message": "获取系列失败"
+            }));
+        }
+    };
+
+    let total = match series_repo.count().await {
+        Ok(c) => c,
+        Err(_) => series_list.len() as i64,
+    };
+
+    let mut data = Vec::with_capacity(series_list.len());
+    for series in series_list {
+        let count = series_repo.count_passages(series.id.unwrap_or(0)).await.unwrap_or(0);
+        data.push(series_response(series, count));
+    }
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "data": data,
+        "total": total
+    }))
+}
+
+/// 获取系列详情（管理员，包含全部文章）
+pub async fn admin_get(
+    repo: web::Data<Arc<dyn Repository>>,
+    path: web::Path<i64>,
+    http_req: actix_web::HttpRequest,
+) -> HttpResponse {
+    // 鉴权检查
+    if http_req.cookie("auth_token").is_none() {
+        return crate::middleware::auth::missing_token_response();
+    }
+    if crate::middleware::auth::check_admin_auth(&http_req).is_none() {
+        return crate::middleware::auth::forbidden_response();
+    }
+    let id = path.into_inner();
+    let series_repo = SeriesRepository::new(repo.get_pool().clone());
+
+    let series = match series_repo.get_by_id(id).await {
+        Ok(s) => s,
+        Err(_) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "success": false,
+                "message": "系列不存在"
+            }));
+        }
+    };
+    let passages = series_repo.get_passages(id).await.unwrap_or_default();
+
+    let count = passages.len() as i64;
+    HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "data": {
+            "series": series_response(series, count),
+            "passages": passage_responses(passages)
+        }
+    }))
+}
+
+/// 创建系列
+pub async fn create(
+    repo: web::Data<Arc<dyn Repository>>,
+    req: web::Json<CreateSeriesRequest>,
+    http_req: actix_web::HttpRequest,
+) -> HttpResponse {
+    // 鉴权检查
+    if http_req.cookie("auth_token").is_none() {
+        return crate::middleware::auth::missing_token_response();
+    }
+    if crate::middleware::auth::check_admin_auth(&http_req).is_none() {
+        return crate::middleware::auth::forbidden_response();
+    }
+
+    if req.title.trim().is_empty() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "success": false,
+            "message": "系列标题不能为空"
+        }));
+    }
+
+    let passages = match &req.passages {
+        Some(uuids) => match validate_passages(&repo, uuids).await {
+            Ok(p) => Some(p),
+            Err(response) => return response,
+        },
+        None => None,
+    };
+
+    let series_repo = SeriesRepository::new(repo.get_pool().clone());
+    let now = Utc::now();
+    let series = Series {
+        id: None,
+        title: req.title.trim().to_string(),
+        description: req.description.clone().unwrap_or_default(),
+        cover_image: req.cover_image.clone().filter(|c| !c.trim().is_empty()),
+        sort_order: req.sort_order.unwrap_or(0),
+        created_at: now,
+        updated_at: now,
+    };
+
+    let id = match series_repo.create(&series).await {
+        Ok(id) => id,
+        Err(e) => {
+            eprintln!("创建系列失败: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "message": "创建系列失败"
+            }));
+        }
+    };
+
+    if let Some(passages) = passages {
+        if let Err(e) = series_repo.set_passages(id, &passages).await {
+            eprintln!("设置系列文章失败: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "message": "设置系列文章失败"
+            }));
+        }
+    }
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "message": "系列创建成功",
+        "data": serde_json::json!({"id": id})
+    }))
+}
+
+/// 更新系列
+pub async fn update(
+    repo: web::Data<Arc<dyn Repository>>,
+    path: web::Path<i64>,
+    req: web::Json<UpdateSeriesRequest>,
+    http_req: actix_web::HttpRequest,
+) -> HttpResponse {
+    // 鉴权检查
+    if http_req.cookie("auth_token").is_none() {
+        return crate::middleware::auth::missing_token_response();
+    }
+    if crate::middleware::auth::check_admin_auth(&http_req).is_none() {
+        return crate::middleware::auth::forbidden_response();
+    }
+    let id = path.into_inner();
+    let series_repo = SeriesRepository::new(repo.get_pool().clone());
+
+    // 先获取现有系列
+    let mut series = match series_repo.get_by_id(id).await {
+        Ok(s) => s,
+        Err(_) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "success": false,
+                "message": "系列不存在"
+            }));
+        }
+    };
+
+    let passages = match &req.passages {
+        Some(uuids) => match validate_passages(&repo, uuids).await {
+            Ok(p) => Some(p),
+            Err(response) => return response,
+        },
+        None => None,
+    };
+
+    // 更新字段
+    if let Some(ref title) = req.title {
+        if title.trim().is_empty() {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "success": false,
+                "message": "系列标题不能为空"
+            }));
+        }
+        series.title = title.trim().to_string();
+    }
+    if let Some(ref description) = req.description {
+        series.description = description.clone();
+    }
+    if let Some(ref cover_image) = req.cover_image {
+        series.cover_image = Some(cover_image.clone()).filter(|c| !c.trim().is_empty());
+    }
+    if let Some(sort_order) = req.sort_order {
+        series.sort_order = sort_order;
+    }
+    series.updated_at = Utc::now();
+
+    if let Err(e) = series_repo.update(&series).await {
+        eprintln!("更新系列失败: {}", e);
+        return HttpResponse::InternalServerError().json(serde_json::json!({
+            "success": false,
+            "message": "更新系列失败"
+        }));
+    }
+
+    if let Some(passages) = passages {
+        if let Err(e) = series_repo.set_passages(id, &passages).await {
+            eprintln!("设置系列文章失败: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "message": "设置系列文章失败"
+            }));
+        }
+    }
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "message": "系列更新成功"
+    }))
+}
+
+/// 删除系列（系列中的文章保留）
+pub async fn delete(
+    repo: web::Data<Arc<dyn Repository>>,
+    path: web::Path<i64>,
+    http_req: actix_web::HttpRequest,
+) -> HttpResponse {
+    // 鉴权检查
+    if http_req.cookie("auth_token").is_none() {
+        return crate::middleware::auth::missing_token_response();
+    }
+    if crate::middleware::auth::check_admin_auth(&http_req).is_none() {
+        return crate::middleware::auth::forbidden_response();
+    }
+    let id = path.into_inner();
+    let series_repo = SeriesRepository::new(repo.get_pool().clone());
+
+    if series_repo.get_by_id(id).await.is_err() {
+        return HttpResponse::NotFound().json(serde_json::json!({
+            "success": false,
+            "message": "系列不存在"
+        }));
+    }
+
+    match series_repo.delete(id).await {
+        Ok(_) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "message": "系列删除成功"
+        })),
+        Err(e) => {
+            eprintln!("删除系列失败: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "message": "删除系列失败"
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_navigation_links_previous_and_next() {
+        let pool = crate::db::init::test_pool();
+        let passage_repo = PassageRepository::new(pool.clone());
+        let series_repo = SeriesRepository::new(pool.clone());
+
+        let mut members = Vec::new();
+        for (title, status) in [("一", "published"), ("草稿", "draft"), ("二", "published"), ("三", "published")] {
+            let mut passage = Passage::sample(title, "正文");
+            passage.status = status.to_string();
+            let id = passage_repo.create(&passage).await.unwrap();
+            members.push(passage_repo.get_by_id(id).await.unwrap());
+        }
+        let now = Utc::now();
+        let series_id = series_repo.create(&Series {
+            id: None,
+            title: "系列".to_string(),
+            description: String::new(),
+            cover_image: None,
+            sort_order: 0,
+            created_at: now,
+            updated_at: now,
+        }).await.unwrap();
+        let uuids: Vec<String> = members.iter().map(|p| p.uuid.clone().unwrap()).collect();
+        series_repo.set_passages(series_id, &uuids).await.unwrap();
+
+        let repo: Arc<dyn Repository> = Arc::new(passage_repo);
+
+        // 草稿对访客不可见，跳过草稿链接到下一篇
+        let nav = navigation_for(&repo, &members[0]).await.unwrap();
+        assert_eq!((nav.position, nav.total), (1, 3));
+        assert!(nav.previous.is_none());
+        assert_eq!(nav.next.unwrap().title, "二");
+
+        let nav = navigation_for(&repo, &members[2]).await.unwrap();
+        assert_eq!(nav.position, 2);
+        assert_eq!(nav.previous.unwrap().title, "一");
+        assert_eq!(nav.next.unwrap().title, "三");
+
+        let nav = navigation_for(&repo, &members[3]).await.unwrap();
+        assert_eq!(nav.previous.unwrap().title, "二");
+        assert!(nav.next.is_none());
+
+        // 预览草稿时也能看到所在位置
+        let nav = navigation_for(&repo, &members[1]).await.unwrap();
+        assert_eq!((nav.position, nav.total), (2, 4));
+        assert_eq!(nav.previous.unwrap().title, "一");
+
+        // 不属于任何系列的文章没有导航
+        let id = PassageRepository::new(pool).create(&Passage::sample("单篇", "正文")).await.unwrap();
+        let single = PassageRepository::new(repo.get_pool()).get_by_id(id).await.unwrap();
+        assert!(navigation_for(&repo, &single).await.is_none());
+    }
+}
@@ -1,6 +1,6 @@
 use actix_web::{web, HttpResponse};
 use serde::Serialize;
-use crate::db::repositories::{PassageRepository, Repository};
+use crate::db::repositories::{PassageRepository, PassageRevisionRepository, Repository};
 use std::sync::Arc;
 use std::path::{Path, PathBuf};
 use std::fs;
@@ -13,6 +13,9 @@ pub struct SyncResponse {
     pub message: String,
 }
 
+/// 文件同步产生的修订记录的修改人
+const SYNC_EDITOR: &str = "system";
+
 /// 同步结果
 #[derive(Debug)]
 pub struct SyncResult {
@@ -117,10 +120,89 @@ async fn sync_directory_async(
     
     // 清理数据库中不存在的文件记录
     cleanup_orphaned_passages(passage_repo, dir, deleted_count).await?;
+
+    // 同步完成后统一重算相关文章
+    crate::cache::invalidate_passages();
     
     Ok(())
 }
 
+/// 同步单个 markdown 文件（供文件监听使用）
+pub async fn sync_file(passage_repo: &PassageRepository, path: &Path) -> Result<(), String> {
+    let mut synced_count = 0;
+    let mut updated_count = 0;
+    sync_markdown_file_async(path, passage_repo, &mut synced_count, &mut updated_count).await
+}
+
+/// 文件或目录改名后更新对应文章的路径，文章 UUID、评论和阅读记录保持不变
+///
+/// 新路径上已有的文章记录视为被覆盖，先删除；返回移动的文章数量
+pub fn move_path(from: &str, to: &str) -> Result<usize, String> {
+    use crate::db::get_db_pool_sync;
+
+    let pool = get_db_pool_sync().map_err(|e| format!("获取数据库连接失败: {}", e))?;
+    let mut conn = pool.get().map_err(|e| format!("获取连接失败: {}", e))?;
+    let moved = move_rows(&mut conn, from, to)?;
+    if moved > 0 {
+        println!("🚚 文章已移动: {} -> {}", from, to);
+    }
+    Ok(moved)
+}
+
+/// 在一个事务中把 `from` 下的文章路径改为 `to`
+///
+/// `from` 下没有文章时不做任何修改：通过接口改名时文件和数据库都已更新，
+/// 新路径上的记录就是被移动的文章本身，不能当作被覆盖的文章删除
+fn move_rows(conn: &mut rusqlite::Connection, from: &str, to: &str) -> Result<usize, String> {
+    use rusqlite::params;
+
+    let tx = conn.transaction().map_err(|e| format!("开启事务失败: {}", e))?;
+    let pending: i64 = tx.query_row(
+        "SELECT COUNT(*) FROM passages WHERE file_path = ?1 OR substr(file_path, 1, length(?1) + 1) = ?1 || '/'",
+        [from],
+        |row| row.get(0),
+    ).map_err(|e| format!("查询文章失败: {}", e))?;
+    if pending == 0 {
+        return Ok(0);
+    }
+
+    delete_under(&tx, to)?;
+    let moved = tx.execute(
+        "UPDATE passages SET file_path = ?2 || substr(file_path, length(?1) + 1)
+         WHERE file_path = ?1 OR substr(file_path, 1, length(?1) + 1) = ?1 || '/'",
+        params![from, to],
+    ).map_err(|e| format!("更新文章路径失败: {}", e))?;
+
+    tx.commit().map_err(|e| format!("提交事务失败: {}", e))?;
+    Ok(moved)
+}
+
+/// 删除文件或目录对应的文章记录，返回删除的数量
+pub fn remove_path(path: &str) -> Result<usize, String> {
+    use crate::db::get_db_pool_sync;
+
+    let pool = get_db_pool_sync().map_err(|e| format!("获取数据库连接失败: {}", e))?;
+    let conn = pool.get().map_err(|e| format!("获取连接失败: {}", e))?;
+    let deleted = delete_under(&conn, path)?;
+    if deleted > 0 {
+        println!("🗑️  已删除不存在的文章记录: {}", path);
+    }
+    Ok(deleted)
+}
+
+/// 删除路径本身及其下所有文件对应的文章，并清理搜索索引和标签关联
+fn delete_under(conn: &rusqlite::Connection, path: &str) -> Result<usize, String> {
+    let deleted = conn.execute(
+        "DELETE FROM passages WHERE file_path = ?1 OR substr(file_path, 1, length(?1) + 1) = ?1 || '/'",
+        [path],
+    ).map_err(|e| format!("删除失败: {}", e))?;
+    if deleted > 0 {
+        crate::db::fts::remove_orphans(conn).map_err(|e| format!("清理搜索索引失败: {}", e))?;
+        crate::db::passage_tags::remove_orphans(conn).map_err(|e| format!("清理标签关联失败: {}", e))?;
+    }
+    Ok(deleted)
+}
+
 /// 异步同步单个 markdown 文件
 async fn sync_markdown_file_async(
     path: &Path,
@@ -129,14 +211,20 @@ async fn sync_markdown_file_async(
     updated_count: &mut usize,
 ) -> Result<(), String> {
     // 读取文件内容
-    let content = fs::read_to_string(path)
+    let raw_content = fs::read_to_string(path)
         .map_err(|e| format!("读取文件失败: {}", e))?;
     
-    // 提取标题（从文件名）
-    let title = path.file_stem()
-        .and_then(|s| s.to_str())
-        .unwrap_or("未命名文章")
-        .to_string();
+    // 拆分 front matter 和正文
+    let (meta, body) = crate::front_matter::parse(&raw_content)?;
+    let content = body.to_string();
+    
+    // 提取标题（优先 front matter，其次文件名）
+    let title = meta.title.clone().unwrap_or_else(|| {
+        path.file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("未命名文章")
+            .to_string()
+    });
     
     // 获取相对路径
     let file_path = path.to_string_lossy().to_string();
@@ -144,20 +232,47 @@ async fn sync_markdown_file_async(
     // 从路径提取日期（格式：markdown/YYYY/MM/DD/filename.md）
     let created_at = extract_date_from_path(&file_path).unwrap_or_else(Utc::now);
     
-    // 转换 markdown 为 HTML
-    let html_content = convert_markdown_to_html(&content);
-    
-    // 生成摘要
-    let summary = extract_summary(&html_content);
+    // 检查是否已存在
+    // 先转换为 Option（Box<dyn Error> 不是 Send，不能跨越 await 持有）
+    let existing = passage_repo.get_by_file_path(&file_path).await.ok();
+    
+    // 转换 markdown 为 HTML，已有文章的作者不是管理员时需要清理（新文件归属站长）
+    let trusted = passage_repo.is_trusted_author(existing.as_ref().and_then(|p| p.author_id)).await;
+    let html_content = crate::markdown::render_passage(&content, trusted);
+    
+    // 摘要（优先 front matter，其次自动生成）
+    let summary = meta.summary.clone().or_else(|| Some(crate::markdown::extract_summary(&html_content)));
+    let reading = crate::reading::stats(&html_content);
+    
+    // 标签和分类写入对应的表
+    let tags_json = match &meta.tags {
+        Some(tags) => {
+            if !tags.is_empty() {
+                super::passage::ensure_tags_exist(tags).await?;
+            }
+            Some(serde_json::to_string(tags).unwrap_or_else(|_| "[]".to_string()))
+        }
+        None => None,
+    };
+    if let Some(category) = &meta.category {
+        super::passage::ensure_category_exist(category).await?;
+    }
     
-    // 不自动生成标签，保持为空数组
-    let tags_json = "[]".to_string();
+    // 解析发布时间（晚于当前时间时视为定时发布）
+    let published_at = meta.published_at_utc();
+    let expires_at = meta.expires_at_utc();
     
     let now = Utc::now();
     
-    // 检查是否已存在
-    if let Ok(existing) = passage_repo.get_by_file_path(&file_path).await {
-        // 更新现有文章 - 保留原有的标签和分类等元数据
+    // front matter 中的 slug（规范化后）
+    let slug = meta.slug.as_deref().and_then(crate::slug::normalize);
+    
+    let revision_repo = PassageRevisionRepository::new(passage_repo.get_pool());
+    if let Some(existing) = existing {
+        let previous = existing.clone();
+        let slug = available_slug(passage_repo, slug, existing.uuid.as_deref(), &file_path).await.or(existing.slug);
+        
+        // 更新现有文章 - front matter 中未指定的元数据保留原值
         let updated_passage = crate::db::models::Passage {
             id: existing.id,
             uuid: existing.uuid,
@@ -166,22 +281,35 @@ async fn sync_markdown_file_async(
             original_content: Some(content.clone()),
             summary,
             author: existing.author,
-            tags: existing.tags, // 保留原有标签
-            category: existing.category, // 保留原有分类
-            status: existing.status, // 保留原有状态
+            tags: tags_json.unwrap_or(existing.tags),
+            category: meta.category.unwrap_or(existing.category),
+            status: meta.status.unwrap_or(existing.status),
             file_path: Some(file_path.clone()),
-            visibility: existing.visibility, // 保留原有可见性
-            is_scheduled: existing.is_scheduled, // 保留原有定时发布设置
-            published_at: existing.published_at, // 保留原有发布时间
-            cover_image: existing.cover_image, // 保留原有封面
+            visibility: meta.visibility.unwrap_or(existing.visibility),
+            is_scheduled: published_at.map_or(existing.is_scheduled, |d| d > now),
+            published_at: published_at.or(existing.published_at),
+            cover_image: meta.cover_image.or(existing.cover_image),
             created_at: existing.created_at,
             updated_at: now,
+            expires_at: expires_at.or(existing.expires_at),
+            slug,
+            word_count: reading.word_count,
+            reading_minutes: reading.reading_minutes,
+            author_id: existing.author_id,
+            pinned: existing.pinned,
+            featured: existing.featured,
+            pin_order: existing.pin_order,
         };
         
         // 更新文章（使用 SQL 直接更新）
         update_passage(passage_repo, &updated_passage).await
             .map_err(|e| format!("更新文章失败: {}", e))?;
         
+        // 记录修订（内容未变化时不会新增记录）
+        if let Err(e) = revision_repo.record_change(Some(&previous), &updated_passage, SYNC_EDITOR, "sync").await {
+            eprintln!("记录文章修订失败 {}: {}", file_path, e);
+        }
+        
         *updated_count += 1;
         println!("✏️  已更新文章: {}", file_path);
     } else {
@@ -194,21 +322,37 @@ async fn sync_markdown_file_async(
             original_content: Some(content.clone()),
             summary,
             author: "Admin".to_string(),
-            tags: tags_json.clone(),
-            category: "未分类".to_string(),
-            status: "published".to_string(),
+            tags: tags_json.unwrap_or_else(|| "[]".to_string()),
+            category: meta.category.unwrap_or_else(|| "未分类".to_string()),
+            status: meta.status.unwrap_or_else(|| "published".to_string()),
             file_path: Some(file_path.clone()),
-            visibility: "public".to_string(),
-            is_scheduled: false,
-            published_at: None,
-            cover_image: Some("/img/passage-cover.webp".to_string()),
+            visibility: meta.visibility.unwrap_or_else(|| "public".to_string()),
+            is_scheduled: published_at.is_some_and(|d| d > now),
+            published_at,
+            cover_image: meta.cover_image.or_else(|| Some("/img/passage-cover.webp".to_string())),
             created_at,
             updated_at: now,
+            expires_at,
+            slug: available_slug(passage_repo, slug, None, &file_path).await,
+            word_count: reading.word_count,
+            reading_minutes: reading.reading_minutes,
+            author_id: default_author_id(passage_repo),
+            pinned: false,
+            featured: false,
+            pin_order: 0,
         };
         
-        passage_repo.create(&passage).await
+        let id = passage_repo.create(&passage).await
             .map_err(|e| format!("创建文章失败: {}", e))?;
         
+        // 记录首个修订
+        let created = passage_repo.get_by_id(id).await.ok();
+        if let Some(created) = created {
+            if let Err(e) = revision_repo.record_change(None, &created, SYNC_EDITOR, "sync").await {
+                eprintln!("记录文章修订失败 {}: {}", file_path, e);
+            }
+        }
+        
         *synced_count += 1;
         println!("✅ 已同步文章: {}", file_path);
     }
@@ -216,6 +360,33 @@ async fn sync_markdown_file_async(
     Ok(())
 }
 
+/// front matter 中的 slug 已被其他文章占用时忽略（保留原 slug 或自动生成）
+async fn available_slug(
+    passage_repo: &PassageRepository,
+    slug: Option<String>,
+    uuid: Option<&str>,
+    file_path: &str,
+) -> Option<String> {
+    let slug = slug?;
+    match passage_repo.is_slug_available(&slug, uuid).await {
+        Ok(true) => Some(slug),
+        Ok(false) => {
+            eprintln!("⚠️  slug \"{}\" 已被其他文章使用，忽略: {}", slug, file_path);
+            None
+        }
+        Err(e) => {
+            eprintln!("⚠️  检查 slug 失败 {}: {}", file_path, e);
+            None
+        }
+    }
+}
+
+/// 同步新建的文章归属站长（最早创建的管理员）
+fn default_author_id(passage_repo: &PassageRepository) -> Option<i64> {
+    let conn = passage_repo.get_pool().get().ok()?;
+    crate::db::authors::default_author_id(&conn).ok().flatten()
+}
+
 /// 从文件路径提取日期
 fn extract_date_from_path(file_path: &str) -> Option<DateTime<Utc>> {
     // 移除 markdown/ 前缀
@@ -241,25 +412,6 @@ fn extract_date_from_path(file_path: &str) -> Option<DateTime<Utc>> {
     None
 }
 
-/// 提取摘要
-fn extract_summary(html_content: &str) -> Option<String> {
-    use regex::Regex;
-    
-    // 移除 HTML 标签
-    let re = Regex::new(r"<[^>]*>").unwrap();
-    let text = re.replace_all(html_content, "");
-    
-    // 移除多余的空白
-    let text: String = text.split_whitespace().collect::<Vec<&str>>().join(" ");
-    
-    // 截取前 200 个字符
-    if text.chars().count() > 200 {
-        Some(text.chars().take(200).collect::<String>() + "...")
-    } else {
-        Some(text)
-    }
-}
-
 /// 更新文章
 async fn update_passage(
     _passage_repo: &PassageRepository,
@@ -272,18 +424,48 @@ async fn update_passage(
     let conn = pool.get().map_err(|e| format!("获取连接失败: {}", e))?;
     
     if let Some(id) = passage.id {
+        let old_slug: Option<String> = conn.query_row(
+            "SELECT slug FROM passages WHERE id = ?",
+            params![id],
+            |row| row.get(0),
+        ).map_err(|e| format!("查询文章失败: {}", e))?;
         conn.execute(
-            "UPDATE passages SET title = ?, content = ?, original_content = ?, summary = ?, tags = ?, updated_at = ? WHERE id = ?",
+            "UPDATE passages SET title = ?, content = ?, original_content = ?, summary = ?, tags = ?, category = ?, status = ?, visibility = ?, is_scheduled = ?, published_at = ?, cover_image = ?, updated_at = ?, expires_at = ?, slug = COALESCE(?, slug), word_count = ?, reading_minutes = ? WHERE id = ?",
             params![
                 &passage.title,
                 &passage.content,
                 &passage.original_content,
                 &passage.summary,
                 &passage.tags,
+                &passage.category,
+                &passage.status,
+                &passage.visibility,
+                &passage.is_scheduled,
+                &passage.published_at,
+                &passage.cover_image,
                 &passage.updated_at,
+                &passage.expires_at,
+                &passage.slug,
+                &passage.word_count,
+                &passage.reading_minutes,
                 id,
             ],
         ).map_err(|e| format!("更新失败: {}", e))?;
+
+        crate::db::passage_tags::set_passage_tags(&conn, id, &passage.tags)
+            .map_err(|e| format!("更新标签关联失败: {}", e))?;
+
+        // slug 变化时保留旧 slug 作为跳转
+        if let Some(uuid) = &passage.uuid {
+            crate::db::repositories::record_slug_change(&conn, uuid, old_slug.as_deref(), passage.slug.as_deref())
+                .map_err(|e| format!("记录 slug 跳转失败: {}", e))?;
+        }
+
+        // 同步全文索引
+        if let Some(uuid) = &passage.uuid {
+            crate::db::fts::index_passage(&conn, uuid, passage)
+                .map_err(|e| format!("更新搜索索引失败: {}", e))?;
+        }
     }
     
     Ok(())
@@ -326,22 +508,58 @@ async fn cleanup_orphaned_passages(
         }
     }
     
+    // 清理已删除文章的搜索索引
+    crate::db::fts::remove_orphans(&conn).map_err(|e| format!("清理搜索索引失败: {}", e))?;
+    crate::db::passage_tags::remove_orphans(&conn).map_err(|e| format!("清理标签关联失败: {}", e))?;
+    
     Ok(())
 }
 
-/// 将 Markdown 转换为 HTML
-fn convert_markdown_to_html(markdown: &str) -> String {
-    use pulldown_cmark::{Parser, html, Options};
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::Passage;
 
-    // 配置选项，启用表格和其他扩展
-    let mut options = Options::empty();
-    options.insert(Options::ENABLE_TABLES);
-    options.insert(Options::ENABLE_STRIKETHROUGH);
-    options.insert(Options::ENABLE_TASKLISTS);
+    async fn create_at(repo: &PassageRepository, title: &str, file_path: &str) -> String {
+        let mut passage = Passage::sample(title, "正文");
+        passage.file_path = Some(file_path.to_string());
+        let id = repo.create(&passage).await.unwrap();
+        repo.get_by_id(id).await.unwrap().uuid.unwrap()
+    }
+
+    fn file_paths(repo: &PassageRepository) -> Vec<(String, String)> {
+        let conn = repo.get_pool().get().unwrap();
+        let mut stmt = conn.prepare("SELECT uuid, file_path FROM passages ORDER BY file_path").unwrap();
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?))).unwrap().collect::<Result<_, _>>().unwrap()
+    }
 
-    let parser = Parser::new_ext(markdown, options);
-    let mut html_output = String::new();
-    html::push_html(&mut html_output, parser);
+    #[tokio::test]
+    async fn test_move_rows_keeps_passage_uuids() {
+        let repo = PassageRepository::new(crate::db::init::test_pool());
+        let a = create_at(&repo, "a", "markdown/2024/a.md").await;
+        let b = create_at(&repo, "b", "markdown/2024/b.md").await;
+        let old = create_at(&repo, "旧", "markdown/2025/a.md").await;
+        let mut conn = repo.get_pool().get().unwrap();
 
-    html_output
-}
\ No newline at end of file
+        // 移动目录时覆盖新路径上的记录
+        assert_eq!(move_rows(&mut conn, "markdown/2024", "markdown/2025").unwrap(), 2);
+        let paths = file_paths(&repo);
+        assert_eq!(paths, vec![(a.clone(), "markdown/2025/a.md".to_string()), (b, "markdown/2025/b.md".to_string())]);
+        assert!(!paths.iter().any(|(uuid, _)| *uuid == old));
+
+        // 单个文件改名
+        assert_eq!(move_rows(&mut conn, "markdown/2025/a.md", "markdown/2025/c.md").unwrap(), 1);
+        assert!(file_paths(&repo).contains(&(a, "markdown/2025/c.md".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_move_rows_after_api_rename() {
+        let repo = PassageRepository::new(crate::db::init::test_pool());
+        // 接口修改标题时已经把文件改名并保存了新路径，随后监听到同一次改名
+        let uuid = create_at(&repo, "新标题", "markdown/2024/新标题.md").await;
+        let mut conn = repo.get_pool().get().unwrap();
+
+        assert_eq!(move_rows(&mut conn, "markdown/2024/旧标题.md", "markdown/2024/新标题.md").unwrap(), 0);
+        assert_eq!(file_paths(&repo), vec![(uuid, "markdown/2024/新标题.md".to_string())]);
+    }
+}
@@ -1,6 +1,6 @@
 use actix_web::{web, HttpResponse};
 use serde::{Deserialize, Serialize};
-use crate::db::repositories::{TagRepository, PassageRepository, Repository};
+use crate::db::repositories::{TagRepository, Repository};
 use crate::db::models::Tag;
 use std::sync::Arc;
 use chrono::Utc;
@@ -22,9 +22,9 @@ pub struct TagResponse {
 /// 标签统计响应
 #[derive(Debug, Serialize)]
 pub struct TagCountResponse {
-    pub id: i32,
+    pub id: i64,
     pub name: String,
-    pub count: i32,
+    pub count: i64,
 }
 
 /// 创建标签请求
@@ -51,11 +51,11 @@ pub struct UpdateTagRequest {
 
 /// 获取所有标签及使用次数（公开）
 pub async fn list(repo: web::Data<Arc<dyn Repository>>) -> HttpResponse {
-    let passage_repo = PassageRepository::new(repo.get_pool().clone());
+    let tag_repo = TagRepository::new(repo.get_pool().clone());
     
-    // 从数据库获取所有文章的标签
-    let passages = match passage_repo.get_all(1000, 0).await {
-        Ok(p) => p,
+    // 通过文章-标签关联表统计公开文章的标签使用次数
+    let counts = match tag_repo.get_usage_counts().await {
+        Ok(c) => c,
         Err(e) => {
             eprintln!("获取标签失败: {}", e);
             return HttpResponse::InternalServerError().json(serde_json::json!({
@@ -65,22 +65,12 @@ pub async fn list(repo: web::Data<Arc<dyn Repository>>) -> HttpResponse {
         }
     };
     
-    // 统计标签使用次数
-    let mut tag_count: std::collections::HashMap<String, i32> = std::collections::HashMap::new();
-    for passage in passages {
-        let tags = parse_tags(&passage.tags);
-        for tag in tags {
-            *tag_count.entry(tag).or_insert(0) += 1;
-        }
-    }
-    
     // 转换为API响应格式
-    let data: Vec<TagCountResponse> = tag_count.into_iter()
-        .enumerate()
-        .map(|(i, (name, count))| TagCountResponse {
-            id: (i + 1) as i32,
-            name,
-            count,
+    let data: Vec<TagCountResponse> = counts.into_iter()
+        .map(|tag| TagCountResponse {
+            id: tag.id,
+            name: tag.name,
+            count: tag.count,
         })
         .collect();
     
@@ -266,9 +256,24 @@ pub async fn update(
         }
     };
     
-    // 更新字段
+    // 更新字段（改名时不能与其他标签重名，文章中的标签会随之更新）
     if let Some(ref name) = req.name {
-        tag.name = name.clone();
+        let name = name.trim();
+        if name.is_empty() {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "success": false,
+                "message": "标签名不能为空"
+            }));
+        }
+        if let Ok(existing) = tag_repo.get_by_name(name).await {
+            if existing.id != tag.id {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "success": false,
+                    "message": "标签名已存在"
+                }));
+            }
+        }
+        tag.name = name.to_string();
     }
     if let Some(ref description) = req.description {
         tag.description = description.clone();
@@ -382,21 +387,3 @@ pub async fn delete_batch(
         }
     }
 }
-
-/// 解析标签 JSON 字符串
-fn parse_tags(tags_str: &str) -> Vec<String> {
-    if tags_str.is_empty() || tags_str == "[]" {
-        return Vec::new();
-    }
-    
-    // 尝试解析 JSON
-    if let Ok(tags) = serde_json::from_str::<Vec<String>>(tags_str) {
-        return tags;
-    }
-    
-    // 如果解析失败，按逗号分割
-    tags_str.split(',')
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .collect()
-}
\ No newline at end of file
@@ -0,0 +1,208 @@
+use actix_web::{web, HttpResponse, HttpRequest};
+use actix_web::http::header::{self, HttpDate};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::db::repositories::{PassageRepository, PublishedFilter, Repository, SettingRepository};
+use crate::feed::{self, FeedChannel, FeedFormat, FeedItem};
+
+/// 订阅源筛选条件
+enum FeedFilter {
+    All,
+    Category(String),
+    Tag(String),
+    Author(String),
+}
+
+/// 订阅源设置
+struct FeedSettings {
+    title: String,
+    description: String,
+    full_content: bool,
+    item_limit: usize,
+}
+
+/// 从数据库读取订阅源设置
+fn load_feed_settings(repo: &Arc<dyn Repository>) -> FeedSettings {
+    let mut settings = FeedSettings {
+        title: "RustBlog".to_string(),
+        description: "RustBlog - 使用 Rust + Actix-web 构建".to_string(),
+        full_content: false,
+        item_limit: 20,
+    };
+
+    if let Ok(conn) = repo.get_pool().get() {
+        if let Ok(Some(setting)) = SettingRepository::get(&conn, "template_name") {
+            settings.title = setting.value;
+        }
+        if let Ok(Some(setting)) = SettingRepository::get(&conn, "template_greting") {
+            settings.description = setting.value;
+        }
+        if let Ok(Some(setting)) = SettingRepository::get(&conn, "feed_full_content") {
+            settings.full_content = setting.value == "true";
+        }
+        if let Ok(Some(setting)) = SettingRepository::get(&conn, "feed_item_limit") {
+            if let Ok(limit) = setting.value.parse::<usize>() {
+                settings.item_limit = limit.clamp(1, 100);
+            }
+        }
+    }
+
+    settings
+}
+
+/// RSS 2.0 订阅源
+pub async fn rss(repo: web::Data<Arc<dyn Repository>>, req: HttpRequest) -> HttpResponse {
+    render_feed(&repo, &req, FeedFormat::Rss, FeedFilter::All).await
+}
+
+/// Atom 订阅源
+pub async fn atom(repo: web::Data<Arc<dyn Repository>>, req: HttpRequest) -> HttpResponse {
+    render_feed(&repo, &req, FeedFormat::Atom, FeedFilter::All).await
+}
+
+/// JSON Feed 订阅源
+pub async fn json(repo: web::Data<Arc<dyn Repository>>, req: HttpRequest) -> HttpResponse {
+    render_feed(&repo, &req, FeedFormat::Json, FeedFilter::All).await
+}
+
+/// 分类订阅源（/category/{name}/feed.xml | atom.xml | feed.json）
+pub async fn category_feed(
+    repo: web::Data<Arc<dyn Repository>>,
+    path: web::Path<(String, String)>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let (category, file) = path.into_inner();
+    match FeedFormat::from_file_name(&file) {
+        Some(format) => render_feed(&repo, &req, format, FeedFilter::Category(category)).await,
+        None => crate::templates::render_status_page(actix_web::http::StatusCode::NOT_FOUND).await,
+    }
+}
+
+/// 标签订阅源（/tag/{name}/feed.xml | atom.xml | feed.json）
+pub async fn tag_feed(
+    repo: web::Data<Arc<dyn Repository>>,
+    path: web::Path<(String, String)>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let (tag, file) = path.into_inner();
+    match FeedFormat::from_file_name(&file) {
+        Some(format) => render_feed(&repo, &req, format, FeedFilter::Tag(tag)).await,
+        None => crate::templates::render_status_page(actix_web::http::StatusCode::NOT_FOUND).await,
+    }
+}
+
+/// 作者订阅源（/author/{username}/feed.xml | atom.xml | feed.json）
+pub async fn author_feed(
+    repo: web::Data<Arc<dyn Repository>>,
+    path: web::Path<(String, String)>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let (username, file) = path.into_inner();
+    match FeedFormat::from_file_name(&file) {
+        Some(format) => render_feed(&repo, &req, format, FeedFilter::Author(username)).await,
+        None => crate::templates::render_status_page(actix_web::http::StatusCode::NOT_FOUND).await,
+    }
+}
+
+/// 生成订阅源，支持 ETag / If-Modified-Since 条件请求
+async fn render_feed(
+    repo: &Arc<dyn Repository>,
+    req: &HttpRequest,
+    format: FeedFormat,
+    filter: FeedFilter,
+) -> HttpResponse {
+    let settings = load_feed_settings(repo);
+    let passage_repo = PassageRepository::new(repo.get_pool().clone());
+
+    // 获取按分类/标签/作者筛选后的公开文章
+    let published_filter = match &filter {
+        FeedFilter::All => PublishedFilter::default(),
+        FeedFilter::Category(category) => PublishedFilter { category: Some(category), ..Default::default() },
+        FeedFilter::Tag(tag) => PublishedFilter { tag: Some(tag), ..Default::default() },
+        FeedFilter::Author(username) => PublishedFilter { author: Some(username), ..Default::default() },
+    };
+    let passages: Vec<_> = match passage_repo.get_all_public_filtered(&published_filter).await {
+        Ok(p) => p.into_iter().take(settings.item_limit).collect(),
+        Err(e) => {
+            eprintln!("生成订阅源失败: {}", e);
+            return HttpResponse::InternalServerError().body("生成订阅源失败");
+        }
+    };
+
+    // 分类、标签或作者下没有文章时返回 404
+    if passages.is_empty() && !matches!(filter, FeedFilter::All) {
+        return crate::templates::render_status_page(actix_web::http::StatusCode::NOT_FOUND).await;
+    }
+
+    let origin = crate::handlers::page_handlers::site_origin(req);
+    let (title, site_path) = match &filter {
+        FeedFilter::All => (settings.title.clone(), String::new()),
+        FeedFilter::Category(category) => (
+            format!("{} - 分类：{}", settings.title, category),
+            format!("/category/{}", urlencoding::encode(category)),
+        ),
+        FeedFilter::Tag(tag) => (
+            format!("{} - 标签：{}", settings.title, tag),
+            format!("/tag/{}", urlencoding::encode(tag)),
+        ),
+        FeedFilter::Author(username) => (
+            format!("{} - 作者：{}", settings.title, username),
+            format!("/author/{}", urlencoding::encode(username)),
+        ),
+    };
+
+    // 最后修改时间取文章更新时间的最大值（精确到秒，与 HTTP 日期一致）
+    let updated = passages.iter()
+        .map(|p| p.updated_at.max(p.published_at.unwrap_or(p.created_at)))
+        .max()
+        .unwrap_or_default();
+    let last_modified = UNIX_EPOCH + Duration::from_secs(updated.timestamp().max(0) as u64);
+
+    // ETag 覆盖格式、筛选条件、输出模式和每篇文章的更新时间
+    use md5::{Md5, Digest};
+    let mut etag_data = format!("{}:{}:{}:{}", format.file_name(), site_path, settings.full_content, settings.title);
+    for p in &passages {
+        etag_data.push_str(&format!("|{}:{}", p.id.unwrap_or(0), p.updated_at.timestamp_millis()));
+    }
+    let etag = format!("\"{:x}\"", Md5::digest(etag_data.as_bytes()));
+
+    if is_not_modified(req, &etag, last_modified) {
+        return HttpResponse::NotModified()
+            .insert_header((header::ETAG, etag))
+            .insert_header((header::LAST_MODIFIED, HttpDate::from(last_modified).to_string()))
+            .finish();
+    }
+
+    let channel = FeedChannel {
+        title,
+        description: settings.description.clone(),
+        site_url: format!("{}{}", origin, if site_path.is_empty() { "/" } else { &site_path }),
+        feed_url: format!("{}{}/{}", origin, site_path, format.file_name()),
+        updated,
+    };
+    let items: Vec<FeedItem> = passages.iter()
+        .map(|p| FeedItem::from_passage(p, &origin, settings.full_content))
+        .collect();
+
+    HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header((header::ETAG, etag))
+        .insert_header((header::LAST_MODIFIED, HttpDate::from(last_modified).to_string()))
+        .insert_header((header::CACHE_CONTROL, "public, max-age=600"))
+        .body(feed::render(format, &channel, &items))
+}
+
+/// 条件请求判断：优先使用 If-None-Match，没有时再比较 If-Modified-Since
+fn is_not_modified(req: &HttpRequest, etag: &str, last_modified: SystemTime) -> bool {
+    if let Some(if_none_match) = req.headers().get(header::IF_NONE_MATCH) {
+        return if_none_match.to_str()
+            .map(|value| value.split(',').any(|tag| tag.trim() == etag || tag.trim() == "*"))
+            .unwrap_or(false);
+    }
+
+    req.headers().get(header::IF_MODIFIED_SINCE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<HttpDate>().ok())
+        .map(|since| last_modified <= SystemTime::from(since))
+        .unwrap_or(false)
+}
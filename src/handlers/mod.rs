@@ -1,2 +1,4 @@
 pub mod page_handlers;
-pub mod api_handlers;
\ No newline at end of file
+pub mod api_handlers;
+pub mod feed_handlers;
+pub mod seo_handlers;
\ No newline at end of file
@@ -1,10 +1,16 @@
 use actix_web::{web, HttpResponse, HttpRequest};
+use actix_web::http::StatusCode;
 use actix_files::NamedFile;
+use std::sync::Arc;
+use crate::db::repositories::{AuthorRepository, PassageRepository, PublishedFilter, Repository};
 use crate::templates::{
     render_template,
+    render_status_page,
     create_index_context,
     create_passage_context,
+    create_passage_detail_context,
     create_collect_context,
+    create_taxonomy_context,
     create_about_context,
     create_friends_context,
     create_markdown_editor_context,
@@ -22,19 +28,276 @@ pub async fn passage_list() -> HttpResponse {
     render_template("passage.html", &context).await
 }
 
-/// 文章详情页（通过 ID）
-pub async fn passage_detail(path: web::Path<String>) -> HttpResponse {
-    let _id = path.into_inner();
-    let context = create_passage_context();
-    render_template("passage.html", &context).await
+/// 文章详情页（旧的 ID / UUID 链接，301 跳转到规范链接）
+pub async fn passage_detail(
+    repo: web::Data<Arc<dyn Repository>>,
+    path: web::Path<String>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let param = path.into_inner();
+    let passage_repo = PassageRepository::new(repo.get_pool().clone());
+
+    // 纯数字优先按 ID 查询（UUID 也是纯数字）
+    let by_id = match param.parse::<i64>() {
+        Ok(id) => passage_repo.get_by_id(id).await.ok(),
+        Err(_) => None,
+    };
+    let passage = match by_id {
+        Some(p) => Some(p),
+        None => passage_repo.get_by_uuid(&param).await.ok(),
+    };
+
+    let passage = match passage {
+        Some(p) => p,
+        None => return render_status_page(StatusCode::NOT_FOUND).await,
+    };
+
+    match redirect_to_permalink(&repo, &passage, &req).await {
+        Some(response) => response,
+        // 没有 slug 的文章直接在当前地址渲染
+        None if passage.permalink().is_none() => render_passage(&repo, &passage, &req).await,
+        None => render_status_page(StatusCode::NOT_FOUND).await,
+    }
 }
 
-/// 文章详情页（通过日期路径：/passage/{year}/{month}/{day}/{title}）
+/// 文章详情页（规范链接：/passage/{year}/{month}/{day}/{slug}）
+///
+/// 日期不匹配或使用旧 slug 时 301 跳转到规范链接
+pub async fn passage_permalink(
+    repo: web::Data<Arc<dyn Repository>>,
+    path: web::Path<(String, String, String, String)>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let (_, _, _, slug) = path.into_inner();
+    let passage_repo = PassageRepository::new(repo.get_pool().clone());
+
+    match passage_repo.get_by_slug(&slug).await {
+        Ok(passage) => {
+            if passage.permalink().as_deref() == Some(req.path()) {
+                return render_passage(&repo, &passage, &req).await;
+            }
+            if let Some(response) = redirect_to_permalink(&repo, &passage, &req).await {
+                return response;
+            }
+        }
+        Err(_) => {
+            let old_slug_target = match passage_repo.get_uuid_by_old_slug(&slug).await {
+                Ok(Some(uuid)) => passage_repo.get_by_uuid(&uuid).await.ok(),
+                _ => None,
+            };
+            if let Some(passage) = old_slug_target {
+                if let Some(response) = redirect_to_permalink(&repo, &passage, &req).await {
+                    return response;
+                }
+            }
+        }
+    }
+
+    render_status_page(StatusCode::NOT_FOUND).await
+}
+
+/// 服务端渲染文章详情，访客不可见的文章返回 404
+async fn render_passage(
+    repo: &Arc<dyn Repository>,
+    passage: &crate::db::models::Passage,
+    req: &HttpRequest,
+) -> HttpResponse {
+    let access = passage_access(repo, passage, req).await;
+    if access == PassageAccess::Denied {
+        return render_status_page(StatusCode::NOT_FOUND).await;
+    }
+
+    // 未解锁时不输出正文和目录
+    let locked = access == PassageAccess::Locked;
+    let locked_view;
+    let shown = if locked {
+        locked_view = crate::db::models::Passage {
+            summary: passage.locked_summary(),
+            content: String::new(),
+            ..passage.clone()
+        };
+        &locked_view
+    } else {
+        passage
+    };
+    let mut context = create_passage_detail_context(
+        shown,
+        &site_origin(req),
+        access != PassageAccess::Public,
+    );
+    context.insert("locked", &locked);
+    let mut response = render_template("passage.html", &context).await;
+
+    // 预览、仅管理员可见和需要密码的内容不允许被缓存
+    if access != PassageAccess::Public {
+        response.headers_mut().insert(
+            actix_web::http::header::CACHE_CONTROL,
+            actix_web::http::header::HeaderValue::from_static("private, no-store"),
+        );
+    }
+    response
+}
+
+/// 当前请求对文章的访问权限
+#[derive(Debug, PartialEq, Eq)]
+enum PassageAccess {
+    /// 访客可见
+    Public,
+    /// 管理员、文章作者、持有有效预览令牌或已解锁需要密码的文章
+    Privileged,
+    /// 需要密码且尚未解锁，只显示标题和摘要
+    Locked,
+    /// 不可见
+    Denied,
+}
+
+/// 判断当前请求能否查看文章
+async fn passage_access(
+    repo: &Arc<dyn Repository>,
+    passage: &crate::db::models::Passage,
+    req: &HttpRequest,
+) -> PassageAccess {
+    if passage.is_publicly_visible() {
+        return PassageAccess::Public;
+    }
+
+    let privileged = crate::middleware::auth::check_admin_auth(req).is_some()
+        || crate::handlers::api_handlers::passage::is_owner(req, passage)
+        || match (preview_token(req), passage.uuid.as_deref()) {
+            (Some(token), Some(uuid)) => {
+                crate::handlers::api_handlers::preview::verify_token(repo, &token, uuid).await
+            }
+            _ => false,
+        };
+
+    if privileged {
+        PassageAccess::Privileged
+    } else if passage.is_password_protected() && passage.is_live() {
+        match passage.uuid.as_deref() {
+            Some(uuid) if crate::handlers::api_handlers::passage::is_unlocked(req, uuid) => PassageAccess::Privileged,
+            _ => PassageAccess::Locked,
+        }
+    } else {
+        PassageAccess::Denied
+    }
+}
+
+/// 站点根地址（协议 + 主机名），用于生成绝对链接
+pub(crate) fn site_origin(req: &HttpRequest) -> String {
+    let info = req.connection_info();
+    format!("{}://{}", info.scheme(), info.host())
+}
+
+/// 301 跳转到文章的规范链接（保留查询参数）
+///
+/// 只有访客可见的文章、管理员或持有有效预览令牌时才跳转，避免通过 ID 泄露未发布文章的 slug
+async fn redirect_to_permalink(
+    repo: &Arc<dyn Repository>,
+    passage: &crate::db::models::Passage,
+    req: &HttpRequest,
+) -> Option<HttpResponse> {
+    let permalink = passage.permalink()?;
+
+    if passage_access(repo, passage, req).await == PassageAccess::Denied {
+        return None;
+    }
+
+    let location = match req.query_string() {
+        "" => permalink,
+        query => format!("{}?{}", permalink, query),
+    };
+    Some(HttpResponse::MovedPermanently()
+        .insert_header(("Location", location))
+        .finish())
+}
+
+/// 请求中的预览令牌（?preview=...）
+fn preview_token(req: &HttpRequest) -> Option<String> {
+    web::Query::<std::collections::HashMap<String, String>>::from_query(req.query_string())
+        .ok()
+        .and_then(|q| q.get("preview").cloned())
+}
+
+/// 归档页
 pub async fn collect() -> HttpResponse {
     let context = create_collect_context();
     render_template("collect.html", &context).await
 }
 
+/// 分类页（列出分类及其子分类下的全部公开文章）
+pub async fn category(
+    repo: web::Data<Arc<dyn Repository>>,
+    path: web::Path<String>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let name = path.into_inner();
+    render_taxonomy(&repo, &req, "分类", &format!("/category/{}", urlencoding::encode(&name)), &name, PublishedFilter { category: Some(&name), recursive: true, ..Default::default() }).await
+}
+
+/// 标签页（列出带有该标签的全部公开文章）
+pub async fn tag(
+    repo: web::Data<Arc<dyn Repository>>,
+    path: web::Path<String>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let name = path.into_inner();
+    render_taxonomy(&repo, &req, "标签", &format!("/tag/{}", urlencoding::encode(&name)), &name, PublishedFilter { tag: Some(&name), ..Default::default() }).await
+}
+
+/// 作者页（作者资料和全部公开文章，用户不存在时返回 404）
+pub async fn author(
+    repo: web::Data<Arc<dyn Repository>>,
+    path: web::Path<String>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let username = path.into_inner();
+    let profile = match AuthorRepository::new(repo.get_pool().clone()).get_by_username(&username).await {
+        Ok(profile) => profile,
+        Err(_) => return render_status_page(StatusCode::NOT_FOUND).await,
+    };
+
+    let passage_repo = PassageRepository::new(repo.get_pool().clone());
+    let filter = PublishedFilter { author: Some(&username), ..Default::default() };
+    let passages = match passage_repo.get_all_public_filtered(&filter).await {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("获取作者文章失败: {}", e);
+            return render_status_page(StatusCode::INTERNAL_SERVER_ERROR).await;
+        }
+    };
+
+    let page_path = format!("/author/{}", urlencoding::encode(&username));
+    let mut context = create_taxonomy_context("作者", &profile.display_name, &passages, &site_origin(&req), &page_path);
+    context.insert("profile", &profile);
+    render_template("taxonomy.html", &context).await
+}
+
+/// 渲染分类、标签页，没有文章时返回 404
+async fn render_taxonomy(
+    repo: &Arc<dyn Repository>,
+    req: &HttpRequest,
+    kind_label: &str,
+    page_path: &str,
+    name: &str,
+    filter: PublishedFilter<'_>,
+) -> HttpResponse {
+    let passage_repo = PassageRepository::new(repo.get_pool().clone());
+    let passages = match passage_repo.get_all_public_filtered(&filter).await {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("获取{}文章失败: {}", kind_label, e);
+            return render_status_page(StatusCode::INTERNAL_SERVER_ERROR).await;
+        }
+    };
+
+    if passages.is_empty() {
+        return render_status_page(StatusCode::NOT_FOUND).await;
+    }
+
+    let context = create_taxonomy_context(kind_label, name, &passages, &site_origin(req), page_path);
+    render_template("taxonomy.html", &context).await
+}
+
 /// 关于页面
 pub async fn about() -> HttpResponse {
     let context = create_about_context();
@@ -117,16 +380,8 @@ pub async fn admin(req: HttpRequest) -> HttpResponse {
 
 /// 状态页面
 pub async fn status_page(path: web::Path<u16>) -> HttpResponse {
-    let status = path.into_inner();
-    let status_text = match status {
-        404 => "Not Found",
-        500 => "Internal Server Error",
-        403 => "Forbidden",
-        _ => "Unknown Status",
-    };
-    
-    HttpResponse::build(actix_web::http::StatusCode::from_u16(status).unwrap_or(actix_web::http::StatusCode::NOT_FOUND))
-        .body(format!("{}: {}", status, status_text))
+    let status = StatusCode::from_u16(path.into_inner()).unwrap_or(StatusCode::NOT_FOUND);
+    render_status_page(status).await
 }
 
 /// 健康检查
@@ -135,4 +390,79 @@ pub async fn health() -> HttpResponse {
         "status": "ok",
         "service": "rustblog"
     }))
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::cookie::Cookie;
+    use actix_web::test::TestRequest;
+    use chrono::{Duration, Utc};
+
+    fn locked_passage(uuid: &str) -> crate::db::models::Passage {
+        let mut passage = crate::db::models::Passage::sample("加密文章", "正文");
+        passage.uuid = Some(uuid.to_string());
+        passage.visibility = "password".to_string();
+        passage
+    }
+
+    fn unlock_cookie(uuid: &str, token: String) -> Cookie<'static> {
+        Cookie::new(format!("passage_unlock_{}", uuid), token)
+    }
+
+    #[tokio::test]
+    async fn test_password_passage_locks_until_unlocked() {
+        crate::jwt::init_test_jwt_service();
+        let repo: Arc<dyn Repository> = Arc::new(PassageRepository::new(crate::db::init::test_pool()));
+        let passage = locked_passage("locked-uuid");
+
+        let req = TestRequest::default().to_http_request();
+        assert_eq!(passage_access(&repo, &passage, &req).await, PassageAccess::Locked);
+
+        let token = crate::jwt::generate_unlock_token("locked-uuid", Utc::now() + Duration::minutes(5)).unwrap();
+        let req = TestRequest::default().cookie(unlock_cookie("locked-uuid", token)).to_http_request();
+        assert_eq!(passage_access(&repo, &passage, &req).await, PassageAccess::Privileged);
+
+        // 未发布的加密文章不能通过解锁访问
+        let mut draft = locked_passage("locked-uuid");
+        draft.status = "draft".to_string();
+        assert_eq!(passage_access(&repo, &draft, &req).await, PassageAccess::Denied);
+    }
+
+    #[tokio::test]
+    async fn test_unlock_cookie_must_match_passage_and_be_valid() {
+        crate::jwt::init_test_jwt_service();
+        let repo: Arc<dyn Repository> = Arc::new(PassageRepository::new(crate::db::init::test_pool()));
+        let passage = locked_passage("locked-uuid");
+
+        // 其他文章的解锁令牌放进本文章的 Cookie 名下
+        let other = crate::jwt::generate_unlock_token("other-uuid", Utc::now() + Duration::minutes(5)).unwrap();
+        // 已过期的令牌
+        let expired = crate::jwt::generate_unlock_token("locked-uuid", Utc::now() - Duration::minutes(5)).unwrap();
+        // 登录令牌不能当作解锁令牌使用
+        let login = crate::jwt::generate_token(1, "admin", "admin").unwrap();
+
+        for token in [other, expired, login, "garbage".to_string()] {
+            let req = TestRequest::default().cookie(unlock_cookie("locked-uuid", token)).to_http_request();
+            assert_eq!(passage_access(&repo, &passage, &req).await, PassageAccess::Locked);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_owner_can_view_own_private_passage() {
+        crate::jwt::init_test_jwt_service();
+        let repo: Arc<dyn Repository> = Arc::new(PassageRepository::new(crate::db::init::test_pool()));
+        let mut passage = crate::db::models::Passage::sample("私有文章", "正文");
+        passage.uuid = Some("private-uuid".to_string());
+        passage.visibility = "private".to_string();
+        passage.author_id = Some(7);
+
+        let as_user = |user_id| {
+            let token = crate::jwt::generate_token(user_id, "writer", "user").unwrap();
+            TestRequest::default().cookie(Cookie::new("auth_token", token)).to_http_request()
+        };
+        assert_eq!(passage_access(&repo, &passage, &as_user(7)).await, PassageAccess::Privileged);
+        assert_eq!(passage_access(&repo, &passage, &as_user(8)).await, PassageAccess::Denied);
+        let anonymous = TestRequest::default().to_http_request();
+        assert_eq!(passage_access(&repo, &passage, &anonymous).await, PassageAccess::Denied);
+    }
+}
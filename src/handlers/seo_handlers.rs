@@ -0,0 +1,164 @@
+use actix_web::{web, HttpResponse, HttpRequest};
+use actix_web::http::StatusCode;
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use crate::cache::{self, AppCache};
+use crate::db::repositories::{PassageRepository, Repository, SettingRepository};
+use crate::sitemap::{self, SitemapEntry, MAX_URLS_PER_SITEMAP};
+
+/// 默认的 robots.txt 规则（不允许抓取管理后台、接口和编辑器）
+const DEFAULT_ROBOTS_RULES: &str = "User-agent: *\nDisallow: /admin\nDisallow: /api\nDisallow: /markdown-editor\nAllow: /\n";
+
+/// 收集站点地图中的全部地址
+async fn collect_entries(
+    repo: &Arc<dyn Repository>,
+    origin: &str,
+) -> Result<Vec<SitemapEntry>, Box<dyn std::error::Error>> {
+    let passage_repo = PassageRepository::new(repo.get_pool().clone());
+    let passages = passage_repo.get_all_public().await?;
+    let latest = passages.iter().map(|p| p.updated_at).max();
+
+    // 固定页面
+    let mut entries = vec![
+        SitemapEntry { loc: format!("{}/", origin), lastmod: latest },
+        SitemapEntry { loc: format!("{}/collect", origin), lastmod: latest },
+        SitemapEntry { loc: format!("{}/about", origin), lastmod: None },
+        SitemapEntry { loc: format!("{}/friends", origin), lastmod: None },
+    ];
+
+    // 分类、标签页的最后修改时间取其中文章的最新更新时间
+    let mut categories: BTreeMap<String, DateTime<Utc>> = BTreeMap::new();
+    let mut tags: BTreeMap<String, DateTime<Utc>> = BTreeMap::new();
+
+    for passage in &passages {
+        if let Some(permalink) = passage.permalink() {
+            entries.push(SitemapEntry {
+                loc: format!("{}{}", origin, permalink),
+                lastmod: Some(passage.updated_at),
+            });
+        }
+
+        if !passage.category.trim().is_empty() {
+            let lastmod = categories.entry(passage.category.clone()).or_insert(passage.updated_at);
+            *lastmod = (*lastmod).max(passage.updated_at);
+        }
+        for tag in passage.tag_list() {
+            let lastmod = tags.entry(tag).or_insert(passage.updated_at);
+            *lastmod = (*lastmod).max(passage.updated_at);
+        }
+    }
+
+    for (category, lastmod) in categories {
+        entries.push(SitemapEntry {
+            loc: format!("{}/category/{}", origin, urlencoding::encode(&category)),
+            lastmod: Some(lastmod),
+        });
+    }
+    for (tag, lastmod) in tags {
+        entries.push(SitemapEntry {
+            loc: format!("{}/tag/{}", origin, urlencoding::encode(&tag)),
+            lastmod: Some(lastmod),
+        });
+    }
+
+    Ok(entries)
+}
+
+/// 读取站点地图条目（文章数据未变化时使用缓存）
+async fn cached_entries(
+    repo: &Arc<dyn Repository>,
+    app_cache: &AppCache,
+    origin: &str,
+) -> Result<Arc<Vec<SitemapEntry>>, Box<dyn std::error::Error>> {
+    if let Some(entries) = app_cache.sitemap_entries(origin) {
+        return Ok(entries);
+    }
+    let version = cache::passages_version();
+    let entries = Arc::new(collect_entries(repo, origin).await?);
+    app_cache.put_sitemap_entries(origin, version, entries.clone());
+    Ok(entries)
+}
+
+fn xml_response(body: String) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("application/xml; charset=utf-8")
+        .insert_header(("Cache-Control", "public, max-age=3600"))
+        .body(body)
+}
+
+/// 站点地图（地址过多时返回站点地图索引）
+pub async fn sitemap(
+    repo: web::Data<Arc<dyn Repository>>,
+    app_cache: web::Data<Arc<AppCache>>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let origin = crate::handlers::page_handlers::site_origin(&req);
+    let entries = match cached_entries(&repo, &app_cache, &origin).await {
+        Ok(e) => e,
+        Err(e) => {
+            eprintln!("生成站点地图失败: {}", e);
+            return HttpResponse::InternalServerError().body("生成站点地图失败");
+        }
+    };
+
+    if entries.len() <= MAX_URLS_PER_SITEMAP {
+        return xml_response(sitemap::render_urlset(&entries));
+    }
+
+    let sitemaps: Vec<SitemapEntry> = entries.chunks(MAX_URLS_PER_SITEMAP)
+        .enumerate()
+        .map(|(index, chunk)| SitemapEntry {
+            loc: format!("{}/sitemap-{}.xml", origin, index + 1),
+            lastmod: chunk.iter().filter_map(|e| e.lastmod).max(),
+        })
+        .collect();
+    xml_response(sitemap::render_index(&sitemaps))
+}
+
+/// 分页站点地图（/sitemap-{page}.xml，页码从 1 开始）
+pub async fn sitemap_page(
+    repo: web::Data<Arc<dyn Repository>>,
+    app_cache: web::Data<Arc<AppCache>>,
+    path: web::Path<usize>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let page = path.into_inner();
+    let origin = crate::handlers::page_handlers::site_origin(&req);
+    let entries = match cached_entries(&repo, &app_cache, &origin).await {
+        Ok(e) => e,
+        Err(e) => {
+            eprintln!("生成站点地图失败: {}", e);
+            return HttpResponse::InternalServerError().body("生成站点地图失败");
+        }
+    };
+
+    match page.checked_sub(1).and_then(|index| entries.chunks(MAX_URLS_PER_SITEMAP).nth(index)) {
+        Some(chunk) => xml_response(sitemap::render_urlset(chunk)),
+        None => crate::templates::render_status_page(StatusCode::NOT_FOUND).await,
+    }
+}
+
+/// robots.txt（可在设置 robots_txt 中自定义，未包含 Sitemap 时自动追加）
+pub async fn robots(repo: web::Data<Arc<dyn Repository>>, req: HttpRequest) -> HttpResponse {
+    let custom = repo.get_pool().get().ok()
+        .and_then(|conn| SettingRepository::get(&conn, "robots_txt").ok().flatten())
+        .map(|setting| setting.value)
+        .filter(|value| !value.trim().is_empty());
+
+    let mut body = custom.unwrap_or_else(|| DEFAULT_ROBOTS_RULES.to_string());
+    if !body.to_ascii_lowercase().contains("sitemap:") {
+        if !body.ends_with('\n') {
+            body.push('\n');
+        }
+        body.push_str(&format!(
+            "\nSitemap: {}/sitemap.xml\n",
+            crate::handlers::page_handlers::site_origin(&req)
+        ));
+    }
+
+    HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .insert_header(("Cache-Control", "public, max-age=3600"))
+        .body(body)
+}
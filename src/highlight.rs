@@ -0,0 +1,708 @@
+//! 代码块语法高亮
+//!
+//! 渲染文章时在服务端完成高亮，输出与 highlight.js 主题（tokyo-night-dark.min.css）一致的 class，
+//! 关闭 JavaScript 或在订阅源中也能正常显示。未支持的语言原样输出，交给页面中的 highlight.js 处理
+
+use crate::markdown::escape_html;
+
+/// 代码块信息（围栏后的 info string，如 `rust linenos {1,3-5}`）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FenceInfo {
+    pub language: Option<String>,
+    /// 需要高亮的行（从 1 开始的闭区间）
+    pub highlight_lines: Vec<(usize, usize)>,
+    pub line_numbers: bool,
+}
+
+impl FenceInfo {
+    /// 解析 info string：第一个词为语言，`{1,3-5}` 为高亮行，`linenos` 显示行号
+    pub fn parse(info: &str) -> Self {
+        let mut fence = FenceInfo::default();
+        let mut rest = info.trim();
+
+        let language_end = rest.find(|c: char| c.is_whitespace() || c == '{').unwrap_or(rest.len());
+        if language_end > 0 {
+            fence.language = Some(rest[..language_end].to_lowercase());
+        }
+        rest = &rest[language_end..];
+
+        while let Some(start) = rest.find('{') {
+            let Some(len) = rest[start..].find('}') else { break };
+            for part in rest[start + 1..start + len].split(',') {
+                let mut bounds = part.trim().splitn(2, '-').map(|n| n.trim().parse::<usize>());
+                match (bounds.next(), bounds.next()) {
+                    (Some(Ok(from)), None) => fence.highlight_lines.push((from, from)),
+                    (Some(Ok(from)), Some(Ok(to))) if from <= to => fence.highlight_lines.push((from, to)),
+                    _ => {}
+                }
+            }
+            rest = &rest[start + len + 1..];
+        }
+
+        fence.line_numbers = info
+            .split(|c: char| c.is_whitespace() || c == '{' || c == '}' || c == ',')
+            .any(|word| word == "linenos" || word == "showLineNumbers");
+        fence
+    }
+
+    fn is_highlighted(&self, line: usize) -> bool {
+        self.highlight_lines.iter().any(|&(from, to)| (from..=to).contains(&line))
+    }
+}
+
+/// 渲染代码块为 HTML
+pub fn render_code_block(code: &str, fence: &FenceInfo) -> String {
+    let language = fence.language.as_deref().and_then(find_language);
+    let tokens = match language {
+        Some(language) => tokenize(code, language),
+        None => vec![(None, code)],
+    };
+
+    let mut pre_class = String::from("code-block");
+    if fence.line_numbers {
+        pre_class.push_str(" line-numbers");
+    }
+    // 语言名只允许常见字符，其余情况不输出 class（避免注入属性）
+    let name = fence.language.as_deref().filter(|name| name.chars().all(is_language_char));
+    let code_class = match (name, language) {
+        (Some(name), Some(_)) => format!(" class=\"hljs language-{}\"", name),
+        (Some(name), None) => format!(" class=\"language-{}\"", name),
+        (None, _) => String::new(),
+    };
+
+    let mut html = format!("<pre class=\"{}\"><code{}>", pre_class, code_class);
+    let wrap_lines = fence.line_numbers || !fence.highlight_lines.is_empty();
+    let mut writer = LineWriter { html: &mut html, fence, wrap_lines, line: 0 };
+    writer.write_tokens(&tokens, code.ends_with('\n'));
+    html.push_str("</code></pre>\n");
+    html
+}
+
+/// 按行输出 token，需要时给每行包上 `code-line`，跨行的 token 在行尾关闭、下一行重新打开
+struct LineWriter<'a> {
+    html: &'a mut String,
+    fence: &'a FenceInfo,
+    wrap_lines: bool,
+    line: usize,
+}
+
+impl LineWriter<'_> {
+    fn write_tokens(&mut self, tokens: &[(Option<&str>, &str)], trailing_newline: bool) {
+        self.open_line();
+        let last = tokens.len().saturating_sub(1);
+        for (index, (class, text)) in tokens.iter().enumerate() {
+            // 代码末尾的换行不再开始新的一行
+            let text = if index == last && trailing_newline { text.strip_suffix('\n').unwrap_or(text) } else { text };
+            for (i, segment) in text.split('\n').enumerate() {
+                if i > 0 {
+                    self.close_line();
+                    self.html.push('\n');
+                    self.open_line();
+                }
+                if segment.is_empty() {
+                    continue;
+                }
+                match class {
+                    Some(class) => {
+                        self.html.push_str(&format!("<span class=\"{}\">{}</span>", class, escape_html(segment)));
+                    }
+                    None => self.html.push_str(&escape_html(segment)),
+                }
+            }
+        }
+        self.close_line();
+        if trailing_newline {
+            self.html.push('\n');
+        }
+    }
+
+    fn open_line(&mut self) {
+        self.line += 1;
+        if self.wrap_lines {
+            let class = if self.fence.is_highlighted(self.line) { "code-line highlight-line" } else { "code-line" };
+            self.html.push_str(&format!("<span class=\"{}\" data-line=\"{}\">", class, self.line));
+        }
+    }
+
+    fn close_line(&mut self) {
+        if self.wrap_lines {
+            self.html.push_str("</span>");
+        }
+    }
+}
+
+/// 语言的词法定义
+struct Language {
+    names: &'static [&'static str],
+    keywords: &'static [&'static str],
+    types: &'static [&'static str],
+    literals: &'static [&'static str],
+    builtins: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    /// 字符串定界符，较长的放在前面（如 `"""` 先于 `"`）
+    strings: &'static [&'static str],
+    kind: Kind,
+    case_insensitive: bool,
+}
+
+/// 需要特殊处理的语法
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Generic,
+    /// `#[...]` 属性、`name!` 宏、生命周期
+    Rust,
+    /// `#include` 等预处理指令
+    CLike,
+    /// `@decorator`
+    Decorated,
+    /// `$VAR` 变量
+    Shell,
+    /// 对象键
+    Json,
+    /// 行首的 `key:` / `key =` / `[section]`
+    Config,
+    /// 标签和属性
+    Markup,
+    /// 属性名和 `@` 规则
+    Css,
+    /// 按行首字符区分增删
+    Diff,
+}
+
+const LANGUAGES: &[Language] = &[
+    Language {
+        names: &["rust", "rs"],
+        keywords: &[
+            "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "fn", "for",
+            "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self", "Self",
+            "static", "struct", "super", "trait", "type", "unsafe", "use", "where", "while",
+        ],
+        types: &[
+            "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize", "f32", "f64", "bool",
+            "char", "str", "String", "Vec", "Option", "Result", "Box", "Arc", "Rc", "HashMap", "HashSet",
+        ],
+        literals: &["true", "false", "None", "Some", "Ok", "Err"],
+        builtins: &[],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        strings: &["\""],
+        kind: Kind::Rust,
+        case_insensitive: false,
+    },
+    Language {
+        names: &["javascript", "js", "jsx", "mjs", "typescript", "ts", "tsx"],
+        keywords: &[
+            "async", "await", "break", "case", "catch", "class", "const", "continue", "debugger", "default", "delete",
+            "do", "else", "export", "extends", "finally", "for", "from", "function", "if", "import", "in", "instanceof",
+            "let", "new", "of", "return", "static", "super", "switch", "this", "throw", "try", "typeof", "var", "void",
+            "while", "yield", "interface", "type", "enum", "implements", "private", "public", "protected", "readonly",
+            "as",
+        ],
+        types: &["string", "number", "boolean", "any", "unknown", "never", "object", "Promise", "Array", "Record"],
+        literals: &["true", "false", "null", "undefined", "NaN", "Infinity"],
+        builtins: &["console", "window", "document", "JSON", "Math", "Object", "require", "module", "process"],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        strings: &["\"", "'", "`"],
+        kind: Kind::Decorated,
+        case_insensitive: false,
+    },
+    Language {
+        names: &["python", "py"],
+        keywords: &[
+            "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif", "else",
+            "except", "finally", "for", "from", "global", "if", "import", "in", "is", "lambda", "nonlocal", "not", "or",
+            "pass", "raise", "return", "try", "while", "with", "yield", "self",
+        ],
+        types: &["int", "float", "str", "bool", "list", "dict", "set", "tuple", "bytes"],
+        literals: &["True", "False", "None"],
+        builtins: &["print", "len", "range", "open", "enumerate", "zip", "isinstance", "super", "sorted", "map", "filter"],
+        line_comments: &["#"],
+        block_comment: None,
+        strings: &["\"\"\"", "'''", "\"", "'"],
+        kind: Kind::Decorated,
+        case_insensitive: false,
+    },
+    Language {
+        names: &["go", "golang"],
+        keywords: &[
+            "break", "case", "chan", "const", "continue", "default", "defer", "else", "fallthrough", "for", "func",
+            "go", "goto", "if", "import", "interface", "map", "package", "range", "return", "select", "struct",
+            "switch", "type", "var",
+        ],
+        types: &[
+            "bool", "byte", "error", "float32", "float64", "int", "int8", "int16", "int32", "int64", "rune", "string",
+            "uint", "uint8", "uint16", "uint32", "uint64", "uintptr",
+        ],
+        literals: &["true", "false", "nil", "iota"],
+        builtins: &["append", "cap", "close", "copy", "delete", "len", "make", "new", "panic", "print", "println", "recover"],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        strings: &["\"", "`", "'"],
+        kind: Kind::Generic,
+        case_insensitive: false,
+    },
+    Language {
+        names: &["c", "h", "cpp", "c++", "cc", "hpp", "cxx", "java", "csharp", "cs", "kotlin", "kt"],
+        keywords: &[
+            "auto", "break", "case", "catch", "class", "const", "continue", "default", "delete", "do", "else", "enum",
+            "extern", "final", "finally", "for", "fun", "goto", "if", "import", "namespace", "new", "operator",
+            "override", "package", "private", "protected", "public", "return", "sizeof", "static", "struct", "switch",
+            "template", "this", "throw", "throws", "try", "typedef", "typename", "union", "using", "val", "var",
+            "virtual", "volatile", "while", "extends", "implements", "interface", "abstract",
+        ],
+        types: &[
+            "void", "int", "long", "short", "char", "float", "double", "bool", "boolean", "unsigned", "signed",
+            "size_t", "string", "String", "byte", "Integer", "List", "Map",
+        ],
+        literals: &["true", "false", "null", "nullptr", "NULL"],
+        builtins: &["printf", "malloc", "free", "std", "System", "cout", "cin", "endl"],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        strings: &["\"", "'"],
+        kind: Kind::CLike,
+        case_insensitive: false,
+    },
+    Language {
+        names: &["bash", "sh", "shell", "zsh", "console"],
+        keywords: &[
+            "if", "then", "else", "elif", "fi", "for", "while", "until", "do", "done", "case", "esac", "in", "function",
+            "return", "local", "export", "readonly",
+        ],
+        types: &[],
+        literals: &["true", "false"],
+        builtins: &[
+            "echo", "cd", "ls", "cat", "grep", "sed", "awk", "mkdir", "rm", "cp", "mv", "chmod", "sudo", "cargo", "git",
+            "curl", "source", "set", "exit",
+        ],
+        line_comments: &["#"],
+        block_comment: None,
+        strings: &["\"", "'"],
+        kind: Kind::Shell,
+        case_insensitive: false,
+    },
+    Language {
+        names: &["sql", "sqlite", "mysql", "postgresql"],
+        keywords: &[
+            "select", "from", "where", "and", "or", "not", "insert", "into", "values", "update", "set", "delete",
+            "create", "table", "index", "drop", "alter", "add", "join", "left", "right", "inner", "outer", "on", "as",
+            "order", "by", "group", "having", "limit", "offset", "primary", "key", "foreign", "references", "unique",
+            "default", "if", "exists", "distinct", "union", "all", "case", "when", "then", "else", "end", "in", "is",
+            "like", "begin", "commit", "rollback", "asc", "desc",
+        ],
+        types: &["integer", "int", "text", "real", "blob", "varchar", "boolean", "datetime", "timestamp"],
+        literals: &["null", "true", "false"],
+        builtins: &["count", "sum", "avg", "min", "max", "coalesce", "length", "lower", "upper"],
+        line_comments: &["--"],
+        block_comment: Some(("/*", "*/")),
+        strings: &["'", "\""],
+        kind: Kind::Generic,
+        case_insensitive: true,
+    },
+    Language {
+        names: &["json", "jsonc"],
+        keywords: &[],
+        types: &[],
+        literals: &["true", "false", "null"],
+        builtins: &[],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        strings: &["\""],
+        kind: Kind::Json,
+        case_insensitive: false,
+    },
+    Language {
+        names: &["yaml", "yml", "toml", "ini"],
+        keywords: &[],
+        types: &[],
+        literals: &["true", "false", "null", "yes", "no"],
+        builtins: &[],
+        line_comments: &["#", ";"],
+        block_comment: None,
+        strings: &["\"", "'"],
+        kind: Kind::Config,
+        case_insensitive: false,
+    },
+    Language {
+        names: &["html", "xml", "svg", "vue"],
+        keywords: &[],
+        types: &[],
+        literals: &[],
+        builtins: &[],
+        line_comments: &[],
+        block_comment: Some(("<!--", "-->")),
+        strings: &["\"", "'"],
+        kind: Kind::Markup,
+        case_insensitive: false,
+    },
+    Language {
+        names: &["css", "scss", "less"],
+        keywords: &["important"],
+        types: &[],
+        literals: &[],
+        builtins: &[],
+        line_comments: &[],
+        block_comment: Some(("/*", "*/")),
+        strings: &["\"", "'"],
+        kind: Kind::Css,
+        case_insensitive: false,
+    },
+    Language {
+        names: &["diff", "patch"],
+        keywords: &[],
+        types: &[],
+        literals: &[],
+        builtins: &[],
+        line_comments: &[],
+        block_comment: None,
+        strings: &[],
+        kind: Kind::Diff,
+        case_insensitive: false,
+    },
+];
+
+/// 代码块语言名允许的字符（c++、c#、objective-c、vue.js 等）
+pub(crate) fn is_language_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '#' | '.' | '-')
+}
+
+fn find_language(name: &str) -> Option<&'static Language> {
+    LANGUAGES.iter().find(|language| language.names.contains(&name))
+}
+
+type Token<'a> = (Option<&'static str>, &'a str);
+
+/// 将代码切分为 (class, 文本) 序列，拼接所有文本即为原始代码
+fn tokenize<'a>(code: &'a str, language: &Language) -> Vec<Token<'a>> {
+    if language.kind == Kind::Diff {
+        return tokenize_diff(code);
+    }
+
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut plain_start = 0;
+    let mut pos = 0;
+    // Markup：是否处于标签内部
+    let mut in_tag = false;
+    // Css：是否处于声明块内部
+    let mut in_block = false;
+
+    macro_rules! emit {
+        ($class:expr, $end:expr) => {{
+            if plain_start < pos {
+                tokens.push((None, &code[plain_start..pos]));
+            }
+            tokens.push((Some($class), &code[pos..$end]));
+            pos = $end;
+            plain_start = pos;
+            continue;
+        }};
+    }
+
+    // 当前位置之前、本行之内是否只有空白；只扫描上次检查之后的新内容
+    let mut at_line_start = true;
+    let mut scanned = 0;
+
+    while pos < code.len() {
+        let rest = &code[pos..];
+        let c = rest.chars().next().unwrap_or_default();
+        for ch in code[scanned..pos].chars() {
+            if ch == '\n' {
+                at_line_start = true;
+            } else if !ch.is_whitespace() {
+                at_line_start = false;
+            }
+        }
+        scanned = pos;
+
+        // 注释
+        if let Some((open, close)) = language.block_comment {
+            if let Some(body) = rest.strip_prefix(open) {
+                let end = body.find(close).map_or(code.len(), |i| pos + open.len() + i + close.len());
+                emit!("hljs-comment", end);
+            }
+        }
+        // shell 中只有行首或空白之后的 # 才是注释（排除 $# 等）
+        let comment_allowed = language.kind != Kind::Shell || c != '#' || at_line_start || preceded_by_space(code, pos);
+        if comment_allowed && language.line_comments.iter().any(|marker| rest.starts_with(marker)) {
+            emit!("hljs-comment", line_end(code, pos));
+        }
+
+        // 特殊语法
+        match language.kind {
+            Kind::Rust if rest.starts_with("#[") || rest.starts_with("#![") => {
+                emit!("hljs-meta", rest.find(']').map_or(line_end(code, pos), |i| pos + i + 1));
+            }
+            Kind::Rust if c == '\'' => {
+                // 'a' 为字符，'a 为生命周期
+                let mut chars = rest.char_indices().skip(1);
+                match (chars.next(), chars.next()) {
+                    (Some((_, '\\')), _) => emit!("hljs-string", string_end(code, pos, "'")),
+                    (Some(_), Some((i, '\''))) => emit!("hljs-string", pos + i + 1),
+                    (Some(_), _) => emit!("hljs-symbol", ident_end(code, pos + 1)),
+                    _ => {}
+                }
+            }
+            Kind::CLike if c == '#' && at_line_start => emit!("hljs-meta", line_end(code, pos)),
+            Kind::Decorated if c == '@' && rest[1..].starts_with(is_ident_start) => {
+                emit!("hljs-meta", ident_end(code, pos + 1));
+            }
+            Kind::Shell if c == '$' => {
+                let end = if rest[1..].starts_with('{') {
+                    rest.find('}').map_or(line_end(code, pos), |i| pos + i + 1)
+                } else {
+                    ident_end(code, pos + 1)
+                };
+                if end > pos + 1 {
+                    emit!("hljs-variable", end);
+                }
+            }
+            Kind::Config if at_line_start && c == '[' => emit!("hljs-section", line_end(code, pos)),
+            Kind::Config if at_line_start && (is_ident_start(c) || c == '-') => {
+                let key_start = if c == '-' { pos + 1 + (rest[1..].len() - rest[1..].trim_start().len()) } else { pos };
+                let key_end = key_end(code, key_start);
+                let after = code[key_end..].trim_start_matches([' ', '\t']);
+                if key_end > key_start && (after.starts_with(':') || after.starts_with('=')) {
+                    if key_start > pos {
+                        pos = key_start;
+                    }
+                    emit!("hljs-attr", key_end);
+                }
+            }
+            Kind::Markup if c == '<' && !in_tag => {
+                let name_start = pos + if rest[1..].starts_with(['/', '!', '?']) { 2 } else { 1 };
+                let name_end = key_end(code, name_start);
+                if name_end > name_start {
+                    in_tag = true;
+                    tokens.push((None, &code[plain_start..pos]));
+                    tokens.push((Some("hljs-tag"), &code[pos..name_start]));
+                    pos = name_start;
+                    plain_start = pos;
+                    emit!("hljs-name", name_end);
+                }
+            }
+            Kind::Markup if in_tag && (c == '>' || rest.starts_with("/>")) => {
+                in_tag = false;
+                emit!("hljs-tag", pos + if c == '>' { 1 } else { 2 });
+            }
+            Kind::Markup if in_tag && is_ident_start(c) => emit!("hljs-attr", key_end(code, pos)),
+            Kind::Markup if !in_tag => {
+                // 标签之外的文本不做高亮
+                let end = rest.find('<').map_or(code.len(), |i| pos + i.max(1));
+                pos = end;
+                continue;
+            }
+            Kind::Css if c == '{' || c == '}' => {
+                in_block = c == '{';
+            }
+            Kind::Css if c == '@' => emit!("hljs-keyword", key_end(code, pos + 1)),
+            Kind::Css if in_block && (is_ident_start(c) || c == '-') => {
+                let end = key_end(code, pos);
+                if code[end..].trim_start().starts_with(':') {
+                    emit!("hljs-attribute", end);
+                }
+            }
+            Kind::Css if !in_block && (c == '.' || c == '#') && rest[1..].starts_with(is_ident_start) => {
+                let class = if c == '.' { "hljs-selector-class" } else { "hljs-selector-id" };
+                emit!(class, key_end(code, pos + 1));
+            }
+            Kind::Css if in_block && c == '#' => emit!("hljs-number", ident_end(code, pos + 1)),
+            _ => {}
+        }
+
+        // 字符串
+        if let Some(delimiter) = language.strings.iter().find(|d| rest.starts_with(**d)) {
+            let end = string_end(code, pos, delimiter);
+            let class = if language.kind == Kind::Json && code[end..].trim_start().starts_with(':') {
+                "hljs-attr"
+            } else {
+                "hljs-string"
+            };
+            emit!(class, end);
+        }
+
+        // 数字（前一个字符不能是标识符的一部分）
+        if c.is_ascii_digit() && !code[..pos].ends_with(is_ident_char) {
+            let end = pos + rest.find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '_' || ch == '.')).unwrap_or(rest.len());
+            // 去掉数字末尾的 .（如范围 0..10）
+            let end = code[pos..end].find("..").map_or(end, |i| pos + i);
+            emit!("hljs-number", end);
+        }
+
+        // 标识符
+        if is_ident_start(c) {
+            let end = ident_end(code, pos);
+            let word = &code[pos..end];
+            let matches = |list: &[&str]| {
+                if language.case_insensitive {
+                    list.iter().any(|w| w.eq_ignore_ascii_case(word))
+                } else {
+                    list.contains(&word)
+                }
+            };
+            let next = code[end..].trim_start_matches([' ', '\t']);
+
+            if matches(language.keywords) {
+                emit!("hljs-keyword", end);
+            }
+            if matches(language.literals) {
+                emit!("hljs-literal", end);
+            }
+            if matches(language.types) {
+                emit!("hljs-type", end);
+            }
+            if matches(language.builtins) {
+                emit!("hljs-built_in", end);
+            }
+            if language.kind == Kind::Rust && code[end..].starts_with('!') && !code[end..].starts_with("!=") {
+                emit!("hljs-built_in", end + 1);
+            }
+            if next.starts_with('(') && !matches!(language.kind, Kind::Css | Kind::Config | Kind::Json) {
+                emit!("hljs-title function_", end);
+            }
+            if language.kind == Kind::Rust && word.starts_with(|ch: char| ch.is_ascii_uppercase()) {
+                emit!("hljs-title class_", end);
+            }
+            pos = end;
+            continue;
+        }
+
+        pos += c.len_utf8();
+    }
+
+    if plain_start < code.len() {
+        tokens.push((None, &code[plain_start..]));
+    }
+    tokens.retain(|(_, text)| !text.is_empty());
+    tokens
+}
+
+fn tokenize_diff(code: &str) -> Vec<Token<'_>> {
+    code.split_inclusive('\n')
+        .map(|line| {
+            let class = if line.starts_with("+++") || line.starts_with("---") || line.starts_with("@@") {
+                Some("hljs-meta")
+            } else if line.starts_with('+') {
+                Some("hljs-addition")
+            } else if line.starts_with('-') {
+                Some("hljs-deletion")
+            } else {
+                None
+            };
+            (class, line)
+        })
+        .collect()
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_'
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn ident_end(code: &str, start: usize) -> usize {
+    start + code[start..].find(|c: char| !is_ident_char(c)).unwrap_or(code.len() - start)
+}
+
+/// 键名、标签名、CSS 属性名可以包含 - . :
+fn key_end(code: &str, start: usize) -> usize {
+    start + code[start..].find(|c: char| !(is_ident_char(c) || c == '-' || c == '.')).unwrap_or(code.len() - start)
+}
+
+fn line_end(code: &str, start: usize) -> usize {
+    code[start..].find('\n').map_or(code.len(), |i| start + i)
+}
+
+fn preceded_by_space(code: &str, pos: usize) -> bool {
+    code[..pos].ends_with([' ', '\t'])
+}
+
+/// 字符串结束位置（支持反斜杠转义；单字符定界符的字符串不跨行）
+fn string_end(code: &str, start: usize, delimiter: &str) -> usize {
+    let body = start + delimiter.len();
+    let mut escaped = false;
+    for (i, c) in code[body..].char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        if c == '\\' {
+            escaped = true;
+        } else if c == '\n' && delimiter.len() == 1 && delimiter != "`" {
+            return body + i;
+        } else if code[body + i..].starts_with(delimiter) {
+            return body + i + delimiter.len();
+        }
+    }
+    code.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fence_info() {
+        let fence = FenceInfo::parse("rust linenos {1,3-5}");
+        assert_eq!(fence.language.as_deref(), Some("rust"));
+        assert_eq!(fence.highlight_lines, vec![(1, 1), (3, 5)]);
+        assert!(fence.line_numbers);
+        assert!(fence.is_highlighted(4) && !fence.is_highlighted(2));
+
+        let fence = FenceInfo::parse("js{2}");
+        assert_eq!(fence.language.as_deref(), Some("js"));
+        assert_eq!(fence.highlight_lines, vec![(2, 2)]);
+        assert!(!fence.line_numbers);
+    }
+
+    #[test]
+    fn test_highlight_rust() {
+        let html = render_code_block("// 注释\nfn main() {\n    println!(\"<hi>\", 42);\n}\n", &FenceInfo::parse("rust"));
+        assert!(html.starts_with("<pre class=\"code-block\"><code class=\"hljs language-rust\">"));
+        assert!(html.contains("<span class=\"hljs-comment\">// 注释</span>"));
+        assert!(html.contains("<span class=\"hljs-keyword\">fn</span> <span class=\"hljs-title function_\">main</span>"));
+        assert!(html.contains("<span class=\"hljs-built_in\">println!</span>"));
+        assert!(html.contains("<span class=\"hljs-string\">\"&lt;hi&gt;\"</span>"));
+        assert!(html.contains("<span class=\"hljs-number\">42</span>"));
+    }
+
+    #[test]
+    fn test_line_numbers_and_highlight_lines() {
+        let html = render_code_block("let s = `a\nb`;\nx\n", &FenceInfo::parse("js linenos {2}"));
+        assert!(html.starts_with("<pre class=\"code-block line-numbers\">"));
+        // 跨行的字符串在行尾关闭，下一行重新打开
+        assert!(html.contains(
+            "<span class=\"code-line\" data-line=\"1\"><span class=\"hljs-keyword\">let</span> s = <span class=\"hljs-string\">`a</span></span>\n\
+             <span class=\"code-line highlight-line\" data-line=\"2\"><span class=\"hljs-string\">b`</span>;</span>\n\
+             <span class=\"code-line\" data-line=\"3\">x</span>\n</code>"
+        ));
+    }
+
+    #[test]
+    fn test_unknown_language_is_left_for_client() {
+        let html = render_code_block("graph TD;\nA-->B\n", &FenceInfo::parse("mermaid"));
+        assert_eq!(html, "<pre class=\"code-block\"><code class=\"language-mermaid\">graph TD;\nA--&gt;B\n</code></pre>\n");
+    }
+
+    #[test]
+    fn test_language_class_rejects_unsafe_names() {
+        let html = render_code_block("x\n", &FenceInfo::parse("vue.js"));
+        assert!(html.contains("<code class=\"language-vue.js\">"));
+
+        let html = render_code_block("x\n", &FenceInfo::parse("x\"onmouseover=\"alert(1)"));
+        assert!(html.starts_with("<pre class=\"code-block\"><code>"));
+    }
+
+    #[test]
+    fn test_line_start_tracking() {
+        let html = render_code_block("int a; # no\n  #include <x>\n", &FenceInfo::parse("c"));
+        assert_eq!(html.matches("hljs-meta").count(), 1);
+        assert!(html.contains("<span class=\"hljs-meta\">#include &lt;x&gt;</span>"));
+
+        let html = render_code_block("echo $# # 注释\n\t# 行首注释\n", &FenceInfo::parse("bash"));
+        assert!(html.contains("<span class=\"hljs-comment\"># 注释</span>"));
+        assert!(html.contains("<span class=\"hljs-comment\"># 行首注释</span>"));
+    }
+}
@@ -0,0 +1,773 @@
+//! 从其他博客系统导入文章
+//!
+//! 支持 Hexo、Hugo、Jekyll 的站点源码目录和 WordPress 导出的 WXR 文件。
+//! [`scan`] 只读取导入源生成导入计划；[`run`] 在预览模式下只生成报告，确认后才写入数据库和附件目录
+
+mod site;
+mod wxr;
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde::Serialize;
+
+use r2d2::Pool;
+use r2d2_sqlite::SqliteConnectionManager;
+
+use crate::db::models::{Attachment, Category, Comment, Passage};
+use crate::db::repositories::{
+    AttachmentRepository, CategoryRepository, CommentRepository, PassageRepository, PassageRevisionRepository, Repository,
+};
+
+/// 导入产生的修订记录的来源
+const IMPORT_SOURCE: &str = "import";
+
+/// 默认分类，导入时不重复创建
+const DEFAULT_CATEGORY: &str = "未分类";
+
+/// 导入源类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceKind {
+    Hexo,
+    Hugo,
+    Jekyll,
+    WordPress,
+}
+
+/// 导入的评论
+#[derive(Debug, Clone)]
+pub struct ImportedComment {
+    pub author: String,
+    pub content: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// 正文中引用的本地文件；`file` 为 None 表示导入源中找不到该文件
+#[derive(Debug, Clone, PartialEq)]
+pub struct Asset {
+    pub url: String,
+    pub file: Option<PathBuf>,
+}
+
+/// 从导入源读取的一篇文章
+#[derive(Debug, Clone, Default)]
+pub struct ImportedPost {
+    /// 源文件的相对路径或 WordPress 中的原文链接
+    pub source: String,
+    pub title: String,
+    /// Markdown 正文（WordPress 的 HTML 正文按原样保留）
+    pub content: String,
+    pub summary: Option<String>,
+    pub slug: Option<String>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub author: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub draft: bool,
+    pub private: bool,
+    pub password: Option<String>,
+    pub pinned: bool,
+    pub cover_image: Option<String>,
+    pub comments: Vec<ImportedComment>,
+    pub assets: Vec<Asset>,
+}
+
+/// 分类及其父分类
+#[derive(Debug, Clone, PartialEq)]
+pub struct CategoryNode {
+    pub name: String,
+    pub parent: Option<String>,
+}
+
+/// 导入计划：导入源中的全部文章和分类层级（父分类排在子分类之前）
+#[derive(Debug)]
+pub struct ImportPlan {
+    pub kind: SourceKind,
+    pub posts: Vec<ImportedPost>,
+    pub categories: Vec<CategoryNode>,
+    /// 不支持导入的条目数（WordPress 页面、菜单等）
+    pub skipped: usize,
+    pub warnings: Vec<String>,
+}
+
+impl ImportPlan {
+    fn new(kind: SourceKind) -> Self {
+        ImportPlan { kind, posts: Vec::new(), categories: Vec::new(), skipped: 0, warnings: Vec::new() }
+    }
+
+    /// 添加分类路径（根分类在前），返回最末一级分类
+    fn add_category_path(&mut self, path: &[String]) -> Option<String> {
+        let mut parent: Option<String> = None;
+        for name in path.iter().map(|n| n.trim()).filter(|n| !n.is_empty()) {
+            if !self.categories.iter().any(|c| c.name == name) {
+                self.categories.push(CategoryNode { name: name.to_string(), parent: parent.clone() });
+            }
+            parent = Some(name.to_string());
+        }
+        parent
+    }
+}
+
+/// 单篇文章的导入预览
+#[derive(Debug, Serialize)]
+pub struct PassageReport {
+    pub title: String,
+    pub slug: String,
+    pub source: String,
+    pub status: String,
+    pub visibility: String,
+    pub category: String,
+    pub tags: Vec<String>,
+    pub comments: usize,
+    pub attachments: usize,
+    pub missing_attachments: Vec<String>,
+    /// create：新建；skip：slug 已被现有文章占用，跳过
+    pub action: &'static str,
+}
+
+/// 导入报告
+#[derive(Debug, Serialize)]
+pub struct ImportReport {
+    pub source: SourceKind,
+    pub dry_run: bool,
+    pub passages: Vec<PassageReport>,
+    pub created: usize,
+    pub skipped: usize,
+    pub new_categories: Vec<String>,
+    pub new_tags: Vec<String>,
+    pub comments: usize,
+    pub attachments: usize,
+    pub warnings: Vec<String>,
+}
+
+/// 识别导入源并读取全部文章，不写入任何数据
+///
+/// `path` 为 WordPress 导出的 XML 文件，或 Hexo、Hugo、Jekyll 的站点目录；
+/// 目录中只有 XML 文件（可附带 `uploads` 目录）时按 WordPress 导入
+pub fn scan(path: &Path) -> Result<ImportPlan, String> {
+    if path.is_file() {
+        return wxr::scan(path);
+    }
+    if !path.is_dir() {
+        return Err(format!("导入源不存在: {}", path.display()));
+    }
+
+    // 上传整个文件夹时外面通常还包了一层目录，逐层进入只含一个子目录的目录
+    let mut root = path.to_path_buf();
+    for _ in 0..8 {
+        if let Some(kind) = site::detect(&root) {
+            return site::scan(&root, kind);
+        }
+        if let Some(xml) = find_xml(&root) {
+            return wxr::scan(&xml);
+        }
+        match only_subdir(&root) {
+            Some(dir) => root = dir,
+            None => break,
+        }
+    }
+    Err("无法识别的导入源：需要 Hexo、Hugo、Jekyll 站点目录或 WordPress 导出的 XML 文件".to_string())
+}
+
+/// 目录中除隐藏文件外只有一个子目录时返回该子目录
+fn only_subdir(dir: &Path) -> Option<PathBuf> {
+    let entries: Vec<PathBuf> = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| !is_hidden(p))
+        .collect();
+    match entries.as_slice() {
+        [only] if only.is_dir() => Some(only.clone()),
+        _ => None,
+    }
+}
+
+fn find_xml(dir: &Path) -> Option<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("xml")))
+        .collect();
+    files.sort();
+    files.into_iter().next()
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with('.'))
+}
+
+/// `candidate` 存在且位于 `root` 之内时返回规范化后的路径，防止正文中的 `../` 读取导入源以外的文件
+fn contained_file(root: &Path, candidate: &Path) -> Option<PathBuf> {
+    let root = root.canonicalize().ok()?;
+    let file = candidate.canonicalize().ok()?;
+    (file.starts_with(&root) && file.is_file()).then_some(file)
+}
+
+/// 链接地址去掉查询参数和锚点，并解码 `%XX`
+fn link_path(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    urlencoding::decode(path).map(|p| p.into_owned()).unwrap_or_else(|_| path.to_string())
+}
+
+/// 链接是否指向一个文件（带扩展名且不是网页），用于报告找不到的附件
+fn looks_like_file(url: &str) -> bool {
+    let path = link_path(url);
+    let name = path.rsplit('/').next().unwrap_or("");
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() && !ext.is_empty() => {
+            !matches!(ext.to_ascii_lowercase().as_str(), "html" | "htm" | "md" | "markdown" | "php" | "xml")
+        }
+        _ => false,
+    }
+}
+
+/// Markdown 链接和图片、引用式链接定义以及 HTML 的 src/href 属性
+static LINK_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"!?\[[^\]]*\]\(\s*<?([^)\s>]+)>?|(?m)^[ \t]*\[[^\]]+\]:[ \t]*<?([^\s>]+)>?|\b(?:src|href)\s*=\s*["']([^"']+)["']"#,
+    )
+    .unwrap()
+});
+
+fn link_target<'a>(caps: &Captures<'a>) -> Option<regex::Match<'a>> {
+    caps.get(1).or_else(|| caps.get(2)).or_else(|| caps.get(3))
+}
+
+/// 正文中的全部链接地址
+pub(crate) fn link_targets(content: &str) -> Vec<String> {
+    let mut targets: Vec<String> = Vec::new();
+    for caps in LINK_RE.captures_iter(content) {
+        if let Some(target) = link_target(&caps) {
+            if !targets.iter().any(|t| t == target.as_str()) {
+                targets.push(target.as_str().to_string());
+            }
+        }
+    }
+    targets
+}
+
+/// 按映射替换正文中的链接地址，只替换完整的链接，不影响其他文本
+pub(crate) fn rewrite_links(content: &str, links: &HashMap<String, String>) -> String {
+    if links.is_empty() {
+        return content.to_string();
+    }
+    LINK_RE
+        .replace_all(content, |caps: &Captures| {
+            let whole = caps.get(0).unwrap();
+            match link_target(caps).and_then(|t| links.get(t.as_str()).map(|new| (t, new))) {
+                Some((target, new)) => {
+                    let start = target.start() - whole.start();
+                    let end = target.end() - whole.start();
+                    format!("{}{}{}", &whole.as_str()[..start], new, &whole.as_str()[end..])
+                }
+                None => whole.as_str().to_string(),
+            }
+        })
+        .into_owned()
+}
+
+/// 按导入计划生成报告；`dry_run` 为 false 时同时写入文章、分类、标签、评论和附件
+///
+/// slug 已被现有文章占用的文章视为已导入过并跳过，因此可以重复导入同一个导入源
+pub async fn run(
+    plan: &ImportPlan,
+    passage_repo: &PassageRepository,
+    editor: &str,
+    dry_run: bool,
+) -> Result<ImportReport, String> {
+    let pool = passage_repo.get_pool();
+    let (existing_categories, existing_tags) = {
+        let conn = pool.get().map_err(|e| format!("获取数据库连接失败: {}", e))?;
+        (
+            query_names(&conn, "SELECT name FROM categories")?,
+            query_names(&conn, "SELECT name FROM tags")?,
+        )
+    };
+
+    let mut report = ImportReport {
+        source: plan.kind,
+        dry_run,
+        passages: Vec::new(),
+        created: 0,
+        skipped: plan.skipped,
+        new_categories: Vec::new(),
+        new_tags: Vec::new(),
+        comments: 0,
+        attachments: 0,
+        warnings: plan.warnings.clone(),
+    };
+
+    for node in &plan.categories {
+        if node.name != DEFAULT_CATEGORY && !existing_categories.contains(&node.name) {
+            report.new_categories.push(node.name.clone());
+        }
+    }
+    for tag in plan.posts.iter().flat_map(|p| &p.tags) {
+        if !existing_tags.contains(tag) && !report.new_tags.contains(tag) {
+            report.new_tags.push(tag.clone());
+        }
+    }
+
+    if !dry_run {
+        ensure_categories(plan, &CategoryRepository::new(pool.clone())).await?;
+        crate::handlers::api_handlers::passage::ensure_tags_exist(&report.new_tags).await?;
+    }
+
+    let mut taken: HashSet<String> = HashSet::new();
+    let mut stored: HashMap<PathBuf, StoredFile> = HashMap::new();
+    for post in &plan.posts {
+        let base = crate::slug::normalize(post.slug.as_deref().unwrap_or(&post.title))
+            .or_else(|| crate::slug::normalize(&post.title))
+            .unwrap_or_else(|| "passage".to_string());
+        let available = |slug: String| async move {
+            passage_repo.is_slug_available(&slug, None).await.map_err(|e| format!("检查 slug 失败: {}", e))
+        };
+
+        let mut entry = PassageReport {
+            title: post.title.clone(),
+            slug: base.clone(),
+            source: post.source.clone(),
+            status: status_of(post).to_string(),
+            visibility: visibility_of(post).to_string(),
+            category: post.category.clone().unwrap_or_else(|| DEFAULT_CATEGORY.to_string()),
+            tags: post.tags.clone(),
+            comments: post.comments.len(),
+            attachments: post.assets.iter().filter(|a| a.file.is_some()).count(),
+            missing_attachments: post.assets.iter().filter(|a| a.file.is_none()).map(|a| a.url.clone()).collect(),
+            action: "create",
+        };
+
+        if !available(base.clone()).await? {
+            entry.action = "skip";
+            report.skipped += 1;
+            report.passages.push(entry);
+            continue;
+        }
+
+        // 导入源中的 slug 重复时依次追加 -2、-3…
+        let mut slug = base.clone();
+        let mut n = 2;
+        while taken.contains(&slug) || !available(slug.clone()).await? {
+            slug = format!("{}-{}", base, n);
+            n += 1;
+        }
+        taken.insert(slug.clone());
+        entry.slug = slug;
+
+        if !dry_run {
+            let new_files = import_post(post, &entry.slug, passage_repo, editor, &mut stored)
+                .await
+                .map_err(|e| format!("导入《{}》失败: {}", post.title, e))?;
+            entry.attachments = new_files;
+        }
+
+        report.created += 1;
+        report.comments += entry.comments;
+        report.attachments += entry.attachments;
+        report.passages.push(entry);
+    }
+
+    if !dry_run {
+        crate::cache::invalidate_passages();
+    }
+    Ok(report)
+}
+
+fn query_names(conn: &rusqlite::Connection, sql: &str) -> Result<HashSet<String>, String> {
+    let mut stmt = conn.prepare(sql).map_err(|e| format!("查询失败: {}", e))?;
+    let names = stmt
+        .query_map([], |row| row.get(0))
+        .and_then(|rows| rows.collect::<Result<HashSet<String>, _>>())
+        .map_err(|e| format!("查询失败: {}", e))?;
+    Ok(names)
+}
+
+fn status_of(post: &ImportedPost) -> &'static str {
+    if post.draft { "draft" } else { "published" }
+}
+
+fn visibility_of(post: &ImportedPost) -> &'static str {
+    if post.password.is_some() {
+        "password"
+    } else if post.private {
+        "private"
+    } else {
+        "public"
+    }
+}
+
+/// 按层级创建缺少的分类，已存在的分类保持原有的父分类
+async fn ensure_categories(plan: &ImportPlan, category_repo: &CategoryRepository) -> Result<(), String> {
+    for node in &plan.categories {
+        if node.name == DEFAULT_CATEGORY || category_repo.get_by_name(&node.name).await.is_ok() {
+            continue;
+        }
+
+        let parent_id = match &node.parent {
+            Some(parent) => category_repo.get_by_name(parent).await.ok().and_then(|c| c.id),
+            None => None,
+        };
+        let now = Utc::now();
+        let category = Category {
+            id: None,
+            parent_id,
+            name: node.name.clone(),
+            description: format!("导入的分类: {}", node.name),
+            icon: "📁".to_string(),
+            sort_order: 0,
+            is_enabled: true,
+            created_at: now,
+            updated_at: now,
+        };
+        category_repo.create(&category).await.map_err(|e| format!("创建分类失败: {}", e))?;
+    }
+    Ok(())
+}
+
+/// 已复制到附件目录的文件
+struct StoredFile {
+    url: String,
+    attachment: Attachment,
+}
+
+/// 写入一篇文章及其评论和附件，返回新复制的附件数
+///
+/// 先复制附件文件，再在一个事务中写入文章、访问密码、评论和附件记录；
+/// 任何一步失败时事务回滚，并删除本篇新复制的文件，重新导入时这篇文章不会被当作已存在而跳过
+async fn import_post(
+    post: &ImportedPost,
+    slug: &str,
+    passage_repo: &PassageRepository,
+    editor: &str,
+    stored: &mut HashMap<PathBuf, StoredFile>,
+) -> Result<usize, String> {
+    // 复制正文引用的文件，同一个文件只复制一次
+    let mut links: HashMap<String, String> = HashMap::new();
+    let mut new_files: Vec<PathBuf> = Vec::new();
+    for asset in &post.assets {
+        let Some(file) = &asset.file else { continue };
+        if !stored.contains_key(file) {
+            match store_file(file) {
+                Ok(file_info) => {
+                    stored.insert(file.clone(), file_info);
+                    new_files.push(file.clone());
+                }
+                Err(e) => {
+                    discard_files(&new_files, stored);
+                    return Err(e);
+                }
+            }
+        }
+        links.insert(asset.url.clone(), stored[file].url.clone());
+    }
+
+    match write_post(post, slug, passage_repo, editor, &links, &new_files, stored).await {
+        Ok(()) => Ok(new_files.len()),
+        Err(e) => {
+            discard_files(&new_files, stored);
+            Err(e)
+        }
+    }
+}
+
+/// 删除导入失败的文章新复制的附件文件
+fn discard_files(new_files: &[PathBuf], stored: &mut HashMap<PathBuf, StoredFile>) {
+    for file in new_files {
+        if let Some(file_info) = stored.remove(file) {
+            if let Err(e) = fs::remove_file(&file_info.attachment.file_path) {
+                eprintln!("删除附件失败 {}: {}", file_info.attachment.file_path, e);
+            }
+        }
+    }
+}
+
+/// 在一个事务中写入文章及其评论和附件记录，提交后再写 Markdown 文件和修订记录
+async fn write_post(
+    post: &ImportedPost,
+    slug: &str,
+    passage_repo: &PassageRepository,
+    editor: &str,
+    links: &HashMap<String, String>,
+    new_files: &[PathBuf],
+    stored: &HashMap<PathBuf, StoredFile>,
+) -> Result<(), String> {
+    let pool = passage_repo.get_pool();
+    let now = Utc::now();
+
+    let content = rewrite_links(&post.content, links);
+    let (author_id, author, trusted) = {
+        let conn = pool.get().map_err(|e| format!("获取数据库连接失败: {}", e))?;
+        let (author_id, author) = resolve_author(&conn, post.author.as_deref()).map_err(|e| format!("查询作者失败: {}", e))?;
+        let trusted = crate::db::authors::is_trusted(&conn, author_id).map_err(|e| format!("查询作者失败: {}", e))?;
+        (author_id, author, trusted)
+    };
+    let html_content = crate::markdown::render_passage(&content, trusted);
+    let summary = post.summary.clone().or_else(|| Some(crate::markdown::extract_summary(&html_content)));
+    let reading = crate::reading::stats(&html_content);
+
+    let created_at = post.created_at.unwrap_or(now);
+    let is_scheduled = !post.draft && created_at > now;
+    let cover_image = match &post.cover_image {
+        Some(cover) => links.get(cover).cloned().unwrap_or_else(|| cover.clone()),
+        None => "/img/passage-cover.webp".to_string(),
+    };
+    let file_path = format!("markdown/{}/{}.md", created_at.format("%Y/%m/%d"), slug);
+
+    let passage = Passage {
+        id: None,
+        uuid: None,
+        title: post.title.clone(),
+        content: html_content,
+        original_content: Some(content),
+        summary,
+        author,
+        tags: serde_json::to_string(&post.tags).unwrap_or_else(|_| "[]".to_string()),
+        category: post.category.clone().unwrap_or_else(|| DEFAULT_CATEGORY.to_string()),
+        status: status_of(post).to_string(),
+        file_path: Some(file_path.clone()),
+        visibility: visibility_of(post).to_string(),
+        is_scheduled,
+        published_at: is_scheduled.then_some(created_at),
+        cover_image: Some(cover_image),
+        created_at,
+        updated_at: post.updated_at.unwrap_or(created_at),
+        expires_at: None,
+        slug: Some(slug.to_string()),
+        word_count: reading.word_count,
+        reading_minutes: reading.reading_minutes,
+        author_id,
+        pinned: post.pinned,
+        featured: false,
+        pin_order: 0,
+    };
+
+    let password_hash = match &post.password {
+        Some(password) => Some(crate::handlers::api_handlers::user::hash_password(password)?),
+        None => None,
+    };
+
+    let (id, uuid) = insert_post(&pool, post, &passage, password_hash.as_deref(), new_files, stored)?;
+
+    let created = Passage { id: Some(id), uuid: Some(uuid), ..passage };
+    if let Err(e) = crate::handlers::api_handlers::passage::update_markdown_file(&file_path, &created) {
+        eprintln!("写入 Markdown 文件失败 {}: {}", file_path, e);
+    }
+    let revision_repo = PassageRevisionRepository::new(pool);
+    if let Err(e) = revision_repo.record_change(None, &created, editor, IMPORT_SOURCE).await {
+        eprintln!("记录文章修订失败 {}: {}", file_path, e);
+    }
+    Ok(())
+}
+
+/// 在一个事务中写入文章、访问密码、评论和附件记录，返回文章的 (ID, UUID)
+fn insert_post(
+    pool: &Pool<SqliteConnectionManager>,
+    post: &ImportedPost,
+    passage: &Passage,
+    password_hash: Option<&str>,
+    new_files: &[PathBuf],
+    stored: &HashMap<PathBuf, StoredFile>,
+) -> Result<(i64, String), String> {
+    let conn = pool.get().map_err(|e| format!("获取数据库连接失败: {}", e))?;
+    let tx = conn.unchecked_transaction().map_err(|e| format!("开启事务失败: {}", e))?;
+    let (id, uuid) = PassageRepository::insert_with(&tx, passage).map_err(|e| format!("创建文章失败: {}", e))?;
+    if let Some(hash) = password_hash {
+        tx.execute("UPDATE passages SET password_hash = ?1 WHERE id = ?2", rusqlite::params![hash, id])
+            .map_err(|e| format!("设置访问密码失败: {}", e))?;
+    }
+    for comment in &post.comments {
+        let comment = Comment {
+            id: None,
+            username: comment.author.clone(),
+            content: comment_html(&comment.content),
+            passage_uuid: uuid.clone(),
+            created_at: comment.created_at.unwrap_or(passage.created_at),
+        };
+        CommentRepository::insert_with(&tx, &comment).map_err(|e| format!("创建评论失败: {}", e))?;
+    }
+    for file in new_files {
+        let mut attachment = stored[file].attachment.clone();
+        attachment.passage_uuid = Some(uuid.clone());
+        AttachmentRepository::insert_with(&tx, &attachment).map_err(|e| format!("创建附件记录失败: {}", e))?;
+    }
+    tx.commit().map_err(|e| format!("提交事务失败: {}", e))?;
+    Ok((id, uuid))
+}
+
+/// 作者：导入源中的作者名与已有用户名相同时关联到该用户，否则归属站长
+fn resolve_author(conn: &rusqlite::Connection, name: Option<&str>) -> Result<(Option<i64>, String), rusqlite::Error> {
+    use rusqlite::OptionalExtension;
+
+    let user_id = match name {
+        Some(name) => conn
+            .query_row("SELECT id FROM users WHERE username = ?", [name], |row| row.get(0))
+            .optional()?,
+        None => None,
+    };
+    let user_id = match user_id {
+        Some(id) => Some(id),
+        None => crate::db::authors::default_author_id(conn)?,
+    };
+    let author = match user_id {
+        Some(id) => crate::db::authors::display_name(conn, id)?,
+        None => None,
+    };
+    Ok((user_id, author.unwrap_or_else(|| "Admin".to_string())))
+}
+
+/// 把文件复制到附件目录，文件名只保留字母、数字和 `._-`，避免改写后的链接中出现空格和括号
+fn store_file(file: &Path) -> Result<StoredFile, String> {
+    let file_name = file.file_name().and_then(|n| n.to_str()).unwrap_or("file").to_string();
+    let safe_name: String = file_name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .collect();
+
+    fs::create_dir_all("attachments").map_err(|e| format!("创建附件目录失败: {}", e))?;
+    let timestamp = Utc::now().timestamp();
+    let mut stored_name = format!("{}_{}", timestamp, safe_name);
+    let mut n = 2;
+    while Path::new("attachments").join(&stored_name).exists() {
+        stored_name = format!("{}_{}_{}", timestamp, n, safe_name);
+        n += 1;
+    }
+
+    let file_path = format!("attachments/{}", stored_name);
+    let file_size = fs::copy(file, &file_path).map_err(|e| format!("复制附件失败 {}: {}", file.display(), e))?;
+    let content_type = mime_guess::from_path(file).first_or_octet_stream().to_string();
+
+    Ok(StoredFile {
+        url: format!("/{}", file_path),
+        attachment: Attachment {
+            id: None,
+            file_type: crate::handlers::api_handlers::attachments::determine_file_type(&file_name, &content_type),
+            file_name,
+            stored_name,
+            file_path,
+            content_type,
+            file_size: file_size as i64,
+            passage_uuid: None,
+            visibility: "public".to_string(),
+            show_in_passage: false,
+            uploaded_at: Utc::now(),
+        },
+    })
+}
+
+/// 评论内容转为 HTML：纯文本按空行分段、换行转为 `<br>`，再按评论规则清理
+fn comment_html(content: &str) -> String {
+    let content = content.trim();
+    let html = if content.contains("<p") {
+        content.to_string()
+    } else {
+        content
+            .split("\n\n")
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(|p| format!("<p>{}</p>", p.replace('\n', "<br>")))
+            .collect()
+    };
+    crate::sanitize::clean(&html, crate::sanitize::Policy::Comment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_targets_and_rewrite() {
+        let content = "![图](images/a.png \"标题\") [下载](/files/b.zip)\n\n[ref]: ./c.pdf\n\n<img src=\"images/a.png\"> ![](images/aa.png)";
+        assert_eq!(
+            link_targets(content),
+            vec!["images/a.png", "/files/b.zip", "./c.pdf", "images/aa.png"]
+        );
+
+        let links = HashMap::from([("images/a.png".to_string(), "/attachments/1_a.png".to_string())]);
+        let rewritten = rewrite_links(content, &links);
+        assert!(rewritten.starts_with("![图](/attachments/1_a.png \"标题\")"));
+        assert!(rewritten.contains("<img src=\"/attachments/1_a.png\">"));
+        assert!(rewritten.contains("![](images/aa.png)"));
+    }
+
+    #[test]
+    fn test_looks_like_file() {
+        assert!(looks_like_file("/images/a.png?v=1"));
+        assert!(looks_like_file("files/%E6%96%87%E6%A1%A3.pdf"));
+        assert!(!looks_like_file("/2020/01/hello/"));
+        assert!(!looks_like_file("/about.html"));
+        assert!(!looks_like_file(".htaccess"));
+    }
+
+    #[test]
+    fn test_category_path() {
+        let mut plan = ImportPlan::new(SourceKind::Hexo);
+        assert_eq!(plan.add_category_path(&["编程".to_string(), "Rust".to_string()]).as_deref(), Some("Rust"));
+        assert_eq!(plan.add_category_path(&["编程".to_string()]).as_deref(), Some("编程"));
+        assert_eq!(plan.add_category_path(&[]), None);
+        assert_eq!(
+            plan.categories,
+            vec![
+                CategoryNode { name: "编程".to_string(), parent: None },
+                CategoryNode { name: "Rust".to_string(), parent: Some("编程".to_string()) },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_failed_post_is_rolled_back() {
+        let pool = crate::db::init::test_pool();
+        let dir = std::env::temp_dir().join(format!("rustblog-import-{}", crate::id_generator::generate_unique_id()));
+        fs::create_dir_all(&dir).unwrap();
+        let copied = dir.join("1_a.png");
+        fs::write(&copied, "png").unwrap();
+
+        let source = PathBuf::from("images/a.png");
+        let mut stored = HashMap::from([(
+            source.clone(),
+            StoredFile {
+                url: "/attachments/1_a.png".to_string(),
+                attachment: Attachment {
+                    id: None,
+                    file_name: "a.png".to_string(),
+                    stored_name: "1_a.png".to_string(),
+                    file_path: copied.to_string_lossy().to_string(),
+                    file_type: "image".to_string(),
+                    content_type: "image/png".to_string(),
+                    file_size: 3,
+                    passage_uuid: None,
+                    visibility: "public".to_string(),
+                    show_in_passage: false,
+                    uploaded_at: Utc::now(),
+                },
+            },
+        )]);
+        let post = ImportedPost {
+            title: "导入".to_string(),
+            comments: vec![ImportedComment { author: "访客".to_string(), content: "你好".to_string(), created_at: None }],
+            ..Default::default()
+        };
+        let passage = Passage::sample("导入", "正文");
+        let count = |table: &str| -> i64 {
+            pool.get().unwrap().query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0)).unwrap()
+        };
+
+        // 附件记录写入失败时，文章和评论一起回滚
+        pool.get().unwrap().execute_batch("DROP TABLE attachments").unwrap();
+        let new_files = vec![source.clone()];
+        assert!(insert_post(&pool, &post, &passage, Some("hash"), &new_files, &stored).is_err());
+        assert_eq!(count("passages"), 0);
+        assert_eq!(count("comments"), 0);
+
+        // 新复制的文件随之删除
+        discard_files(&new_files, &mut stored);
+        assert!(!copied.exists());
+        assert!(stored.is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,442 @@
+//! Hexo、Hugo、Jekyll 站点源码目录
+//!
+//! 三者都是 Markdown + front matter，差别在于文章目录、字段名和正文中的模板标签
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+
+use super::{contained_file, is_hidden, link_path, looks_like_file, Asset, ImportPlan, ImportedPost, SourceKind};
+use crate::front_matter::parse_datetime;
+
+/// 遍历文章目录时跳过的目录
+const SKIPPED_DIRS: [&str; 4] = ["node_modules", "public", "_site", "resources"];
+
+/// 识别站点类型
+pub(super) fn detect(root: &Path) -> Option<SourceKind> {
+    if root.join("source/_posts").is_dir() {
+        Some(SourceKind::Hexo)
+    } else if root.join("_posts").is_dir() {
+        Some(SourceKind::Jekyll)
+    } else if root.join("content").is_dir() {
+        Some(SourceKind::Hugo)
+    } else {
+        None
+    }
+}
+
+/// 文章目录（目录，是否为草稿目录）
+fn post_dirs(kind: SourceKind) -> &'static [(&'static str, bool)] {
+    match kind {
+        SourceKind::Hexo => &[("source/_posts", false), ("source/_drafts", true)],
+        SourceKind::Jekyll => &[("_posts", false), ("_drafts", true)],
+        _ => &[("content", false)],
+    }
+}
+
+/// 以 `/` 开头的链接对应的目录
+fn asset_root(root: &Path, kind: SourceKind) -> PathBuf {
+    match kind {
+        SourceKind::Hexo => root.join("source"),
+        SourceKind::Hugo => root.join("static"),
+        _ => root.to_path_buf(),
+    }
+}
+
+/// 读取站点中的全部文章，解析失败的文件记入警告并跳过
+pub(super) fn scan(root: &Path, kind: SourceKind) -> Result<ImportPlan, String> {
+    let mut plan = ImportPlan::new(kind);
+
+    for (dir, drafts) in post_dirs(kind) {
+        for path in markdown_files(&root.join(dir))? {
+            let source = path.strip_prefix(root).unwrap_or(&path).to_string_lossy().to_string();
+            let raw = match fs::read_to_string(&path) {
+                Ok(raw) => raw,
+                Err(e) => {
+                    plan.warnings.push(format!("{}: 读取失败: {}", source, e));
+                    continue;
+                }
+            };
+            match parse_post(&mut plan, kind, root, &path, &raw, *drafts) {
+                Ok(mut post) => {
+                    post.source = source;
+                    plan.posts.push(post);
+                }
+                Err(e) => plan.warnings.push(format!("{}: {}", source, e)),
+            }
+        }
+    }
+
+    if plan.posts.is_empty() {
+        plan.warnings.push("没有找到文章".to_string());
+    }
+    Ok(plan)
+}
+
+/// 递归查找 Markdown 文件（Hugo 的 `_index.md` 是栏目页，不是文章）
+fn markdown_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    if !dir.is_dir() {
+        return Ok(files);
+    }
+
+    let mut dir_stack = vec![dir.to_path_buf()];
+    while let Some(current_dir) = dir_stack.pop() {
+        let entries = fs::read_dir(&current_dir).map_err(|e| format!("读取目录失败: {}", e))?;
+        for entry in entries {
+            let path = entry.map_err(|e| format!("读取条目失败: {}", e))?.path();
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            if is_hidden(&path) {
+                continue;
+            }
+            if path.is_dir() {
+                if !SKIPPED_DIRS.contains(&name) {
+                    dir_stack.push(path);
+                }
+            } else if name != "_index.md" && path.extension().is_some_and(|ext| ext == "md" || ext == "markdown") {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn parse_post(
+    plan: &mut ImportPlan,
+    kind: SourceKind,
+    root: &Path,
+    path: &Path,
+    raw: &str,
+    in_drafts: bool,
+) -> Result<ImportedPost, String> {
+    let (meta, body) = match crate::front_matter::parse_value(raw)? {
+        (Value::Null, body) if kind == SourceKind::Hexo => bare_hexo_front_matter(body).unwrap_or((Value::Null, body)),
+        parsed => parsed,
+    };
+
+    let (file_date, file_slug) = file_name_parts(path, kind);
+    let title = string_field(&meta, &["title"]).or_else(|| file_slug.clone()).unwrap_or_else(|| "未命名文章".to_string());
+
+    let draft = in_drafts
+        || meta.get("draft").is_some_and(truthy)
+        || meta.get("published").is_some_and(|v| !truthy(v));
+    let pinned = ["sticky", "top", "pinned"].iter().any(|key| meta.get(*key).is_some_and(truthy));
+    let private = meta.get("private").is_some_and(truthy);
+
+    // Jekyll 的标签和分类可以写成空格分隔的字符串
+    let separator = if kind == SourceKind::Jekyll { ' ' } else { ',' };
+    let tags = meta.get("tags").map(|v| string_list(v, separator)).unwrap_or_default();
+    let category_path = category_path(&meta, kind, separator);
+    let category = plan.add_category_path(&category_path);
+
+    let cover_image = string_field(&meta, &["cover", "cover_image", "thumbnail", "featured_image", "image", "banner_img"])
+        .or_else(|| meta.get("images").map(|v| string_list(v, ',')).and_then(|images| images.into_iter().next()));
+
+    let content = convert_body(body, kind);
+    let post_dir = path.parent().unwrap_or(root);
+    let assets_root = asset_root(root, kind);
+    let mut targets = super::link_targets(&content);
+    targets.extend(cover_image.clone());
+
+    let mut assets: Vec<Asset> = Vec::new();
+    for url in targets {
+        if is_external(&url) || assets.iter().any(|a| a.url == url) {
+            continue;
+        }
+        let file = resolve_asset(root, &assets_root, post_dir, path, &url);
+        if file.is_some() || looks_like_file(&url) {
+            assets.push(Asset { url, file });
+        }
+    }
+
+    Ok(ImportedPost {
+        title,
+        content,
+        summary: string_field(&meta, &["summary", "description", "excerpt"]),
+        slug: string_field(&meta, &["slug"]).or(file_slug),
+        category,
+        tags,
+        author: string_field(&meta, &["author"]),
+        created_at: date_field(&meta, &["date", "publishDate"]).or(file_date),
+        updated_at: date_field(&meta, &["updated", "lastmod", "last_modified_at"]),
+        draft,
+        private,
+        // hexo-blog-encrypt 插件的文章密码
+        password: string_field(&meta, &["password"]).filter(|_| kind == SourceKind::Hexo),
+        pinned,
+        cover_image,
+        assets,
+        ..Default::default()
+    })
+}
+
+/// Hexo 允许省略开头的 `---`，只用一行 `---` 分隔元数据和正文
+fn bare_hexo_front_matter(content: &str) -> Option<(Value, &str)> {
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            let value: serde_yaml::Value = serde_yaml::from_str(&content[..offset]).ok()?;
+            let value = serde_json::to_value(value).ok()?;
+            let body = content[offset + line.len()..].trim_start_matches(['\r', '\n']);
+            return value.get("title").is_some().then_some((value, body));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// 从文件名中取日期和 slug：Jekyll 为 `YYYY-MM-DD-slug.md`，Hugo 的页面包 `index.md` 取目录名
+fn file_name_parts(path: &Path, kind: SourceKind) -> (Option<DateTime<Utc>>, Option<String>) {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+    let stem = if kind == SourceKind::Hugo && stem == "index" {
+        path.parent().and_then(|p| p.file_name()).and_then(|n| n.to_str()).unwrap_or(stem)
+    } else {
+        stem
+    };
+
+    if kind == SourceKind::Jekyll && stem.len() > 11 && stem.is_char_boundary(10) {
+        let (date, rest) = stem.split_at(10);
+        if let (Ok(date), Some(slug)) = (NaiveDate::parse_from_str(date, "%Y-%m-%d"), rest.strip_prefix('-')) {
+            let date = date.and_hms_opt(0, 0, 0).map(|d| d.and_utc());
+            return (date, Some(slug.to_string()));
+        }
+    }
+    (None, (!stem.is_empty()).then(|| stem.to_string()))
+}
+
+/// 分类路径（根分类在前）
+///
+/// Hexo 的 `categories: [A, B]` 表示 A 下的子分类 B，嵌套列表表示多个分类（取第一个）；
+/// Hugo 和 Jekyll 的多个分类是并列的，取第一个
+fn category_path(meta: &Value, kind: SourceKind, separator: char) -> Vec<String> {
+    let Some(value) = meta.get("categories").or_else(|| meta.get("category")) else {
+        return Vec::new();
+    };
+
+    if kind == SourceKind::Hexo {
+        if let Value::Array(items) = value {
+            if let Some(first @ Value::Array(_)) = items.first() {
+                return string_list(first, separator);
+            }
+        }
+        return string_list(value, separator);
+    }
+    string_list(value, separator).into_iter().take(1).collect()
+}
+
+/// 字符串、数字、布尔值都转为字符串，空字符串视为未设置
+fn scalar_string(value: &Value) -> Option<String> {
+    let text = match value {
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        _ => return None,
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+fn string_field(meta: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|key| meta.get(*key).and_then(scalar_string))
+}
+
+fn date_field(meta: &Value, keys: &[&str]) -> Option<DateTime<Utc>> {
+    keys.iter().find_map(|key| meta.get(*key).and_then(scalar_string).and_then(|d| parse_datetime(&d)))
+}
+
+/// 列表或分隔符分隔的字符串，去掉空白和重复项
+fn string_list(value: &Value, separator: char) -> Vec<String> {
+    let raw: Vec<String> = match value {
+        Value::Array(items) => items.iter().filter_map(scalar_string).collect(),
+        Value::String(s) => s.split(separator).map(str::to_string).collect(),
+        other => scalar_string(other).into_iter().collect(),
+    };
+
+    let mut list: Vec<String> = Vec::new();
+    for item in raw {
+        let item = item.trim();
+        if !item.is_empty() && !list.iter().any(|i| i == item) {
+            list.push(item.to_string());
+        }
+    }
+    list
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(s) => matches!(s.trim(), "true" | "yes" | "1"),
+        _ => false,
+    }
+}
+
+/// 把各站点特有的模板标签转为普通 Markdown
+fn convert_body(body: &str, kind: SourceKind) -> String {
+    static HEXO_ASSET_IMG: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r#"\{%\s*asset_img\s+(\S+)(?:\s+"?([^%"]*?)"?)?\s*%\}"#).unwrap());
+    static HEXO_ASSET_LINK: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r#"\{%\s*asset_link\s+(\S+)(?:\s+"?([^%"]*?)"?)?\s*%\}"#).unwrap());
+    static HEXO_ASSET_PATH: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{%\s*asset_path\s+(\S+)\s*%\}").unwrap());
+    static HUGO_FIGURE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{\{<\s*figure\s+(.*?)\s*/?>\}\}").unwrap());
+    static HUGO_ATTR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(\w+)\s*=\s*"([^"]*)""#).unwrap());
+    static JEKYLL_SITE_URL: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{\{\s*site\.(?:base)?url\s*\}\}").unwrap());
+    static JEKYLL_URL_FILTER: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r#"\{\{\s*["']([^"']+)["']\s*\|\s*(?:relative_url|absolute_url)\s*\}\}"#).unwrap());
+    static JEKYLL_RAW: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{%-?\s*(?:raw|endraw)\s*-?%\}").unwrap());
+    static JEKYLL_HIGHLIGHT: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"(?s)\{%\s*highlight\s+(\w+)[^%]*%\}\n?(.*?)\n?\{%\s*endhighlight\s*%\}").unwrap()
+    });
+
+    match kind {
+        SourceKind::Hexo => {
+            let body = HEXO_ASSET_IMG.replace_all(body, "![$2]($1)");
+            let body = HEXO_ASSET_LINK.replace_all(&body, |caps: &regex::Captures| {
+                let title = caps.get(2).map_or("", |m| m.as_str());
+                format!("[{}]({})", if title.is_empty() { &caps[1] } else { title }, &caps[1])
+            });
+            HEXO_ASSET_PATH.replace_all(&body, "$1").into_owned()
+        }
+        SourceKind::Hugo => HUGO_FIGURE
+            .replace_all(body, |caps: &regex::Captures| {
+                let attrs: Vec<(String, String)> = HUGO_ATTR
+                    .captures_iter(&caps[1])
+                    .map(|a| (a[1].to_string(), a[2].to_string()))
+                    .collect();
+                let attr = |name: &str| attrs.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str());
+                match attr("src") {
+                    Some(src) => format!("![{}]({})", attr("alt").or(attr("title")).or(attr("caption")).unwrap_or(""), src),
+                    None => caps[0].to_string(),
+                }
+            })
+            .into_owned(),
+        _ => {
+            let body = JEKYLL_URL_FILTER.replace_all(body, "$1");
+            let body = JEKYLL_SITE_URL.replace_all(&body, "");
+            let body = JEKYLL_HIGHLIGHT.replace_all(&body, "```$1\n$2\n```");
+            JEKYLL_RAW.replace_all(&body, "").into_owned()
+        }
+    }
+}
+
+fn is_external(url: &str) -> bool {
+    let lower = url.to_ascii_lowercase();
+    url.starts_with('#')
+        || url.starts_with("//")
+        || ["http:", "https:", "mailto:", "data:", "tel:", "javascript:"].iter().any(|p| lower.starts_with(p))
+}
+
+/// 在导入源中查找链接指向的文件
+///
+/// 以 `/` 开头的链接相对于站点的静态文件目录；其余链接相对于文章所在目录，
+/// Hexo 开启 post_asset_folder 时还会在与文章同名的目录中查找
+fn resolve_asset(root: &Path, assets_root: &Path, post_dir: &Path, post_path: &Path, url: &str) -> Option<PathBuf> {
+    let path = link_path(url);
+    if let Some(absolute) = path.strip_prefix('/') {
+        return contained_file(root, &assets_root.join(absolute));
+    }
+
+    let mut candidates = vec![post_dir.join(&path)];
+    if let Some(stem) = post_path.file_stem() {
+        candidates.push(post_dir.join(stem).join(&path));
+    }
+    candidates.iter().find_map(|candidate| contained_file(root, candidate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn site(files: &[(&str, &str)]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("rustblog-import-{}", crate::id_generator::generate_unique_id()));
+        for (path, content) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn test_hexo_site() {
+        let root = site(&[
+            ("_config.yml", "title: blog"),
+            (
+                "source/_posts/hello.md",
+                "---\ntitle: 你好\ndate: 2020-01-02 03:04:05\ncategories: [编程, Rust]\ntags: rust\nsticky: 10\n---\n{% asset_img cat.png 小猫 %}\n![](/images/logo.png) ![](../../../etc/passwd.png)\n",
+            ),
+            ("source/_posts/hello/cat.png", "png"),
+            ("source/images/logo.png", "png"),
+            ("source/_drafts/wip.md", "title: 草稿\n---\n正文\n"),
+        ]);
+        let plan = super::super::scan(&root).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(plan.kind, SourceKind::Hexo);
+        assert_eq!(plan.posts.len(), 2);
+        let post = &plan.posts[0];
+        assert_eq!(post.title, "你好");
+        assert_eq!(post.slug.as_deref(), Some("hello"));
+        assert_eq!(post.category.as_deref(), Some("Rust"));
+        assert_eq!(plan.categories[1].parent.as_deref(), Some("编程"));
+        assert_eq!(post.tags, vec!["rust"]);
+        assert!(post.pinned && !post.draft);
+        assert_eq!(post.created_at.unwrap().to_rfc3339(), "2020-01-02T03:04:05+00:00");
+        assert!(post.content.starts_with("![小猫](cat.png)"));
+        assert_eq!(post.assets.len(), 3);
+        assert!(post.assets[0].file.is_some() && post.assets[1].file.is_some());
+        assert_eq!(post.assets[2].file, None);
+
+        let draft = &plan.posts[1];
+        assert_eq!(draft.title, "草稿");
+        assert!(draft.draft);
+        assert_eq!(draft.content, "正文\n");
+    }
+
+    #[test]
+    fn test_jekyll_and_hugo_sites() {
+        let root = site(&[(
+            "_posts/2019-05-06-first-post.markdown",
+            "---\ntitle: First\ncategories: notes misc\ntags: a b\npublished: false\n---\n![]({{ site.baseurl }}/assets/x.png)\n{% highlight rust %}\nfn main() {}\n{% endhighlight %}\n",
+        )]);
+        let plan = super::super::scan(&root).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(plan.kind, SourceKind::Jekyll);
+        let post = &plan.posts[0];
+        assert_eq!(post.slug.as_deref(), Some("first-post"));
+        assert_eq!(post.created_at.unwrap().to_rfc3339(), "2019-05-06T00:00:00+00:00");
+        assert_eq!(post.category.as_deref(), Some("notes"));
+        assert_eq!(post.tags, vec!["a", "b"]);
+        assert!(post.draft);
+        assert!(post.content.contains("![](/assets/x.png)"));
+        assert!(post.content.contains("```rust\nfn main() {}\n```"));
+        assert_eq!(post.assets, vec![Asset { url: "/assets/x.png".to_string(), file: None }]);
+
+        let root = site(&[
+            ("hugo.toml", "title = 'blog'"),
+            ("content/_index.md", "栏目"),
+            (
+                "content/posts/trip/index.md",
+                "+++\ntitle = \"旅行\"\ndate = 2021-07-01T10:00:00+08:00\ndraft = true\ncategories = [\"生活\", \"游记\"]\n+++\n{{< figure src=\"photo.jpg\" title=\"海边\" >}}\n",
+            ),
+            ("content/posts/trip/photo.jpg", "jpg"),
+        ]);
+        let plan = super::super::scan(&root).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(plan.kind, SourceKind::Hugo);
+        assert_eq!(plan.posts.len(), 1);
+        let post = &plan.posts[0];
+        assert_eq!(post.slug.as_deref(), Some("trip"));
+        assert_eq!(post.category.as_deref(), Some("生活"));
+        assert!(post.draft);
+        assert_eq!(post.created_at.unwrap().to_rfc3339(), "2021-07-01T02:00:00+00:00");
+        assert_eq!(post.content, "![海边](photo.jpg)\n");
+        assert!(post.assets[0].file.is_some());
+    }
+}
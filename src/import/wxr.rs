@@ -0,0 +1,433 @@
+//! WordPress 导出文件（WXR）
+//!
+//! WXR 是结构固定的 RSS 扩展，这里只按标签名读取需要的字段，不实现完整的 XML 解析。
+//! 附件文件不在 XML 中，需要把 `wp-content/uploads` 目录放在 XML 文件旁边（`uploads/` 或 `wp-content/uploads/`）
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::{contained_file, link_path, Asset, CategoryNode, ImportPlan, ImportedComment, ImportedPost, SourceKind};
+use crate::front_matter::parse_datetime;
+
+/// 上传文件在 WordPress 站点中的路径
+const UPLOADS_PATH: &str = "/wp-content/uploads/";
+
+/// WordPress 的默认分类
+const UNCATEGORIZED: &str = "uncategorized";
+
+pub(super) fn scan(path: &Path) -> Result<ImportPlan, String> {
+    let xml = fs::read_to_string(path).map_err(|e| format!("读取导出文件失败: {}", e))?;
+    if !xml.contains("<rss") || !xml.contains("wordpress.org/export/") {
+        return Err(format!("{} 不是 WordPress 导出文件（WXR）", path.display()));
+    }
+    let base = path.parent().unwrap_or(Path::new("."));
+    Ok(parse(&xml, base))
+}
+
+/// 文章及其关联附件所需的 ID
+struct Item {
+    post_id: String,
+    thumbnail_id: Option<String>,
+    post: ImportedPost,
+}
+
+fn parse(xml: &str, base: &Path) -> ImportPlan {
+    let mut plan = ImportPlan::new(SourceKind::WordPress);
+
+    // 分类层级：WXR 中的父分类用 nicename 表示
+    let mut names: HashMap<String, String> = HashMap::new();
+    let mut parents: HashMap<String, String> = HashMap::new();
+    for (_, category) in elements(xml, "wp:category") {
+        let (Some(nicename), Some(name)) = (text(category, "wp:category_nicename"), term_name(category, "wp:cat_name")) else {
+            continue;
+        };
+        if let Some(parent) = text(category, "wp:category_parent").filter(|p| !p.is_empty()) {
+            parents.insert(nicename.clone(), parent);
+        }
+        names.insert(nicename, name);
+    }
+    let mut nicenames: Vec<&String> = names.keys().filter(|n| n.as_str() != UNCATEGORIZED).collect();
+    nicenames.sort_by_key(|n| (depth(&parents, n), names[*n].clone()));
+    for nicename in nicenames {
+        let parent = parents.get(nicename).and_then(|p| names.get(p)).cloned();
+        plan.categories.push(CategoryNode { name: names[nicename].clone(), parent });
+    }
+
+    let mut items: Vec<Item> = Vec::new();
+    let mut attachments: BTreeMap<String, (String, String)> = BTreeMap::new();
+    for (_, item) in elements(xml, "item") {
+        let post_id = text(item, "wp:post_id").unwrap_or_default();
+        match text(item, "wp:post_type").as_deref() {
+            Some("post") => {}
+            Some("attachment") => {
+                if let Some(url) = text(item, "wp:attachment_url") {
+                    attachments.insert(post_id, (url, text(item, "wp:post_parent").unwrap_or_default()));
+                }
+                continue;
+            }
+            _ => {
+                plan.skipped += 1;
+                continue;
+            }
+        }
+
+        let status = text(item, "wp:status").unwrap_or_default();
+        if matches!(status.as_str(), "trash" | "auto-draft" | "inherit") {
+            plan.skipped += 1;
+            continue;
+        }
+
+        let mut category = None;
+        let mut tags: Vec<String> = Vec::new();
+        for (attrs, value) in elements(item, "category") {
+            let value = unescape(&decode(value));
+            match attr(attrs, "domain").as_deref() {
+                Some("category") if category.is_none() && attr(attrs, "nicename").as_deref() != Some(UNCATEGORIZED) => {
+                    category = Some(value)
+                }
+                Some("post_tag") if !tags.contains(&value) => tags.push(value),
+                _ => {}
+            }
+        }
+
+        let thumbnail_id = elements(item, "wp:postmeta")
+            .into_iter()
+            .find(|(_, meta)| text(meta, "wp:meta_key").as_deref() == Some("_thumbnail_id"))
+            .and_then(|(_, meta)| text(meta, "wp:meta_value"));
+
+        let slug = text(item, "wp:post_name")
+            .map(|s| urlencoding::decode(&s).map(|s| s.into_owned()).unwrap_or(s))
+            .filter(|s| !s.is_empty());
+        let title = text(item, "title").filter(|t| !t.trim().is_empty()).unwrap_or_else(|| "未命名文章".to_string());
+
+        let post = ImportedPost {
+            source: text(item, "link").unwrap_or_else(|| format!("post #{}", post_id)),
+            title,
+            content: clean_content(&text(item, "content:encoded").unwrap_or_default()),
+            summary: text(item, "excerpt:encoded").map(|s| s.trim().to_string()).filter(|s| !s.is_empty()),
+            slug,
+            category,
+            tags,
+            author: text(item, "dc:creator"),
+            created_at: date(item, "wp:post_date_gmt").or_else(|| date(item, "wp:post_date")),
+            updated_at: date(item, "wp:post_modified_gmt"),
+            draft: matches!(status.as_str(), "draft" | "pending"),
+            private: status == "private",
+            password: text(item, "wp:post_password").filter(|p| !p.is_empty()),
+            pinned: text(item, "wp:is_sticky").as_deref() == Some("1"),
+            comments: comments(item),
+            ..Default::default()
+        };
+        items.push(Item { post_id, thumbnail_id, post });
+    }
+
+    for mut item in items {
+        // 上传到文章的附件即使正文没有引用也一并导入
+        let mut urls = super::link_targets(&item.post.content);
+        for (id, (url, parent)) in &attachments {
+            if *parent == item.post_id || item.thumbnail_id.as_ref() == Some(id) {
+                urls.push(url.clone());
+            }
+        }
+        item.post.cover_image = item.thumbnail_id.as_ref().and_then(|id| attachments.get(id)).map(|(url, _)| url.clone());
+
+        for url in urls {
+            if !url.contains(UPLOADS_PATH) || item.post.assets.iter().any(|a| a.url == url) {
+                continue;
+            }
+            let file = resolve_upload(base, &url);
+            item.post.assets.push(Asset { url, file });
+        }
+        plan.posts.push(item.post);
+    }
+
+    if plan.posts.is_empty() {
+        plan.warnings.push("没有找到文章".to_string());
+    }
+    plan
+}
+
+/// 分类的层级深度，用于让父分类排在子分类之前
+fn depth(parents: &HashMap<String, String>, nicename: &str) -> usize {
+    let mut depth = 0;
+    let mut current = nicename;
+    while let Some(parent) = parents.get(current) {
+        depth += 1;
+        current = parent;
+        if depth > 64 {
+            break;
+        }
+    }
+    depth
+}
+
+/// 已审核的评论（不含 pingback、trackback）
+fn comments(item: &str) -> Vec<ImportedComment> {
+    elements(item, "wp:comment")
+        .into_iter()
+        .filter(|(_, c)| text(c, "wp:comment_approved").as_deref() == Some("1"))
+        .filter(|(_, c)| matches!(text(c, "wp:comment_type").as_deref(), None | Some("") | Some("comment")))
+        .filter_map(|(_, c)| {
+            let content = text(c, "wp:comment_content").filter(|s| !s.trim().is_empty())?;
+            Some(ImportedComment {
+                author: text(c, "wp:comment_author").filter(|a| !a.trim().is_empty()).unwrap_or_else(|| "匿名".to_string()),
+                content,
+                created_at: date(c, "wp:comment_date_gmt").or_else(|| date(c, "wp:comment_date")),
+            })
+        })
+        .collect()
+}
+
+/// WordPress 的正文是 HTML：去掉指向原站缩略图的 srcset/sizes 和 caption 短代码
+fn clean_content(content: &str) -> String {
+    static RESPONSIVE_ATTRS: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\s(?:srcset|sizes)="[^"]*""#).unwrap());
+    static CAPTION: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[/?caption[^\]]*\]").unwrap());
+
+    let content = RESPONSIVE_ATTRS.replace_all(content, "");
+    CAPTION.replace_all(&content, "").trim().to_string()
+}
+
+/// 在 XML 文件旁边的 uploads 目录中查找附件
+fn resolve_upload(base: &Path, url: &str) -> Option<PathBuf> {
+    let path = link_path(url);
+    let relative = &path[path.find(UPLOADS_PATH)? + UPLOADS_PATH.len()..];
+    [base.join("wp-content/uploads"), base.join("uploads"), base.to_path_buf()]
+        .iter()
+        .find_map(|dir| contained_file(base, &dir.join(relative)))
+}
+
+/// WordPress 用 `0000-00-00 00:00:00` 表示未设置的时间
+fn date(xml: &str, tag: &str) -> Option<DateTime<Utc>> {
+    text(xml, tag).filter(|d| !d.starts_with("0000")).and_then(|d| parse_datetime(&d))
+}
+
+/// 从 `from` 开始查找 `pattern`，跳过 CDATA 中的内容
+fn find_outside_cdata(xml: &str, pattern: &str, from: usize) -> Option<usize> {
+    let mut pos = from;
+    loop {
+        let found = pos + xml[pos..].find(pattern)?;
+        match xml[pos..].find("<![CDATA[").map(|i| pos + i) {
+            Some(cdata) if cdata < found => pos = cdata + xml[cdata..].find("]]>")? + 3,
+            _ => return Some(found),
+        }
+    }
+}
+
+/// 依次找出 `<tag …>…</tag>` 元素，返回（属性文本，原始内容）；同名元素不能嵌套
+fn elements<'a>(xml: &'a str, tag: &str) -> Vec<(&'a str, &'a str)> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+    let mut found = Vec::new();
+    let mut pos = 0;
+
+    while let Some(start) = find_outside_cdata(xml, &open, pos) {
+        let after = start + open.len();
+        // 排除名称更长的标签，例如查找 wp:category 时遇到 wp:category_parent
+        if !xml[after..].starts_with(|c: char| c == '>' || c == '/' || c.is_whitespace()) {
+            pos = after;
+            continue;
+        }
+        let Some(tag_end) = xml[after..].find('>').map(|i| after + i) else { break };
+        let attrs = &xml[after..tag_end];
+        if let Some(attrs) = attrs.strip_suffix('/') {
+            found.push((attrs.trim(), ""));
+            pos = tag_end + 1;
+            continue;
+        }
+        let Some(end) = find_outside_cdata(xml, &close, tag_end + 1) else { break };
+        found.push((attrs.trim(), &xml[tag_end + 1..end]));
+        pos = end + close.len();
+    }
+    found
+}
+
+/// 第一个 `tag` 元素解码后的文本
+fn text(xml: &str, tag: &str) -> Option<String> {
+    elements(xml, tag).first().map(|(_, inner)| decode(inner))
+}
+
+/// 分类、标签名：WordPress 保存的是转义后的 HTML，CDATA 中仍有 `&amp;` 等实体
+fn term_name(xml: &str, tag: &str) -> Option<String> {
+    text(xml, tag).map(|name| unescape(&name))
+}
+
+fn attr(attrs: &str, name: &str) -> Option<String> {
+    static ATTR_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"([\w:-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap());
+    ATTR_RE
+        .captures_iter(attrs)
+        .find(|caps| &caps[1] == name)
+        .map(|caps| unescape(caps.get(2).or_else(|| caps.get(3)).map_or("", |m| m.as_str())))
+}
+
+/// 元素内容：CDATA 原样保留，其余部分解码实体（WordPress 会把内容中的 `]]>` 拆成两段 CDATA）
+fn decode(inner: &str) -> String {
+    let mut text = String::new();
+    let mut rest = inner;
+    while let Some(start) = rest.find("<![CDATA[") {
+        text.push_str(&unescape(&rest[..start]));
+        let data = &rest[start + 9..];
+        match data.find("]]>") {
+            Some(end) => {
+                text.push_str(&data[..end]);
+                rest = &data[end + 3..];
+            }
+            None => {
+                text.push_str(data);
+                rest = "";
+            }
+        }
+    }
+    text.push_str(&unescape(rest));
+    text.trim().to_string()
+}
+
+/// 解码 XML 实体和数字字符引用
+fn unescape(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semi) = rest.find(';').filter(|&i| i <= 10) else {
+            out.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+        let entity = &rest[1..semi];
+        let decoded = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WXR: &str = r#"<?xml version="1.0" encoding="UTF-8" ?>
+<rss version="2.0" xmlns:wp="http://wordpress.org/export/1.2/">
+<channel>
+  <title>示例站点</title>
+  <wp:category><wp:term_id>2</wp:term_id><wp:category_nicename>rust</wp:category_nicename><wp:category_parent>code</wp:category_parent><wp:cat_name><![CDATA[Rust]]></wp:cat_name></wp:category>
+  <wp:category><wp:term_id>1</wp:term_id><wp:category_nicename>code</wp:category_nicename><wp:category_parent></wp:category_parent><wp:cat_name><![CDATA[编程 &amp; 开发]]></wp:cat_name></wp:category>
+  <item>
+    <title>Hello &amp; welcome</title>
+    <link>https://example.com/2020/01/hello/</link>
+    <dc:creator><![CDATA[admin]]></dc:creator>
+    <content:encoded><![CDATA[<p>正文 <img src="https://example.com/wp-content/uploads/2020/01/cat.png" srcset="a 300w" /></p>
+[caption id="x"]说明[/caption] 代码中的 </item> 不影响解析 ]]]]><![CDATA[>]]></content:encoded>
+    <excerpt:encoded><![CDATA[]]></excerpt:encoded>
+    <wp:post_id>10</wp:post_id>
+    <wp:post_date><![CDATA[2020-01-02 11:00:00]]></wp:post_date>
+    <wp:post_date_gmt><![CDATA[2020-01-02 03:00:00]]></wp:post_date_gmt>
+    <wp:post_name><![CDATA[%e4%bd%a0%e5%a5%bd]]></wp:post_name>
+    <wp:status><![CDATA[publish]]></wp:status>
+    <wp:post_parent>0</wp:post_parent>
+    <wp:post_type><![CDATA[post]]></wp:post_type>
+    <wp:post_password><![CDATA[secret]]></wp:post_password>
+    <wp:is_sticky>1</wp:is_sticky>
+    <category domain="category" nicename="rust"><![CDATA[Rust]]></category>
+    <category domain="post_tag" nicename="web"><![CDATA[web]]></category>
+    <wp:postmeta><wp:meta_key><![CDATA[_thumbnail_id]]></wp:meta_key><wp:meta_value><![CDATA[11]]></wp:meta_value></wp:postmeta>
+    <wp:comment>
+      <wp:comment_author><![CDATA[Bob]]></wp:comment_author>
+      <wp:comment_date_gmt><![CDATA[2020-01-03 00:00:00]]></wp:comment_date_gmt>
+      <wp:comment_content><![CDATA[不错]]></wp:comment_content>
+      <wp:comment_approved><![CDATA[1]]></wp:comment_approved>
+      <wp:comment_type><![CDATA[comment]]></wp:comment_type>
+    </wp:comment>
+    <wp:comment>
+      <wp:comment_author><![CDATA[spammer]]></wp:comment_author>
+      <wp:comment_content><![CDATA[buy]]></wp:comment_content>
+      <wp:comment_approved><![CDATA[spam]]></wp:comment_approved>
+    </wp:comment>
+  </item>
+  <item>
+    <title>cover.jpg</title>
+    <wp:post_id>11</wp:post_id>
+    <wp:post_parent>10</wp:post_parent>
+    <wp:post_type><![CDATA[attachment]]></wp:post_type>
+    <wp:attachment_url><![CDATA[https://example.com/wp-content/uploads/2020/01/cover.jpg]]></wp:attachment_url>
+  </item>
+  <item>
+    <title>关于</title>
+    <wp:post_type><![CDATA[page]]></wp:post_type>
+  </item>
+</channel>
+</rss>"#;
+
+    #[test]
+    fn test_parse_wxr() {
+        let base = std::env::temp_dir().join(format!("rustblog-wxr-{}", crate::id_generator::generate_unique_id()));
+        fs::create_dir_all(base.join("uploads/2020/01")).unwrap();
+        fs::write(base.join("uploads/2020/01/cat.png"), "png").unwrap();
+        let plan = parse(WXR, &base);
+        fs::remove_dir_all(&base).unwrap();
+
+        assert_eq!(plan.skipped, 1);
+        assert_eq!(
+            plan.categories,
+            vec![
+                CategoryNode { name: "编程 & 开发".to_string(), parent: None },
+                CategoryNode { name: "Rust".to_string(), parent: Some("编程 & 开发".to_string()) },
+            ]
+        );
+
+        assert_eq!(plan.posts.len(), 1);
+        let post = &plan.posts[0];
+        assert_eq!(post.title, "Hello & welcome");
+        assert_eq!(post.slug.as_deref(), Some("你好"));
+        assert_eq!(post.category.as_deref(), Some("Rust"));
+        assert_eq!(post.tags, vec!["web"]);
+        assert_eq!(post.password.as_deref(), Some("secret"));
+        assert!(post.pinned && !post.draft && post.summary.is_none());
+        assert_eq!(post.created_at.unwrap().to_rfc3339(), "2020-01-02T03:00:00+00:00");
+        assert!(post.content.ends_with("说明 代码中的 </item> 不影响解析 ]]>"));
+        assert!(!post.content.contains("srcset"));
+
+        assert_eq!(post.comments.len(), 1);
+        assert_eq!(post.comments[0].author, "Bob");
+
+        assert_eq!(post.cover_image.as_deref(), Some("https://example.com/wp-content/uploads/2020/01/cover.jpg"));
+        assert_eq!(post.assets.len(), 2);
+        assert!(post.assets[0].file.is_some());
+        assert_eq!(post.assets[1].file, None);
+    }
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!(unescape("a &lt;b&gt; &#20320;&#x597D; & c &unknown;"), "a <b> 你好 & c &unknown;");
+    }
+}
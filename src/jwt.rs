@@ -4,6 +4,7 @@ use chrono::{Duration, Utc};
 use rand::Rng;
 use std::fs;
 use std::path::Path;
+use std::sync::{Arc, RwLock};
 
 /// JWT Claims
 #[derive(Debug, Serialize, Deserialize)]
@@ -17,6 +18,31 @@ pub struct Claims {
     pub iss: String,
 }
 
+/// 文章预览令牌 Claims
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PreviewClaims {
+    pub sub: String,  // 文章 UUID
+    pub jti: String,  // 令牌 ID，对应 preview_links.token_id
+    pub exp: i64,
+    pub iat: i64,
+    pub iss: String,
+}
+
+/// 预览令牌的签发者，与登录令牌区分
+const PREVIEW_ISSUER: &str = "rustblog-preview";
+
+/// 加密文章解锁令牌 Claims
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UnlockClaims {
+    pub sub: String,  // 文章 UUID
+    pub exp: i64,
+    pub iat: i64,
+    pub iss: String,
+}
+
+/// 解锁令牌的签发者，与登录令牌、预览令牌区分
+const UNLOCK_ISSUER: &str = "rustblog-unlock";
+
 /// JWT 错误类型
 #[derive(Debug)]
 pub enum JwtError {
@@ -94,6 +120,77 @@ impl JwtService {
 
         Ok(claims)
     }
+
+    /// 生成文章预览令牌
+    pub fn generate_preview_token(&self, passage_uuid: &str, token_id: &str, expires_at: chrono::DateTime<Utc>) -> Result<String, JwtError> {
+        let claims = PreviewClaims {
+            sub: passage_uuid.to_string(),
+            jti: token_id.to_string(),
+            exp: expires_at.timestamp(),
+            iat: Utc::now().timestamp(),
+            iss: PREVIEW_ISSUER.to_string(),
+        };
+
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(self.secret.as_ref()),
+        )
+        .map_err(|e| JwtError::EncodingError(e.to_string()))
+    }
+
+    /// 验证文章预览令牌（登录令牌的签发者不同，不能用作预览令牌）
+    pub fn validate_preview_token(&self, token: &str) -> Result<PreviewClaims, JwtError> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.set_issuer(&[PREVIEW_ISSUER]);
+
+        let token_data = decode::<PreviewClaims>(
+            token,
+            &DecodingKey::from_secret(self.secret.as_ref()),
+            &validation,
+        )
+        .map_err(|e| match e.kind() {
+            jsonwebtoken::errors::ErrorKind::ExpiredSignature => JwtError::ExpiredToken,
+            _ => JwtError::DecodingError(e.to_string()),
+        })?;
+
+        Ok(token_data.claims)
+    }
+
+    /// 生成加密文章解锁令牌
+    pub fn generate_unlock_token(&self, passage_uuid: &str, expires_at: chrono::DateTime<Utc>) -> Result<String, JwtError> {
+        let claims = UnlockClaims {
+            sub: passage_uuid.to_string(),
+            exp: expires_at.timestamp(),
+            iat: Utc::now().timestamp(),
+            iss: UNLOCK_ISSUER.to_string(),
+        };
+
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(self.secret.as_ref()),
+        )
+        .map_err(|e| JwtError::EncodingError(e.to_string()))
+    }
+
+    /// 验证加密文章解锁令牌
+    pub fn validate_unlock_token(&self, token: &str) -> Result<UnlockClaims, JwtError> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.set_issuer(&[UNLOCK_ISSUER]);
+
+        let token_data = decode::<UnlockClaims>(
+            token,
+            &DecodingKey::from_secret(self.secret.as_ref()),
+            &validation,
+        )
+        .map_err(|e| match e.kind() {
+            jsonwebtoken::errors::ErrorKind::ExpiredSignature => JwtError::ExpiredToken,
+            _ => JwtError::DecodingError(e.to_string()),
+        })?;
+
+        Ok(token_data.claims)
+    }
 }
 
 /// 生成32位随机密钥
@@ -154,20 +251,37 @@ pub fn init_jwt_secret(base_dir: &Path, jwt_secret: Option<&str>) -> String {
     new_secret
 }
 
-/// 全局 JWT 服务实例（使用 once_cell 延迟初始化）
-use once_cell::sync::OnceCell;
-
-static JWT_SERVICE: OnceCell<JwtService> = OnceCell::new();
+/// 全局 JWT 服务实例，恢复备份后可以用新的密钥重新加载
+static JWT_SERVICE: RwLock<Option<Arc<JwtService>>> = RwLock::new(None);
 
 /// 初始化全局 JWT 服务
 pub fn init_jwt_service(secret: &str) {
-    let service = JwtService::new(secret);
-    JWT_SERVICE.set(service).expect("JWT service already initialized");
+    let mut service = JWT_SERVICE.write().unwrap();
+    assert!(service.is_none(), "JWT service already initialized");
+    *service = Some(Arc::new(JwtService::new(secret)));
+}
+
+/// 从密钥文件重新加载全局 JWT 服务（恢复备份后调用），之前签发的令牌全部失效
+pub fn reload_jwt_service(jwt_secret_file: &Path) -> Result<(), String> {
+    let secret = fs::read_to_string(jwt_secret_file)
+        .map_err(|e| format!("读取JWT密钥文件失败: {}", e))?;
+    let secret = secret.trim();
+    if secret.is_empty() {
+        return Err("JWT密钥文件为空".to_string());
+    }
+    *JWT_SERVICE.write().unwrap() = Some(Arc::new(JwtService::new(secret)));
+    Ok(())
 }
 
 /// 获取全局 JWT 服务
-pub fn get_jwt_service() -> &'static JwtService {
-    JWT_SERVICE.get().expect("JWT service not initialized")
+pub fn get_jwt_service() -> Arc<JwtService> {
+    JWT_SERVICE.read().unwrap().clone().expect("JWT service not initialized")
+}
+
+/// 测试中使用固定密钥初始化全局 JWT 服务（可重复调用）
+#[cfg(test)]
+pub fn init_test_jwt_service() {
+    JWT_SERVICE.write().unwrap().get_or_insert_with(|| Arc::new(JwtService::new("test-secret")));
 }
 
 /// 生成 token（使用全局服务）
@@ -178,4 +292,24 @@ pub fn generate_token(user_id: i64, username: &str, role: &str) -> Result<String
 /// 验证 token（使用全局服务）
 pub fn validate_token(token: &str) -> Result<Claims, JwtError> {
     get_jwt_service().validate_token(token)
-}
\ No newline at end of file
+}
+
+/// 生成文章预览令牌（使用全局服务）
+pub fn generate_preview_token(passage_uuid: &str, token_id: &str, expires_at: chrono::DateTime<Utc>) -> Result<String, JwtError> {
+    get_jwt_service().generate_preview_token(passage_uuid, token_id, expires_at)
+}
+
+/// 验证文章预览令牌（使用全局服务）
+pub fn validate_preview_token(token: &str) -> Result<PreviewClaims, JwtError> {
+    get_jwt_service().validate_preview_token(token)
+}
+
+/// 生成加密文章解锁令牌（使用全局服务）
+pub fn generate_unlock_token(passage_uuid: &str, expires_at: chrono::DateTime<Utc>) -> Result<String, JwtError> {
+    get_jwt_service().generate_unlock_token(passage_uuid, expires_at)
+}
+
+/// 验证加密文章解锁令牌（使用全局服务）
+pub fn validate_unlock_token(token: &str) -> Result<UnlockClaims, JwtError> {
+    get_jwt_service().validate_unlock_token(token)
+}
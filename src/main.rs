@@ -11,15 +11,28 @@ mod geoip;
 mod embedded;
 mod cache;
 mod view_batch;
+mod scheduler;
 mod jwt;
 mod id_generator;
+mod front_matter;
+mod slug;
+mod feed;
+mod sitemap;
+mod markdown;
+mod highlight;
+mod sanitize;
+mod reading;
+mod import;
+mod export;
+mod backup;
+mod watcher;
 
 #[cfg(not(feature = "no_std"))]
 use actix_web::{App, HttpServer, middleware as actix_middleware, web};
 #[cfg(not(feature = "no_std"))]
 use clap::Parser;
 #[cfg(not(feature = "no_std"))]
-use config::{AppConfig, CliArgs};
+use config::{AppConfig, CliArgs, Command};
 #[cfg(not(feature = "no_std"))]
 use routes::configure_routes;
 #[cfg(not(feature = "no_std"))]
@@ -125,6 +138,11 @@ async fn main() -> std::io::Result<()> {
     // 解析路径
     args.resolve_paths();
 
+    // 执行维护命令后直接退出，不启动服务器
+    if let Some(command) = args.command.clone() {
+        return run_command(&command, &args).await;
+    }
+
     // 从命令行参数创建配置
     let config = AppConfig::from_cli(args.clone());
 
@@ -215,6 +233,38 @@ async fn main() -> std::io::Result<()> {
         }
     }
     
+    // 启动定时发布调度器（在文章同步之后，避免同步覆盖刚变更的状态）
+    println!("⏰ 启动定时发布调度器...");
+    let _publish_scheduler = scheduler::PublishScheduler::new(
+        repository.get_pool().clone(),
+        app_cache.clone(),
+        scheduler::SchedulerConfig::default(),
+    );
+
+    // 启动相关文章后台计算（文章变更后自动重算）
+    println!("🔗 启动相关文章计算任务...");
+    let _related_worker = db::related::spawn_worker(repository.get_pool().clone());
+
+    // 启动文件变化监听（markdown、music 目录变化后自动同步）
+    let file_watcher = if args.disable_file_watch {
+        None
+    } else {
+        watcher::FileWatcher::new(repository.clone(), app_cache.clone(), watcher::WatcherConfig::default())
+    };
+    if file_watcher.is_some() {
+        println!("👀 启动文件变化监听: markdown、music 目录");
+    }
+
+    // 启动定时备份（未配置间隔时不启用）
+    let backup_config = std::sync::Arc::new(backup::BackupConfig::from_cli(&args));
+    let backup_scheduler = backup::BackupScheduler::new(repository.get_pool().clone(), backup_config.clone());
+    if backup_scheduler.is_some() {
+        println!(
+            "💾 启动定时备份: 每 {} 小时一次，保留 {} 份",
+            backup_config.interval_hours, backup_config.keep
+        );
+    }
+    
     // 启动 HTTP/1.1/HTTP/2 服务器
     HttpServer::new(move || {
         App::new()
@@ -224,6 +274,8 @@ async fn main() -> std::io::Result<()> {
             .app_data(web::Data::new(app_cache.clone()))
             // 注入阅读记录批量处理器
             .app_data(web::Data::new(view_batch_processor.clone()))
+            // 注入备份配置
+            .app_data(web::Data::new(backup_config.clone()))
             // 配置所有路由
             .configure(configure_routes)
             // 添加中间件
@@ -237,6 +289,105 @@ async fn main() -> std::io::Result<()> {
     .await
 }
 
+/// 执行维护命令
+async fn run_command(command: &Command, args: &CliArgs) -> std::io::Result<()> {
+    let to_io_error = |e: Box<dyn std::error::Error>| std::io::Error::other(e.to_string());
+
+    db::init_db(&args.db_path).map_err(to_io_error)?;
+    let pool = db::get_db_pool_sync().map_err(std::io::Error::other)?;
+    let conn = pool.get().map_err(std::io::Error::other)?;
+
+    match command {
+        Command::Sanitize => {
+            println!("🧹 重新清理已有内容...");
+            let report = db::maintenance::resanitize(&conn).map_err(to_io_error)?;
+            println!(
+                "✅ 清理完成: 评论 {} 条，文章 {} 篇，自定义 CSS {} 处",
+                report.comments, report.passages, report.styles
+            );
+        }
+        Command::Import { path, apply } => {
+            println!("📥 读取导入源: {}", path.display());
+            let plan = import::scan(path).map_err(std::io::Error::other)?;
+            let passage_repo = db::repositories::PassageRepository::new(std::sync::Arc::new(pool.clone()));
+            let report = import::run(&plan, &passage_repo, "system", !apply).await.map_err(std::io::Error::other)?;
+            print_import_report(&report);
+        }
+        Command::ExportStatic { out, base_url } => {
+            println!("📤 导出静态站点到: {}", out.display());
+            let repository = db::repositories::create_repository(pool.clone());
+            let report = export::run(repository, out, base_url).await.map_err(std::io::Error::other)?;
+            for failed in &report.failed {
+                println!("⚠️  导出失败: {}", failed);
+            }
+            println!(
+                "✅ 导出完成: 页面 {} 个，接口快照 {} 个，静态资源 {} 个",
+                report.pages, report.snapshots, report.assets
+            );
+        }
+        Command::Backup => {
+            let config = backup::BackupConfig::from_cli(args);
+            println!("💾 创建备份...");
+            let info = backup::create(&pool, &config, backup::BackupKind::Manual).map_err(std::io::Error::other)?;
+            println!("✅ 备份完成: {} ({} 字节)", config.dir.join(&info.name).display(), info.size);
+        }
+        Command::Restore { path } => {
+            let config = backup::BackupConfig::from_cli(args);
+            println!("♻️  从备份恢复: {}", path.display());
+            let report = backup::restore(&pool, &config, path).map_err(std::io::Error::other)?;
+            print_restore_report(&report);
+        }
+    }
+    Ok(())
+}
+
+/// 输出恢复结果
+fn print_restore_report(report: &backup::RestoreReport) {
+    println!("💾 恢复前的数据已备份为: {}", report.safety_backup);
+    println!(
+        "✅ 已恢复 {} 的备份: 文件 {} 个，目录 {}",
+        report.created_at.format("%Y-%m-%d %H:%M:%S UTC"),
+        report.files,
+        report.directories.join(", ")
+    );
+    if report.jwt_secret {
+        println!("ℹ️  JWT 密钥已恢复，重启服务后生效");
+    }
+}
+
+/// 输出导入报告
+fn print_import_report(report: &import::ImportReport) {
+    for passage in &report.passages {
+        let mark = if passage.action == "skip" { "⏭️ " } else { "📝" };
+        println!(
+            "{} {} ({}) [{} / {}] 分类: {} 评论: {} 附件: {}",
+            mark, passage.title, passage.slug, passage.status, passage.visibility,
+            passage.category, passage.comments, passage.attachments
+        );
+        for url in &passage.missing_attachments {
+            println!("   ⚠️  找不到附件: {}", url);
+        }
+    }
+    for warning in &report.warnings {
+        println!("⚠️  {}", warning);
+    }
+    if !report.new_categories.is_empty() {
+        println!("📁 新分类: {}", report.new_categories.join(", "));
+    }
+    if !report.new_tags.is_empty() {
+        println!("🏷️  新标签: {}", report.new_tags.join(", "));
+    }
+
+    let verb = if report.dry_run { "将导入" } else { "已导入" };
+    println!(
+        "✅ {}: 文章 {} 篇，评论 {} 条，附件 {} 个；跳过 {} 项",
+        verb, report.created, report.comments, report.attachments, report.skipped
+    );
+    if report.dry_run {
+        println!("ℹ️  以上为预览，确认无误后加上 --apply 执行导入");
+    }
+}
+
 /// 创建必要的目录
 #[cfg(not(feature = "no_std"))]
 fn create_directories(base_dir: &Path) {
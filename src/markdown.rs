@@ -0,0 +1,320 @@
+//! Markdown 渲染模块
+//!
+//! 文章、编辑器、同步和评论统一使用这里的渲染函数，按用途选择渲染配置
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use pulldown_cmark::{html, CodeBlockKind, CowStr, Event, Options, Parser, Tag, TagEnd};
+use regex::Regex;
+use serde::Serialize;
+
+use crate::cache::LruCache;
+use crate::highlight::{self, FenceInfo};
+
+/// 自动摘要的最大字符数
+const SUMMARY_MAX_CHARS: usize = 200;
+
+/// 渲染缓存的最大条目数
+const CACHE_CAPACITY: usize = 1000;
+
+/// 渲染配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Profile {
+    /// 文章：支持脚注、标题属性、智能标点和数学公式，保留原始 HTML
+    Full,
+    /// 评论：只支持基础语法，原始 HTML 按文本输出，结果再经过允许列表清理
+    Comment,
+}
+
+impl Profile {
+    fn options(self) -> Options {
+        let mut options = Options::empty();
+        options.insert(Options::ENABLE_TABLES);
+        options.insert(Options::ENABLE_STRIKETHROUGH);
+        options.insert(Options::ENABLE_TASKLISTS);
+        if self == Profile::Full {
+            options.insert(Options::ENABLE_FOOTNOTES);
+            options.insert(Options::ENABLE_HEADING_ATTRIBUTES);
+            options.insert(Options::ENABLE_SMART_PUNCTUATION);
+            options.insert(Options::ENABLE_MATH);
+        }
+        options
+    }
+}
+
+/// 渲染缓存：(配置, 内容哈希) -> HTML
+static RENDER_CACHE: Lazy<Mutex<LruCache<(Profile, String), String>>> =
+    Lazy::new(|| Mutex::new(LruCache::new(CACHE_CAPACITY)));
+
+/// 将 Markdown 渲染为 HTML（带缓存）
+pub fn render(markdown: &str, profile: Profile) -> String {
+    use md5::{Digest, Md5};
+
+    let key = (profile, format!("{:x}", Md5::digest(markdown.as_bytes())));
+    if let Some(cached) = RENDER_CACHE.lock().unwrap().get(&key) {
+        return cached;
+    }
+
+    let html_output = render_uncached(markdown, profile);
+    RENDER_CACHE.lock().unwrap().put(key, html_output.clone());
+    html_output
+}
+
+/// 渲染文章正文，作者不可信时结果经过允许列表清理
+///
+/// 文章的创建、编辑、同步、修订恢复和重新渲染都通过这里渲染，
+/// `trusted` 由作者角色决定（见 `db::authors::is_trusted`）
+pub fn render_passage(markdown: &str, trusted: bool) -> String {
+    let html_output = render(markdown, Profile::Full);
+    if trusted {
+        html_output
+    } else {
+        crate::sanitize::clean(&html_output, crate::sanitize::Policy::Passage)
+    }
+}
+
+fn render_uncached(markdown: &str, profile: Profile) -> String {
+    let mut events: Vec<Event> = Parser::new_ext(markdown, profile.options()).collect();
+    if profile == Profile::Full {
+        assign_heading_ids(&mut events);
+        events = highlight_code_blocks(events);
+    }
+
+    let events = events.into_iter().map(|event| match event {
+        // 数学公式保留 $…$ / $$…$$ 定界符，交给页面中的 KaTeX 渲染
+        Event::InlineMath(math) => Event::InlineHtml(CowStr::from(format!(
+            "<span class=\"math math-inline\">${}$</span>",
+            escape_html(&math)
+        ))),
+        Event::DisplayMath(math) => Event::InlineHtml(CowStr::from(format!(
+            "<span class=\"math math-display\">$${}$$</span>",
+            escape_html(&math)
+        ))),
+        Event::Html(raw) | Event::InlineHtml(raw) if profile == Profile::Comment => Event::Text(raw),
+        other => other,
+    });
+
+    let mut html_output = String::new();
+    html::push_html(&mut html_output, events);
+    if profile == Profile::Comment {
+        // 链接地址等仍可能携带脚本协议
+        html_output = crate::sanitize::clean(&html_output, crate::sanitize::Policy::Comment);
+    }
+    html_output
+}
+
+/// 为没有指定 id 的标题生成锚点，重复的锚点依次追加 -1、-2…
+fn assign_heading_ids(events: &mut [Event]) {
+    // 手动指定的 id（{#custom}）优先保留，自动生成的 id 需要避开它们
+    let mut used: HashSet<String> = events
+        .iter()
+        .filter_map(|event| match event {
+            Event::Start(Tag::Heading { id: Some(id), .. }) => Some(id.to_string()),
+            _ => None,
+        })
+        .collect();
+
+    for start in 0..events.len() {
+        if !matches!(events[start], Event::Start(Tag::Heading { id: None, .. })) {
+            continue;
+        }
+
+        let mut text = String::new();
+        for event in &events[start + 1..] {
+            match event {
+                Event::End(TagEnd::Heading(_)) => break,
+                Event::Text(t) | Event::Code(t) | Event::InlineMath(t) => text.push_str(t),
+                _ => {}
+            }
+        }
+
+        let base = heading_id(&text);
+        let mut id = base.clone();
+        let mut suffix = 1;
+        while used.contains(&id) {
+            id = format!("{}-{}", base, suffix);
+            suffix += 1;
+        }
+        used.insert(id.clone());
+
+        if let Event::Start(Tag::Heading { id: heading_id, .. }) = &mut events[start] {
+            *heading_id = Some(CowStr::from(id));
+        }
+    }
+}
+
+/// 根据标题文本生成锚点：保留字母、数字和中日韩文字，空白和连字符合并为一个 -
+pub fn heading_id(text: &str) -> String {
+    let mut id = String::new();
+    for c in text.trim().chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() || c == '_' {
+            id.push(c);
+        } else if (c.is_whitespace() || c == '-') && !id.is_empty() && !id.ends_with('-') {
+            id.push('-');
+        }
+    }
+    let id = id.trim_end_matches('-');
+    if id.is_empty() {
+        "section".to_string()
+    } else {
+        id.to_string()
+    }
+}
+
+/// 目录条目
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TocEntry {
+    pub level: u8,
+    pub id: String,
+    pub title: String,
+    pub children: Vec<TocEntry>,
+}
+
+/// 从渲染后的 HTML 中提取带 id 的标题，按层级生成嵌套目录
+pub fn toc(html_content: &str) -> Vec<TocEntry> {
+    static HEADING_RE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"(?s)<h([1-6])\s[^>]*?\bid="([^"]+)"[^>]*>(.*?)</h[1-6]>"#).unwrap()
+    });
+
+    let mut headings = HEADING_RE
+        .captures_iter(html_content)
+        .map(|caps| TocEntry {
+            level: caps[1].parse().unwrap_or(1),
+            id: unescape_html(&caps[2]),
+            title: unescape_html(&plain_text(&caps[3])),
+            children: Vec::new(),
+        })
+        .peekable();
+
+    nest_toc(&mut headings, 0)
+}
+
+/// 依次读取标题，比 `parent_level` 更深的标题作为子项
+fn nest_toc<I: Iterator<Item = TocEntry>>(headings: &mut std::iter::Peekable<I>, parent_level: u8) -> Vec<TocEntry> {
+    let mut entries = Vec::new();
+    while let Some(mut entry) = headings.next_if(|heading| heading.level > parent_level) {
+        entry.children = nest_toc(headings, entry.level);
+        entries.push(entry);
+    }
+    entries
+}
+
+pub(crate) fn unescape_html(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// 将代码块替换为服务端高亮后的 HTML
+fn highlight_code_blocks(events: Vec<Event>) -> Vec<Event> {
+    let mut output = Vec::with_capacity(events.len());
+    let mut fence: Option<FenceInfo> = None;
+    let mut code = String::new();
+
+    for event in events {
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) => {
+                fence = Some(match kind {
+                    CodeBlockKind::Fenced(info) => FenceInfo::parse(&info),
+                    CodeBlockKind::Indented => FenceInfo::default(),
+                });
+                code.clear();
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some(fence) = fence.take() {
+                    output.push(Event::Html(CowStr::from(highlight::render_code_block(&code, &fence))));
+                }
+            }
+            Event::Text(text) if fence.is_some() => code.push_str(&text),
+            other => output.push(other),
+        }
+    }
+    output
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// 从渲染后的 HTML 中提取摘要
+///
+/// 正文包含摘要分隔符 `<!-- more -->` 时取分隔符之前的全部文本，否则取前 200 个字符
+pub fn extract_summary(html_content: &str) -> String {
+    static SEPARATOR_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"<!--\s*more\s*-->").unwrap());
+
+    if let Some(found) = SEPARATOR_RE.find(html_content) {
+        let excerpt = plain_text(&html_content[..found.start()]);
+        if !excerpt.is_empty() {
+            return excerpt;
+        }
+    }
+
+    let text = plain_text(html_content);
+    if text.chars().count() > SUMMARY_MAX_CHARS {
+        format!("{}...", text.chars().take(SUMMARY_MAX_CHARS).collect::<String>())
+    } else {
+        text
+    }
+}
+
+/// 移除 HTML 标签并合并空白
+fn plain_text(html_content: &str) -> String {
+    static TAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^>]*>").unwrap());
+
+    let text = TAG_RE.replace_all(html_content, "");
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_profile_extensions() {
+        let html = render("行内 $a<b$ 公式\n\n$$x^2$$\n\n脚注[^1]\n\n[^1]: 说明\n\n## 标题 {#custom}\n", Profile::Full);
+        assert!(html.contains("<span class=\"math math-inline\">$a&lt;b$</span>"));
+        assert!(html.contains("<span class=\"math math-display\">$$x^2$$</span>"));
+        assert!(html.contains("class=\"footnote-definition\""));
+        assert!(html.contains("<h2 id=\"custom\">"));
+    }
+
+    #[test]
+    fn test_heading_ids_and_toc() {
+        let html = render("# 快速开始\n\n## 安装 Rust\n\n## 安装 Rust\n\n### `cargo` & 工具\n\n## 自定义 {#install-rust}\n\n# ？！\n", Profile::Full);
+        assert!(html.contains("<h1 id=\"快速开始\">"));
+        assert!(html.contains("<h2 id=\"安装-rust\">"));
+        assert!(html.contains("<h2 id=\"安装-rust-1\">"));
+        assert!(html.contains("<h2 id=\"install-rust\">"));
+        assert!(html.contains("<h1 id=\"section\">"));
+
+        let toc = toc(&html);
+        assert_eq!(toc.len(), 2);
+        assert_eq!(toc[0].children.len(), 3);
+        assert_eq!(toc[0].children[1].children[0].title, "cargo & 工具");
+        assert_eq!(toc[0].children[1].children[0].id, "cargo-工具");
+    }
+
+    #[test]
+    fn test_comment_profile_escapes_html() {
+        let html = render("<script>alert(1)</script>\n\n**粗体**", Profile::Comment);
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("<strong>粗体</strong>"));
+
+        let html = render("[链接](javascript:alert(1)) [站外](https://example.com)", Profile::Comment);
+        assert!(!html.contains("javascript:"));
+        assert!(html.contains("<a href=\"https://example.com\" rel=\"nofollow ugc noopener\">"));
+    }
+
+    #[test]
+    fn test_extract_summary_honours_separator() {
+        let html = render("第一段\n\n第二段\n\n<!-- more -->\n\n正文", Profile::Full);
+        assert_eq!(extract_summary(&html), "第一段 第二段");
+
+        let long = "字".repeat(300);
+        assert_eq!(extract_summary(&long).chars().count(), SUMMARY_MAX_CHARS + 3);
+    }
+}
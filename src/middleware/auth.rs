@@ -74,6 +74,15 @@ pub fn check_admin_auth(req: &actix_web::HttpRequest) -> Option<(i64, String, St
     }
 }
 
+/// 获取当前登录用户（不限角色）
+/// 返回 (用户 ID, 用户名, 角色)，未登录或 token 无效时返回 None
+pub fn current_user(req: &actix_web::HttpRequest) -> Option<(i64, String, String)> {
+    let token = req.cookie("auth_token")?;
+    crate::jwt::validate_token(token.value())
+        .ok()
+        .map(|claims| (claims.user_id, claims.username, claims.role))
+}
+
 /// 返回权限被拒绝的响应
 pub fn forbidden_response() -> HttpResponse {
     HttpResponse::Forbidden().json(serde_json::json!({
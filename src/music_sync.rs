@@ -68,15 +68,8 @@ impl MusicSyncService {
                 continue;
             }
 
-            // 提取时间戳（格式：timestamp_filename.ext）
-            let timestamp = Self::extract_timestamp(&file_name);
-
             // 查找匹配的封面
-            let cover_image = if let Some(ts) = &timestamp {
-                covers_map.get(ts).map(|cover| format!("/music/covers/{}", cover))
-            } else {
-                None
-            }.unwrap_or_default();
+            let cover_image = Self::cover_for(&covers_map, &file_name);
 
             // 如果文件已存在于数据库中，更新封面信息
             if existing_files.contains(&file_name) {
@@ -94,37 +87,9 @@ impl MusicSyncService {
                 continue;
             }
 
-            // 提取元数据
-            let full_path = file_path.to_string_lossy().to_string();
-            let metadata = match extract_metadata(&full_path) {
-                Ok(m) => m,
-                Err(e) => {
-                    eprintln!("Warning: Failed to extract metadata for {}: {}", file_name, e);
-                    crate::audio_metadata::fallback_metadata(&file_name)
-                }
-            };
-
-            // 准备标题和艺术家
-            let title = metadata.title.unwrap_or_else(|| {
-                Self::clean_title(&file_name)
-            });
-            let artist = metadata.artist.unwrap_or_else(|| "未知艺术家".to_string());
-            let duration = "未知".to_string();
-
-            // 插入数据库
-            let track = crate::db::models::MusicTrack {
-                id: None,
-                title,
-                artist,
-                file_path: format!("/music/{}", file_name),
-                file_name: file_name.clone(),
-                duration,
-                cover_image,
-                created_at: chrono::Utc::now(),
-            };
-
-            match music_repo.create(&track).await {
-                Ok(_) => {
+            // 提取元数据并插入数据库
+            match self.insert_track(&music_repo, &file_path, &file_name, cover_image).await {
+                Ok(track) => {
                     synced_count += 1;
                     println!("Synced music file: {} - {}", track.title, track.artist);
                 }
@@ -145,6 +110,126 @@ impl MusicSyncService {
         })
     }
 
+    /// 同步单个音乐文件：新文件写入数据库，已有文件只更新封面
+    pub async fn sync_file(&self, file_name: &str) -> Result<(), String> {
+        let music_dir = "music";
+        let file_path = Path::new(music_dir).join(file_name);
+        if !file_path.is_file() || !Self::is_audio_file(file_name) {
+            return Ok(());
+        }
+
+        let covers_map = self.read_covers_map(&format!("{}/covers", music_dir)).await?;
+        let cover_image = Self::cover_for(&covers_map, file_name);
+
+        let music_repo = MusicTrackRepository::new(self.repo.get_pool().clone());
+        let existing_files = self.get_existing_files(&music_repo).await?;
+        if existing_files.contains(file_name) {
+            if !cover_image.is_empty() {
+                music_repo.update_cover_by_filename(file_name, &cover_image).await
+                    .map_err(|e| format!("Failed to update cover for {}: {}", file_name, e))?;
+            }
+            return Ok(());
+        }
+
+        let track = self.insert_track(&music_repo, &file_path, file_name, cover_image).await
+            .map_err(|e| format!("Failed to insert track {}: {}", file_name, e))?;
+        println!("Synced music file: {} - {}", track.title, track.artist);
+        Ok(())
+    }
+
+    /// 文件改名时更新记录，保留标题、艺术家和封面；旧文件没有记录时按新文件同步
+    pub async fn rename_file(&self, old_file_name: &str, new_file_name: &str) -> Result<(), String> {
+        if !Self::is_audio_file(new_file_name) {
+            return self.remove_file(old_file_name).await;
+        }
+        let music_repo = MusicTrackRepository::new(self.repo.get_pool().clone());
+        // 新文件名上已有的记录视为被覆盖
+        music_repo.delete_by_filename(new_file_name).await
+            .map_err(|e| format!("Failed to delete overwritten record {}: {}", new_file_name, e))?;
+        let updated = music_repo.rename_file(old_file_name, new_file_name).await
+            .map_err(|e| format!("Failed to rename record {}: {}", old_file_name, e))?;
+        if updated > 0 {
+            println!("Renamed music file: {} -> {}", old_file_name, new_file_name);
+        }
+        self.sync_file(new_file_name).await
+    }
+
+    /// 删除文件对应的记录
+    pub async fn remove_file(&self, file_name: &str) -> Result<(), String> {
+        let music_repo = MusicTrackRepository::new(self.repo.get_pool().clone());
+        let deleted = music_repo.delete_by_filename(file_name).await
+            .map_err(|e| format!("Failed to delete record {}: {}", file_name, e))?;
+        if deleted > 0 {
+            println!("Removed orphaned music record: {}", file_name);
+        }
+        Ok(())
+    }
+
+    /// 封面文件变化时重新匹配对应时间戳的音乐文件
+    pub async fn sync_cover(&self, cover_name: &str) -> Result<(), String> {
+        let Some(timestamp) = Self::extract_cover_timestamp(cover_name) else {
+            return Ok(());
+        };
+        let entries = fs::read_dir("music").map_err(|e| format!("Failed to read music directory: {}", e))?;
+        for entry in entries.flatten() {
+            let Ok(file_name) = entry.file_name().into_string() else {
+                continue;
+            };
+            if Self::extract_timestamp(&file_name).as_deref() == Some(timestamp.as_str()) {
+                self.sync_file(&file_name).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 提取元数据并插入一条新记录
+    async fn insert_track(
+        &self,
+        music_repo: &MusicTrackRepository,
+        file_path: &Path,
+        file_name: &str,
+        cover_image: String,
+    ) -> Result<crate::db::models::MusicTrack, Box<dyn std::error::Error>> {
+        // 提取元数据
+        let full_path = file_path.to_string_lossy().to_string();
+        let metadata = match extract_metadata(&full_path) {
+            Ok(m) => m,
+            Err(e) => {
+                eprintln!("Warning: Failed to extract metadata for {}: {}", file_name, e);
+                crate::audio_metadata::fallback_metadata(file_name)
+            }
+        };
+
+        // 准备标题和艺术家
+        let title = metadata.title.unwrap_or_else(|| {
+            Self::clean_title(file_name)
+        });
+        let artist = metadata.artist.unwrap_or_else(|| "未知艺术家".to_string());
+        let duration = "未知".to_string();
+
+        // 插入数据库
+        let track = crate::db::models::MusicTrack {
+            id: None,
+            title,
+            artist,
+            file_path: format!("/music/{}", file_name),
+            file_name: file_name.to_string(),
+            duration,
+            cover_image,
+            created_at: chrono::Utc::now(),
+        };
+        music_repo.create(&track).await?;
+        Ok(track)
+    }
+
+    /// 按文件名中的时间戳（格式：timestamp_filename.ext）查找匹配的封面
+    fn cover_for(covers_map: &HashMap<String, String>, file_name: &str) -> String {
+        Self::extract_timestamp(file_name)
+            .and_then(|ts| covers_map.get(&ts))
+            .map(|cover| format!("/music/covers/{}", cover))
+            .unwrap_or_default()
+    }
+
     /// 读取 covers 目录中的所有封面文件，建立时间戳到文件名的映射
     async fn read_covers_map(&self, covers_dir: &str) -> Result<HashMap<String, String>, String> {
         let mut covers_map = HashMap::new();
@@ -0,0 +1,106 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// 中日文每分钟阅读字数
+const CJK_CHARS_PER_MINUTE: f64 = 400.0;
+
+/// 拉丁文字每分钟阅读词数
+const WORDS_PER_MINUTE: f64 = 200.0;
+
+/// 代码每分钟阅读词数（代码读得比正文慢）
+const CODE_WORDS_PER_MINUTE: f64 = 100.0;
+
+/// 文章的字数和预计阅读时间
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReadingStats {
+    /// 正文字数：中日文按字计，其余按空白分隔的词计，不含代码块
+    pub word_count: i64,
+    /// 预计阅读分钟数，有内容时至少为 1
+    pub reading_minutes: i64,
+}
+
+/// 根据渲染后的 HTML 统计字数和阅读时间
+///
+/// 代码块单独统计，只计入阅读时间且按较慢的速度计算
+pub fn stats(html_content: &str) -> ReadingStats {
+    static CODE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<pre\b[^>]*>(.*?)</pre>").unwrap());
+    static TAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^>]*>").unwrap());
+
+    let mut code_words = 0;
+    for caps in CODE_RE.captures_iter(html_content) {
+        let code = crate::markdown::unescape_html(&TAG_RE.replace_all(&caps[1], ""));
+        code_words += code.split_whitespace().count();
+    }
+
+    let prose = CODE_RE.replace_all(html_content, " ");
+    let prose = crate::markdown::unescape_html(&TAG_RE.replace_all(&prose, " "));
+    let (cjk_chars, words) = count_words(&prose);
+
+    let minutes = cjk_chars as f64 / CJK_CHARS_PER_MINUTE
+        + words as f64 / WORDS_PER_MINUTE
+        + code_words as f64 / CODE_WORDS_PER_MINUTE;
+    let reading_minutes = if cjk_chars + words + code_words == 0 { 0 } else { (minutes.ceil() as i64).max(1) };
+
+    ReadingStats { word_count: (cjk_chars + words) as i64, reading_minutes }
+}
+
+/// 统计纯文本中的中日文字符数和其余文字的词数
+fn count_words(text: &str) -> (usize, usize) {
+    let mut cjk_chars = 0;
+    let mut words = 0;
+    for token in text.split_whitespace() {
+        // 中日文与拉丁文字混写时（例如“使用Rust编写”），中日文字符之间的片段各算一个词
+        let mut in_word = false;
+        for c in token.chars() {
+            if is_cjk(c) {
+                cjk_chars += 1;
+                in_word = false;
+            } else if c.is_alphanumeric() {
+                if !in_word {
+                    words += 1;
+                    in_word = true;
+                }
+            } else if !matches!(c, '\'' | '’' | '-' | '_' | '.') {
+                in_word = false;
+            }
+        }
+    }
+    (cjk_chars, words)
+}
+
+/// 是否为逐字计数的文字（汉字、假名）
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF      // 平假名、片假名
+        | 0x3400..=0x4DBF    // 扩展 A
+        | 0x4E00..=0x9FFF    // 基本汉字
+        | 0xF900..=0xFAFF    // 兼容汉字
+        | 0x20000..=0x2FA1F  // 扩展 B 及以后
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_cjk_and_latin() {
+        let html = "<p>使用Rust编写博客，支持 full-text search.</p><p>It's <em>fast</em>!</p>";
+        // 使用 编写 博客 支持 共 8 个汉字；Rust、full-text、search、It's、fast 共 5 个词
+        assert_eq!(stats(html), ReadingStats { word_count: 13, reading_minutes: 1 });
+        assert_eq!(stats("<p></p>"), ReadingStats::default());
+    }
+
+    #[test]
+    fn test_code_blocks_weighted_separately() {
+        let code = "let x = 1;\n".repeat(100);
+        let html = format!("<p>示例</p><pre><code>{}</code></pre>", code);
+        let result = stats(&html);
+        assert_eq!(result.word_count, 2);
+        // 400 个代码词按每分钟 100 词计算
+        assert_eq!(result.reading_minutes, 5);
+
+        let prose = format!("<p>{}</p>", "字".repeat(800));
+        assert_eq!(stats(&prose).reading_minutes, 2);
+    }
+}
@@ -198,6 +198,16 @@ pub fn configure_api_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::resource("/api/user/info")
             .route(web::get().to(api_handlers::user::info))
+    ).service(
+        web::resource("/api/user/profile")
+            .route(web::get().to(api_handlers::authors::get_profile))
+            .route(web::put().to(api_handlers::authors::update_profile))
+    );
+
+    // 作者 API
+    cfg.service(
+        web::resource("/api/authors/{username}")
+            .route(web::get().to(api_handlers::authors::get))
     );
 
     // 管理员 API - 用户
@@ -277,6 +287,12 @@ pub fn configure_api_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::resource("/api/passage/list")
             .route(web::get().to(api_handlers::passage::list))
+    ).service(
+        web::resource("/api/passage/{uuid}/related")
+            .route(web::get().to(api_handlers::related::list))
+    ).service(
+        web::resource("/api/passage/{uuid}/unlock")
+            .route(web::post().to(api_handlers::passage::unlock))
     ).service(
         web::resource("/api/passage/{uuid}")
             .route(web::get().to(api_handlers::passage::get))
@@ -287,6 +303,12 @@ pub fn configure_api_routes(cfg: &mut web::ServiceConfig) {
             .route(web::post().to(api_handlers::passage::create))
     );
 
+    // 搜索 API
+    cfg.service(
+        web::resource("/api/search")
+            .route(web::get().to(api_handlers::search::search))
+    );
+
     // 管理员 API - 文章
     cfg.service(
         web::resource("/api/admin/passages")
@@ -297,6 +319,32 @@ pub fn configure_api_routes(cfg: &mut web::ServiceConfig) {
     ).service(
         web::resource("/api/admin/passages/batch-delete")
             .route(web::post().to(api_handlers::passage::delete_batch))
+    ).service(
+        web::resource("/api/admin/passages/reorder")
+            .route(web::post().to(api_handlers::passage::reorder_pinned))
+    ).service(
+        web::resource("/api/admin/import")
+            .route(web::post().to(api_handlers::import::upload))
+    ).service(
+        web::resource("/api/admin/import/{id}")
+            .route(web::delete().to(api_handlers::import::discard))
+    ).service(
+        web::resource("/api/admin/import/{id}/apply")
+            .route(web::post().to(api_handlers::import::apply))
+    ).service(
+        web::resource("/api/admin/backups")
+            .route(web::get().to(api_handlers::backup::list))
+            .route(web::post().to(api_handlers::backup::create))
+    ).service(
+        web::resource("/api/admin/backups/upload")
+            .route(web::post().to(api_handlers::backup::upload))
+    ).service(
+        web::resource("/api/admin/backups/{name}")
+            .route(web::get().to(api_handlers::backup::download))
+            .route(web::delete().to(api_handlers::backup::delete))
+    ).service(
+        web::resource("/api/admin/backups/{name}/restore")
+            .route(web::post().to(api_handlers::backup::restore))
     ).service(
         web::resource("/api/admin/passages/{uuid}")
             .route(web::get().to(api_handlers::passage::get))
@@ -304,11 +352,45 @@ pub fn configure_api_routes(cfg: &mut web::ServiceConfig) {
             .route(web::delete().to(api_handlers::passage::delete))
     );
 
+    // 管理员 API - 文章修订
+    cfg.service(
+        web::resource("/api/admin/passages/{uuid}/revisions")
+            .route(web::get().to(api_handlers::revision::list))
+    ).service(
+        web::resource("/api/admin/passages/{uuid}/revisions/diff")
+            .route(web::get().to(api_handlers::revision::diff))
+    ).service(
+        web::resource("/api/admin/passages/{uuid}/revisions/{id}")
+            .route(web::get().to(api_handlers::revision::get))
+    ).service(
+        web::resource("/api/admin/passages/{uuid}/revisions/{id}/restore")
+            .route(web::post().to(api_handlers::revision::restore))
+    );
+
+    // 管理员 API - 文章预览链接
+    cfg.service(
+        web::resource("/api/admin/passages/{uuid}/preview-links")
+            .route(web::get().to(api_handlers::preview::list_for_passage))
+            .route(web::post().to(api_handlers::preview::create))
+    ).service(
+        web::resource("/api/admin/preview-links")
+            .route(web::get().to(api_handlers::preview::list))
+    ).service(
+        web::resource("/api/admin/preview-links/{id}")
+            .route(web::delete().to(api_handlers::preview::revoke))
+    );
+
     // 兼容 Go 版本的路由
     cfg.service(
         web::resource("/api/passages")
             .route(web::get().to(api_handlers::passage::list))
             .route(web::post().to(api_handlers::passage::create))
+    ).service(
+        web::resource("/api/passages/featured")
+            .route(web::get().to(api_handlers::passage::featured))
+    ).service(
+        web::resource("/api/passages/{uuid}/unlock")
+            .route(web::post().to(api_handlers::passage::unlock))
     ).service(
         web::resource("/api/passages/{uuid}")
             .route(web::get().to(api_handlers::passage::get))
@@ -318,6 +400,9 @@ pub fn configure_api_routes(cfg: &mut web::ServiceConfig) {
     ).service(
         web::resource("/api/categories")
             .route(web::get().to(api_handlers::categories::list))
+    ).service(
+        web::resource("/api/series/{id}")
+            .route(web::get().to(api_handlers::series::get))
     ).service(
         web::resource("/api/archive")
             .route(web::get().to(api_handlers::archive::list))
@@ -337,6 +422,12 @@ pub fn configure_api_routes(cfg: &mut web::ServiceConfig) {
     ).service(
         web::resource("/api/admin/categories/batch-delete")
             .route(web::post().to(api_handlers::categories::delete_batch))
+    ).service(
+        web::resource("/api/admin/categories/reorder")
+            .route(web::post().to(api_handlers::categories::reorder))
+    ).service(
+        web::resource("/api/admin/categories/{id}/move")
+            .route(web::post().to(api_handlers::categories::move_category))
     ).service(
         web::resource("/api/admin/categories/{id}")
             .route(web::get().to(api_handlers::categories::get))
@@ -359,6 +450,18 @@ pub fn configure_api_routes(cfg: &mut web::ServiceConfig) {
             .route(web::delete().to(api_handlers::tags::delete))
     );
 
+    // 管理员 API - 系列
+    cfg.service(
+        web::resource("/api/admin/series")
+            .route(web::get().to(api_handlers::series::admin_list))
+            .route(web::post().to(api_handlers::series::create))
+    ).service(
+        web::resource("/api/admin/series/{id}")
+            .route(web::get().to(api_handlers::series::admin_get))
+            .route(web::put().to(api_handlers::series::update))
+            .route(web::delete().to(api_handlers::series::delete))
+    );
+
     // 数据库统计和健康检查 API
     cfg.service(
         web::resource("/api/db/pool-status")
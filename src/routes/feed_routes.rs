@@ -0,0 +1,16 @@
+use actix_web::web;
+use crate::handlers::feed_handlers;
+
+/// 配置订阅源路由
+/// 单职责：仅负责 RSS / Atom / JSON Feed 的路由配置
+pub fn configure_feed_routes(cfg: &mut web::ServiceConfig) {
+    // 全站订阅源
+    cfg.route("/feed.xml", web::get().to(feed_handlers::rss));
+    cfg.route("/atom.xml", web::get().to(feed_handlers::atom));
+    cfg.route("/feed.json", web::get().to(feed_handlers::json));
+
+    // 分类、标签、作者订阅源
+    cfg.route("/category/{name}/{file}", web::get().to(feed_handlers::category_feed));
+    cfg.route("/tag/{name}/{file}", web::get().to(feed_handlers::tag_feed));
+    cfg.route("/author/{name}/{file}", web::get().to(feed_handlers::author_feed));
+}
@@ -11,10 +11,16 @@ pub fn configure_page_routes(cfg: &mut web::ServiceConfig) {
     // 文章页面
     cfg.route("/passage", web::get().to(page_handlers::passage_list));
     cfg.route("/passage/{id}", web::get().to(page_handlers::passage_detail));
+    cfg.route("/passage/{year}/{month}/{day}/{slug}", web::get().to(page_handlers::passage_permalink));
 
     // 归档页面
     cfg.route("/collect", web::get().to(page_handlers::collect));
 
+    // 分类、标签、作者页面
+    cfg.route("/category/{name}", web::get().to(page_handlers::category));
+    cfg.route("/tag/{name}", web::get().to(page_handlers::tag));
+    cfg.route("/author/{username}", web::get().to(page_handlers::author));
+
     // 关于页面
     cfg.route("/about", web::get().to(page_handlers::about));
 
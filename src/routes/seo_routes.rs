@@ -0,0 +1,13 @@
+use actix_web::web;
+use crate::handlers::seo_handlers;
+
+/// 配置搜索引擎相关路由
+/// 单职责：仅负责站点地图和 robots.txt 的路由配置
+pub fn configure_seo_routes(cfg: &mut web::ServiceConfig) {
+    // 站点地图（地址过多时 /sitemap.xml 为索引，分页位于 /sitemap-{page}.xml）
+    cfg.route("/sitemap.xml", web::get().to(seo_handlers::sitemap));
+    cfg.route("/sitemap-{page}.xml", web::get().to(seo_handlers::sitemap_page));
+
+    // robots.txt
+    cfg.route("/robots.txt", web::get().to(seo_handlers::robots));
+}
@@ -0,0 +1,277 @@
+//! HTML / CSS 清理模块
+//!
+//! 评论和非管理员作者的文章在渲染后经过这里的允许列表过滤（基于 ammonia）：
+//! 不在列表中的标签去掉标签本身、保留文本，脚本类标签连同内容一起删除，
+//! 属性只保留列表中的项，链接地址只允许 http、https、mailto 和站内地址。
+//! 文章中只有标题可以带 id（加上 [`ID_PREFIX`] 前缀，避免与页面元素重名），
+//! class 只保留 Markdown 渲染和代码高亮会输出的类名
+
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+
+use ammonia::{Builder, UrlRelative};
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+/// 清理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    /// 评论：只保留基础排版标签
+    Comment,
+    /// 文章：额外允许标题、图片、脚注等 Markdown 渲染会产生的标签
+    Passage,
+}
+
+/// 评论允许的标签
+const COMMENT_TAGS: &[&str] = &[
+    "a", "b", "blockquote", "br", "code", "del", "em", "hr", "i", "input", "li", "ol", "p", "pre", "s",
+    "span", "strong", "table", "tbody", "td", "th", "thead", "tr", "ul",
+];
+
+/// 文章在评论基础上额外允许的标签
+const PASSAGE_TAGS: &[&str] = &[
+    "abbr", "caption", "dd", "details", "div", "dl", "dt", "figcaption", "figure", "h1", "h2", "h3", "h4", "h5",
+    "h6", "img", "ins", "kbd", "mark", "section", "small", "sub", "summary", "sup", "tfoot", "u",
+];
+
+/// 连同内容一起删除的标签
+const DROP_CONTENT_TAGS: &[&str] = &[
+    "applet", "embed", "frame", "frameset", "iframe", "math", "noembed", "noframes", "noscript", "object",
+    "script", "style", "svg", "template", "textarea", "title", "xmp",
+];
+
+/// 给链接添加的 rel
+pub const LINK_REL: &str = "nofollow ugc noopener";
+
+/// 清理后的文章中标题 id 的前缀（目录从清理后的 HTML 中提取，锚点随之带上前缀）
+pub const ID_PREFIX: &str = "user-content-";
+
+/// 渲染器输出的固定类名（代码块、行号、公式、脚注）
+const RENDERED_CLASSES: &[&str] = &[
+    "code-block", "code-line", "footnote-definition", "footnote-definition-label", "footnote-reference",
+    "highlight-line", "hljs", "line-numbers", "math", "math-display", "math-inline",
+];
+
+static COMMENT_CLEANER: Lazy<Builder<'static>> = Lazy::new(|| builder(Policy::Comment));
+static PASSAGE_CLEANER: Lazy<Builder<'static>> = Lazy::new(|| builder(Policy::Passage));
+
+/// 按策略清理 HTML
+pub fn clean(html: &str, policy: Policy) -> String {
+    let cleaner = match policy {
+        Policy::Comment => &COMMENT_CLEANER,
+        Policy::Passage => &PASSAGE_CLEANER,
+    };
+    cleaner.clean(html).to_string()
+}
+
+/// 按策略构建允许列表
+fn builder(policy: Policy) -> Builder<'static> {
+    let mut tags: HashSet<&str> = COMMENT_TAGS.iter().copied().collect();
+    let mut generic_attributes: HashSet<&str> = HashSet::from(["title"]);
+    let mut tag_attributes: HashMap<&str, HashSet<&str>> = HashMap::from([
+        ("a", HashSet::from(["href"])),
+        ("ol", HashSet::from(["start"])),
+        ("td", HashSet::from(["colspan", "rowspan", "style"])),
+        ("th", HashSet::from(["colspan", "rowspan", "style"])),
+        ("input", HashSet::from(["checked"])),
+        ("span", HashSet::from(["data-line"])),
+    ]);
+    match policy {
+        Policy::Comment => {
+            for tag in ["code", "pre", "span"] {
+                tag_attributes.entry(tag).or_default().insert("class");
+            }
+        }
+        Policy::Passage => {
+            tags.extend(PASSAGE_TAGS);
+            generic_attributes.insert("class");
+            for heading in ["h1", "h2", "h3", "h4", "h5", "h6"] {
+                tag_attributes.insert(heading, HashSet::from(["id"]));
+            }
+            tag_attributes.insert("img", HashSet::from(["src", "alt", "width", "height"]));
+        }
+    }
+
+    let mut builder = Builder::empty();
+    builder
+        .tags(tags)
+        .clean_content_tags(DROP_CONTENT_TAGS.iter().copied().collect())
+        .generic_attributes(generic_attributes)
+        .tag_attributes(tag_attributes)
+        // 复选框只能是禁用状态的任务列表
+        .set_tag_attribute_value("input", "type", "checkbox")
+        .set_tag_attribute_value("input", "disabled", "")
+        .url_schemes(HashSet::from(["http", "https", "mailto"]))
+        .url_relative(UrlRelative::PassThrough)
+        .link_rel(Some(LINK_REL))
+        .attribute_filter(filter_attribute);
+    if policy == Policy::Passage {
+        builder.id_prefix(Some(ID_PREFIX));
+    }
+    builder
+}
+
+/// 表格对齐由 pulldown-cmark 以内联样式输出，style 只放行这一种写法；class 只保留渲染器输出的类名
+fn filter_attribute<'u>(_element: &str, attribute: &str, value: &'u str) -> Option<Cow<'u, str>> {
+    static ALIGN_RE: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"^\s*text-align:\s*(left|center|right)\s*;?\s*$").unwrap());
+
+    match attribute {
+        "style" if !ALIGN_RE.is_match(value) => None,
+        "class" => {
+            let classes: Vec<&str> = value.split_whitespace().filter(|class| is_rendered_class(class)).collect();
+            (!classes.is_empty()).then(|| Cow::Owned(classes.join(" ")))
+        }
+        _ => Some(Cow::Borrowed(value)),
+    }
+}
+
+/// 是否为渲染器会输出的类名：固定类名、`language-*`、`hljs-*` 以及 highlight.js 的 `title function_` 这类次级类名
+fn is_rendered_class(class: &str) -> bool {
+    if RENDERED_CLASSES.contains(&class) {
+        return true;
+    }
+    if let Some(language) = class.strip_prefix("language-") {
+        return !language.is_empty() && language.chars().all(crate::highlight::is_language_char);
+    }
+    let scope = class.strip_prefix("hljs-").unwrap_or(class);
+    let is_scope_char = |c: char| c.is_ascii_lowercase() || c == '_' || c == '-';
+    !scope.is_empty()
+        && scope.chars().all(is_scope_char)
+        && (class.starts_with("hljs-") || class.ends_with('_'))
+}
+
+/// 链接地址是否安全：相对地址、锚点或 http / https / mailto
+pub fn safe_url(url: &str) -> bool {
+    // 浏览器解析协议时会忽略空白和控制字符
+    let compact: String = url
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect::<String>()
+        .to_ascii_lowercase();
+
+    match compact.find([':', '/', '?', '#']) {
+        Some(pos) if compact.as_bytes()[pos] == b':' => {
+            matches!(&compact[..pos], "http" | "https" | "mailto")
+        }
+        _ => true,
+    }
+}
+
+/// 清理用户提交的 CSS
+///
+/// 自定义 CSS 既会放进 `<style>` 也会放进 `style="…"` 属性，因此去掉尖括号、引号和反斜杠转义，
+/// 删除 `@import`、`expression()`、`behavior`、`-moz-binding` 和脚本协议，`url()` 只允许安全地址
+pub fn sanitize_css(css: &str) -> String {
+    static COMMENT_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)/\*.*?(\*/|$)").unwrap());
+    static IMPORT_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)@import[^;]*;?").unwrap());
+    static EXPRESSION_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)expression\s*\(").unwrap());
+    static BINDING_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)(-moz-binding|behavior)\s*:[^;}]*;?").unwrap());
+    static URL_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)url\s*\(\s*'?([^)']*)'?\s*\)").unwrap());
+    static SCHEME_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)(javascript|vbscript)\s*:").unwrap());
+
+    let css: String = css
+        .chars()
+        .filter(|c| !matches!(c, '<' | '>' | '"' | '\\') && (!c.is_control() || matches!(c, '\n' | '\t')))
+        .collect();
+    let css = COMMENT_RE.replace_all(&css, "");
+    let css = IMPORT_RE.replace_all(&css, "");
+    let css = EXPRESSION_RE.replace_all(&css, "(");
+    let css = BINDING_RE.replace_all(&css, "");
+    let css = URL_RE.replace_all(&css, |caps: &Captures| {
+        let url = caps[1].trim();
+        if safe_url(url) && !url.to_ascii_lowercase().starts_with("mailto:") {
+            format!("url('{}')", url)
+        } else {
+            "none".to_string()
+        }
+    });
+    SCHEME_RE.replace_all(&css, "").trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_removes_scripts_and_handlers() {
+        let html = clean(
+            "<p onclick=\"x()\">你好<script>alert(1)</script><img src=x onerror=alert(1)></p><iframe src=\"//evil\"></iframe>",
+            Policy::Comment,
+        );
+        assert_eq!(html, "<p>你好</p>");
+    }
+
+    #[test]
+    fn test_clean_links() {
+        let html = clean(
+            "<a href=\"https://example.com\" rel=\"me\">站外</a><a href=\"&#106;avascript:alert(1)\">x</a><a href=\"#fn1\">1</a>",
+            Policy::Comment,
+        );
+        assert_eq!(
+            html,
+            "<a href=\"https://example.com\" rel=\"nofollow ugc noopener\">站外</a>\
+             <a rel=\"nofollow ugc noopener\">x</a>\
+             <a href=\"#fn1\" rel=\"nofollow ugc noopener\">1</a>"
+        );
+        assert!(!clean("<a href=\" java\tscript:x\">x</a>", Policy::Passage).contains("href"));
+    }
+
+    #[test]
+    fn test_clean_keeps_rendered_markdown() {
+        let markdown = "## 标题\n\n| a |\n|:-:|\n| b |\n\n```rust\nfn main() {}\n```\n\n![图](/a.png)\n";
+        let rendered = crate::markdown::render(markdown, crate::markdown::Profile::Full);
+        // 空元素按 HTML5 序列化（<img …> 而不是 <img … />），标题 id 加上前缀
+        let expected = rendered.replace(" />", ">").replace(" id=\"", &format!(" id=\"{}", ID_PREFIX));
+        assert_eq!(clean(&rendered, Policy::Passage), expected);
+
+        let task = clean(&crate::markdown::render("- [x] 完成", crate::markdown::Profile::Full), Policy::Passage);
+        assert!(task.contains("type=\"checkbox\"") && task.contains("disabled=\"\"") && task.contains("checked=\"\""));
+
+        // 其他输入框统一改为禁用的复选框
+        let input = clean("<input type=\"text\" value=\"x\">", Policy::Comment);
+        assert!(input.contains("type=\"checkbox\"") && input.contains("disabled=\"\"") && !input.contains("value"));
+    }
+
+    #[test]
+    fn test_untrusted_passage_cannot_set_page_ids_or_classes() {
+        let html = crate::markdown::render_passage(
+            "<div id=\"externalLinkModal\" class=\"admin modal\">x</div>\n\n<h2 id=\"sidebar\" class=\"hljs-x admin\">标题</h2>\n",
+            false,
+        );
+        assert!(html.contains("<div>x</div>"));
+        assert!(html.contains("<h2 id=\"user-content-sidebar\" class=\"hljs-x\">标题</h2>"));
+        assert!(!html.contains("id=\"externalLinkModal\"") && !html.contains("admin"));
+
+        // 目录锚点与清理后的标题 id 一致，代码高亮的类名保留
+        let html = crate::markdown::render_passage("## 介绍\n\n```rust\nfn main() {}\n```\n", false);
+        assert_eq!(crate::markdown::toc(&html)[0].id, "user-content-介绍");
+        assert!(html.contains("<code class=\"hljs language-rust\">"));
+        assert!(html.contains("class=\"hljs-title function_\""));
+
+        // 管理员的文章不经过清理
+        let trusted = crate::markdown::render_passage("<div id=\"x\">x</div>", true);
+        assert!(trusted.contains("id=\"x\""));
+    }
+
+    #[test]
+    fn test_clean_balances_tags() {
+        assert_eq!(clean("<b><i>x</b> y</i> 1 < 2", Policy::Comment), "<b><i>x</i></b><i> y</i> 1 &lt; 2");
+        assert_eq!(clean("<h1>标题", Policy::Comment), "标题");
+        assert_eq!(clean("<ul><li>未闭合", Policy::Comment), "<ul><li>未闭合</li></ul>");
+    }
+
+    #[test]
+    fn test_sanitize_css() {
+        let css = sanitize_css(
+            "@import url(evil.css); .a { color: red; background: url(\"javascript:x\"); width: expression(alert(1)); } </style><script>",
+        );
+        assert!(!css.contains("@import"));
+        assert!(!css.contains("javascript"));
+        assert!(!css.contains("expression"));
+        assert!(!css.contains('<'));
+        assert!(css.contains("color: red;"));
+        assert!(css.contains("background: none;"));
+        assert_eq!(sanitize_css("background: url(/img/bg.png)"), "background: url('/img/bg.png')");
+    }
+}
@@ -0,0 +1,186 @@
+//! 文章定时发布/下线模块
+//!
+//! 后台定期检查 published_at 已到的定时文章并发布，
+//! 检查 expires_at 已到的已发布文章并下线为草稿
+
+use std::sync::Arc;
+use std::time::Duration;
+use r2d2::Pool;
+use r2d2_sqlite::SqliteConnectionManager;
+
+use crate::cache::AppCache;
+use crate::db::repositories::PassageRepository;
+
+/// 调度配置
+#[derive(Debug, Clone)]
+pub struct SchedulerConfig {
+    /// 检查间隔（秒）
+    pub interval: u64,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            interval: 30,  // 每30秒检查一次
+        }
+    }
+}
+
+/// 定时发布调度器
+pub struct PublishScheduler {
+    _handle: tokio::task::JoinHandle<()>,
+}
+
+impl PublishScheduler {
+    /// 创建并启动调度器（启动时立即执行一次检查）
+    pub fn new(pool: Arc<Pool<SqliteConnectionManager>>, cache: Arc<AppCache>, config: SchedulerConfig) -> Self {
+        let handle = tokio::spawn(async move {
+            let passage_repo = PassageRepository::new(pool);
+            let mut interval = tokio::time::interval(Duration::from_secs(config.interval.max(1)));
+
+            loop {
+                interval.tick().await;
+                let changed = Self::run_once(&passage_repo).await;
+                if changed > 0 {
+                    cache.invalidate_passages();
+                }
+            }
+        });
+
+        Self { _handle: handle }
+    }
+
+    /// 执行一次检查，返回状态发生变化的文章数量
+    pub async fn run_once(passage_repo: &PassageRepository) -> usize {
+        let now = chrono::Utc::now();
+        let mut changed = 0;
+
+        // 先发布，再下线：发布时间和下线时间都已过的文章最终为下线状态
+        // 错误在 await 之前就地处理（Box<dyn Error> 不是 Send）
+        let due_for_publish = passage_repo.get_due_for_publish(now).await.unwrap_or_else(|e| {
+            eprintln!("❌ 查询待发布文章失败: {}", e);
+            Vec::new()
+        });
+        for uuid in due_for_publish {
+            match Self::publish(passage_repo, &uuid).await {
+                Ok(()) => changed += 1,
+                Err(e) => eprintln!("❌ 定时发布文章 {} 失败: {}", uuid, e),
+            }
+        }
+
+        let due_for_expiry = passage_repo.get_due_for_expiry(now).await.unwrap_or_else(|e| {
+            eprintln!("❌ 查询待下线文章失败: {}", e);
+            Vec::new()
+        });
+        for uuid in due_for_expiry {
+            match Self::expire(passage_repo, &uuid).await {
+                Ok(()) => changed += 1,
+                Err(e) => eprintln!("❌ 定时下线文章 {} 失败: {}", uuid, e),
+            }
+        }
+
+        changed
+    }
+
+    /// 发布一篇定时文章
+    async fn publish(passage_repo: &PassageRepository, uuid: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let mut passage = passage_repo.get_by_uuid(uuid).await?;
+        let from_status = passage.status.clone();
+        let scheduled_at = passage.published_at;
+
+        passage.status = "published".to_string();
+        passage.is_scheduled = false;
+        Self::save(passage_repo, &mut passage).await?;
+
+        passage_repo.log_schedule_transition(uuid, "publish", &from_status, &passage.status, scheduled_at).await?;
+        println!("⏰ 定时发布文章: {} ({})", passage.title, uuid);
+        Ok(())
+    }
+
+    /// 下线一篇到期文章
+    async fn expire(passage_repo: &PassageRepository, uuid: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let mut passage = passage_repo.get_by_uuid(uuid).await?;
+        let from_status = passage.status.clone();
+        let expires_at = passage.expires_at;
+
+        // 清除下线时间，避免重新发布后立即再次下线
+        passage.status = "draft".to_string();
+        passage.expires_at = None;
+        Self::save(passage_repo, &mut passage).await?;
+
+        passage_repo.log_schedule_transition(uuid, "expire", &from_status, &passage.status, expires_at).await?;
+        println!("⏰ 定时下线文章: {} ({})", passage.title, uuid);
+        Ok(())
+    }
+
+    /// 保存文章并同步 Markdown 文件的 front matter（否则下次同步会还原状态）
+    async fn save(passage_repo: &PassageRepository, passage: &mut crate::db::models::Passage) -> Result<(), Box<dyn std::error::Error>> {
+        passage.updated_at = chrono::Utc::now();
+        passage_repo.update(passage).await?;
+
+        if let Some(ref file_path) = passage.file_path {
+            if let Err(e) = crate::handlers::api_handlers::passage::update_markdown_file(file_path, passage) {
+                eprintln!("⚠️  更新 Markdown 文件失败: {}", e);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::Passage;
+    use chrono::{Duration as ChronoDuration, Utc};
+
+    #[tokio::test]
+    async fn test_run_once_publishes_due_passages() {
+        let passage_repo = PassageRepository::new(crate::db::init::test_pool());
+
+        let mut due = Passage::sample("到期", "正文");
+        due.status = "scheduled".to_string();
+        due.is_scheduled = true;
+        due.published_at = Some(Utc::now() - ChronoDuration::minutes(1));
+        let due_id = passage_repo.create(&due).await.unwrap();
+
+        let mut later = Passage::sample("未到期", "正文");
+        later.status = "scheduled".to_string();
+        later.is_scheduled = true;
+        later.published_at = Some(Utc::now() + ChronoDuration::hours(1));
+        let later_id = passage_repo.create(&later).await.unwrap();
+
+        assert_eq!(PublishScheduler::run_once(&passage_repo).await, 1);
+
+        let published = passage_repo.get_by_id(due_id).await.unwrap();
+        assert_eq!(published.status, "published");
+        assert!(!published.is_scheduled);
+        let waiting = passage_repo.get_by_id(later_id).await.unwrap();
+        assert_eq!(waiting.status, "scheduled");
+        assert!(waiting.is_scheduled);
+
+        // 再次执行不会重复发布
+        assert_eq!(PublishScheduler::run_once(&passage_repo).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_run_once_expires_passages() {
+        let passage_repo = PassageRepository::new(crate::db::init::test_pool());
+
+        let mut expired = Passage::sample("已到期", "正文");
+        expired.expires_at = Some(Utc::now() - ChronoDuration::minutes(1));
+        let expired_id = passage_repo.create(&expired).await.unwrap();
+
+        let mut active = Passage::sample("未到期", "正文");
+        active.expires_at = Some(Utc::now() + ChronoDuration::hours(1));
+        let active_id = passage_repo.create(&active).await.unwrap();
+
+        assert_eq!(PublishScheduler::run_once(&passage_repo).await, 1);
+
+        let offline = passage_repo.get_by_id(expired_id).await.unwrap();
+        assert_eq!(offline.status, "draft");
+        assert!(offline.expires_at.is_none());
+        assert_eq!(passage_repo.get_by_id(active_id).await.unwrap().status, "published");
+
+        assert_eq!(PublishScheduler::run_once(&passage_repo).await, 0);
+    }
+}
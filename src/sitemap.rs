@@ -0,0 +1,63 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use crate::feed::escape;
+
+/// 单个站点地图文件最多包含的 URL 数量（协议上限为 50000，这里取较小值控制响应体积）
+pub const MAX_URLS_PER_SITEMAP: usize = 5000;
+
+/// 站点地图条目
+#[derive(Debug, Clone)]
+pub struct SitemapEntry {
+    pub loc: String,
+    pub lastmod: Option<DateTime<Utc>>,
+}
+
+/// 生成 urlset
+pub fn render_urlset(entries: &[SitemapEntry]) -> String {
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
+    for entry in entries {
+        xml.push_str("<url>");
+        xml.push_str(&format!("<loc>{}</loc>", escape(&entry.loc)));
+        if let Some(lastmod) = &entry.lastmod {
+            xml.push_str(&format!("<lastmod>{}</lastmod>", lastmod.to_rfc3339_opts(SecondsFormat::Secs, true)));
+        }
+        xml.push_str("</url>\n");
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+/// 生成站点地图索引，`sitemaps` 为各分页站点地图的地址和最后修改时间
+pub fn render_index(sitemaps: &[SitemapEntry]) -> String {
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<sitemapindex xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
+    for sitemap in sitemaps {
+        xml.push_str("<sitemap>");
+        xml.push_str(&format!("<loc>{}</loc>", escape(&sitemap.loc)));
+        if let Some(lastmod) = &sitemap.lastmod {
+            xml.push_str(&format!("<lastmod>{}</lastmod>", lastmod.to_rfc3339_opts(SecondsFormat::Secs, true)));
+        }
+        xml.push_str("</sitemap>\n");
+    }
+    xml.push_str("</sitemapindex>\n");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_urlset() {
+        let lastmod = DateTime::parse_from_rfc3339("2024-05-01T08:00:00Z").unwrap().with_timezone(&Utc);
+        let xml = render_urlset(&[
+            SitemapEntry { loc: "https://example.com/tag/a&b".to_string(), lastmod: Some(lastmod) },
+            SitemapEntry { loc: "https://example.com/about".to_string(), lastmod: None },
+        ]);
+        assert!(xml.contains("<url><loc>https://example.com/tag/a&amp;b</loc><lastmod>2024-05-01T08:00:00Z</lastmod></url>"));
+        assert!(xml.contains("<url><loc>https://example.com/about</loc></url>"));
+    }
+}
@@ -0,0 +1,77 @@
+use chrono::{DateTime, Utc};
+
+/// slug 最大长度（字符数）
+const MAX_LENGTH: usize = 80;
+
+/// 根据标题生成 slug
+///
+/// 中文等非 ASCII 字符先转写为拉丁字母（汉字转为拼音），
+/// 然后只保留小写字母和数字，其余字符合并为单个连字符
+pub fn slugify(text: &str) -> String {
+    let ascii = deunicode::deunicode(text);
+
+    let mut slug = String::with_capacity(ascii.len());
+    for c in ascii.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    // 截断到最大长度，避免在连字符处结束
+    slug.truncate(MAX_LENGTH);
+    slug.trim_end_matches('-').to_string()
+}
+
+/// 规范化用户输入的 slug，结果为空时返回 None
+///
+/// 纯数字的 slug 会被当作文章 ID（/api/passages/{id}），因此加上 `p-` 前缀
+pub fn normalize(input: &str) -> Option<String> {
+    let slug = slugify(input);
+    if slug.is_empty() {
+        None
+    } else if slug.bytes().all(|b| b.is_ascii_digit()) {
+        Some(format!("p-{}", slug))
+    } else {
+        Some(slug)
+    }
+}
+
+/// 文章的规范链接：/passage/{yyyy}/{mm}/{dd}/{slug}
+pub fn permalink(created_at: &DateTime<Utc>, slug: &str) -> String {
+    format!("/passage/{}/{}", created_at.format("%Y/%m/%d"), slug)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Hello, World!"), "hello-world");
+        assert_eq!(slugify("  Rust 全文搜索 "), "rust-quan-wen-sou-suo");
+        assert_eq!(slugify("---"), "");
+        assert_eq!(normalize("《》！？"), None);
+    }
+
+    #[test]
+    fn test_normalize_prefixes_numeric_slugs() {
+        assert_eq!(normalize("2024"), Some("p-2024".to_string()));
+        assert_eq!(normalize("2024!"), Some("p-2024".to_string()));
+        assert_eq!(normalize("2024 回顾"), Some("2024-hui-gu".to_string()));
+    }
+
+    #[test]
+    fn test_slugify_truncates() {
+        let slug = slugify(&"ab ".repeat(40));
+        assert!(slug.len() <= MAX_LENGTH);
+        assert!(!slug.ends_with('-'));
+    }
+
+    #[test]
+    fn test_permalink() {
+        let created_at = DateTime::parse_from_rfc3339("2024-05-01T08:00:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(permalink(&created_at, "hello"), "/passage/2024/05/01/hello");
+    }
+}
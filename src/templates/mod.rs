@@ -768,9 +768,13 @@ pub fn create_passage_context() -> TeraContext {
     context.insert("passage_id", "");
     context.insert("published_at", &now.format("%Y-%m-%d %H:%M").to_string());
     context.insert("read_time", "5 分钟");
+    context.insert("word_count", &0);
+    context.insert("reading_minutes", &0);
     context.insert("passage_status", "published");
     context.insert("is_scheduled", &false);
     context.insert("is_unpublished", &false);
+    context.insert("locked", &false);
+    context.insert("passage_uuid", "");
     
     // 赞助
     context.insert("sponsor_enabled", &sponsor_enabled);
@@ -862,6 +866,156 @@ pub fn create_passage_context() -> TeraContext {
     context
 }
 
+/// 创建文章详情上下文（服务端渲染正文和 SEO 元数据）
+///
+/// `origin` 为站点根地址（如 `https://example.com`），用于生成规范链接和分享图片的绝对地址；
+/// `noindex` 为 true 时（预览、仅管理员可见）禁止搜索引擎收录
+pub fn create_passage_detail_context(
+    passage: &crate::db::models::Passage,
+    origin: &str,
+    noindex: bool,
+) -> TeraContext {
+    let mut context = create_passage_context();
+
+    let tags = passage.tag_list();
+    let published_at = passage.published_at.unwrap_or(passage.created_at);
+    let canonical_url = format!(
+        "{}{}",
+        origin,
+        passage.permalink().unwrap_or_else(|| format!("/passage/{}", passage.id.unwrap_or(0)))
+    );
+    let cover_image = passage.cover_image.as_deref()
+        .filter(|c| !c.trim().is_empty())
+        .map(|c| absolute_url(origin, c.trim()));
+    let summary = passage.summary.as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+
+    // 阅读时长在保存时计算，至少显示 1 分钟
+    let read_time = passage.reading_minutes.max(1);
+
+    context.insert("title", &passage.title);
+    context.insert("content", &passage.content);
+    context.insert("tags", &tags.join(","));
+    context.insert("tag_list", &tags);
+    context.insert("toc", &crate::markdown::toc(&passage.content));
+    context.insert("category", &passage.category);
+    context.insert("author", &passage.author);
+    context.insert("date", &passage.created_at.format("%Y-%m-%d").to_string());
+    context.insert("passage_id", &passage.id.unwrap_or(0).to_string());
+    context.insert("passage_uuid", passage.uuid.as_deref().unwrap_or_default());
+    context.insert("published_at", &published_at.format("%Y-%m-%d %H:%M").to_string());
+    context.insert("read_time", &read_time);
+    context.insert("word_count", &passage.word_count);
+    context.insert("reading_minutes", &passage.reading_minutes);
+    context.insert("passage_status", &passage.status);
+    context.insert("is_scheduled", &passage.is_scheduled);
+    if let Some(summary) = summary {
+        context.insert("summary", summary);
+    }
+
+    // SEO：规范链接、OpenGraph / Twitter 卡片、JSON-LD
+    context.insert("canonical_url", &canonical_url);
+    context.insert("og_image", &cover_image);
+    context.insert("published_time", &published_at.to_rfc3339());
+    context.insert("modified_time", &passage.updated_at.to_rfc3339());
+    context.insert("noindex", &noindex);
+
+    let mut json_ld = serde_json::json!({
+        "@context": "https://schema.org",
+        "@type": "BlogPosting",
+        "headline": passage.title,
+        "url": canonical_url,
+        "mainEntityOfPage": { "@type": "WebPage", "@id": canonical_url },
+        "datePublished": published_at.to_rfc3339(),
+        "dateModified": passage.updated_at.to_rfc3339(),
+        "author": { "@type": "Person", "name": passage.author },
+        "articleSection": passage.category,
+        "keywords": tags.join(","),
+    });
+    if let Some(summary) = summary {
+        json_ld["description"] = serde_json::Value::from(summary);
+    }
+    if let Some(image) = &cover_image {
+        json_ld["image"] = serde_json::Value::from(image.as_str());
+    }
+    // 转义 `</`，防止标题等内容提前闭合 <script> 标签
+    context.insert("json_ld", &json_ld.to_string().replace("</", "<\\/"));
+
+    context
+}
+
+/// 创建分类、标签页上下文
+///
+/// `path` 为页面路径（如 `/category/技术`，已编码），订阅源地址为 `{path}/feed.xml`
+pub fn create_taxonomy_context(
+    kind_label: &str,
+    name: &str,
+    passages: &[crate::db::models::Passage],
+    origin: &str,
+    path: &str,
+) -> TeraContext {
+    let mut context = create_collect_context();
+
+    let mut site_title = "RustBlog".to_string();
+    if let Ok(pool) = crate::db::get_db_pool_sync() {
+        if let Ok(conn) = pool.get() {
+            if let Ok(Some(setting)) = crate::db::repositories::SettingRepository::get(&conn, "template_name") {
+                site_title = setting.value;
+            }
+        }
+    }
+
+    let items: Vec<serde_json::Value> = passages.iter()
+        .map(|p| serde_json::json!({
+            "title": p.title,
+            "url": p.permalink().unwrap_or_else(|| format!("/passage/{}", p.id.unwrap_or(0))),
+            "date": p.published_at.unwrap_or(p.created_at).format("%Y-%m-%d").to_string(),
+            "summary": p.summary,
+            "word_count": p.word_count,
+            "reading_minutes": p.reading_minutes,
+        }))
+        .collect();
+
+    context.insert("site_title", &site_title);
+    context.insert("kind_label", kind_label);
+    context.insert("name", name);
+    context.insert("passages", &items);
+    context.insert("canonical_url", &format!("{}{}", origin, path));
+    context.insert("feed_url", &format!("{}/feed.xml", path));
+
+    context
+}
+
+/// 将站内路径转换为绝对地址（已是完整 URL 时原样返回）
+fn absolute_url(origin: &str, path: &str) -> String {
+    if path.starts_with("http://") || path.starts_with("https://") {
+        path.to_string()
+    } else if path.starts_with("//") {
+        format!("https:{}", path)
+    } else {
+        format!("{}/{}", origin, path.trim_start_matches('/'))
+    }
+}
+
+/// 渲染状态页（templates/status/{code}.html），并返回对应的状态码
+pub async fn render_status_page(status: actix_web::http::StatusCode) -> HttpResponse {
+    let tera = TERA.read().await;
+    let template_name = format!("status/{}.html", status.as_u16());
+
+    match tera.render(&template_name, &TeraContext::new()) {
+        Ok(html) => HttpResponse::build(status)
+            .content_type("text/html; charset=utf-8")
+            .insert_header(("Cache-Control", "no-cache"))
+            .body(html),
+        Err(_) => HttpResponse::build(status).body(format!(
+            "{}: {}",
+            status.as_u16(),
+            status.canonical_reason().unwrap_or("Unknown Status")
+        )),
+    }
+}
+
 /// 创建归档上下文
 pub fn create_collect_context() -> TeraContext {
     let mut context = TeraContext::new();
@@ -1409,4 +1563,59 @@ pub fn create_admin_context() -> TeraContext {
     context.insert("live2d_height", &live2d_height);
     
     context
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::Passage;
+
+    const ORIGIN: &str = "https://example.com";
+
+    fn context_str<'a>(context: &'a TeraContext, key: &str) -> &'a str {
+        context.get(key).and_then(|value| value.as_str()).unwrap()
+    }
+
+    #[test]
+    fn test_absolute_url() {
+        assert_eq!(absolute_url(ORIGIN, "/uploads/a.png"), "https://example.com/uploads/a.png");
+        assert_eq!(absolute_url(ORIGIN, "uploads/a.png"), "https://example.com/uploads/a.png");
+        assert_eq!(absolute_url(ORIGIN, "//cdn.example.com/a.png"), "https://cdn.example.com/a.png");
+        assert_eq!(absolute_url(ORIGIN, "http://cdn.example.com/a.png"), "http://cdn.example.com/a.png");
+        assert_eq!(absolute_url(ORIGIN, "https://cdn.example.com/a.png"), "https://cdn.example.com/a.png");
+    }
+
+    #[test]
+    fn test_canonical_url() {
+        let mut passage = Passage::sample("标题", "正文");
+        passage.id = Some(7);
+        let context = create_passage_detail_context(&passage, ORIGIN, false);
+        assert_eq!(context_str(&context, "canonical_url"), "https://example.com/passage/7");
+
+        passage.slug = Some("hello-world".to_string());
+        let context = create_passage_detail_context(&passage, ORIGIN, false);
+        let expected = format!("{}{}", ORIGIN, passage.permalink().unwrap());
+        assert_eq!(context_str(&context, "canonical_url"), expected);
+        assert!(context_str(&context, "json_ld").contains(&expected));
+    }
+
+    #[test]
+    fn test_json_ld_escapes_script_end() {
+        let passage = Passage::sample("</script><script>alert(1)</script>", "正文");
+        let context = create_passage_detail_context(&passage, ORIGIN, false);
+        let json_ld = context_str(&context, "json_ld");
+        assert!(!json_ld.contains("</"));
+        // 转义后仍是合法 JSON，标题保持原样
+        let value: serde_json::Value = serde_json::from_str(json_ld).unwrap();
+        assert_eq!(value["headline"], "</script><script>alert(1)</script>");
+    }
+
+    #[test]
+    fn test_noindex_for_non_public_access() {
+        let passage = Passage::sample("标题", "正文");
+        let public = create_passage_detail_context(&passage, ORIGIN, false);
+        assert_eq!(public.get("noindex"), Some(&serde_json::Value::Bool(false)));
+        let restricted = create_passage_detail_context(&passage, ORIGIN, true);
+        assert_eq!(restricted.get("noindex"), Some(&serde_json::Value::Bool(true)));
+    }
+}
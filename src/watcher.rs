@@ -0,0 +1,648 @@
+//! 文件变化监听模块
+//!
+//! 通过 inotify 监听 markdown、music 目录，把短时间内的连续事件合并后只同步发生变化的文件。
+//! 改名按移动处理：只更新文章或音乐记录中的路径，文章 UUID、评论和阅读记录保持不变。
+//! 服务自身写入的 markdown 文件（数据库已同步更新）通过 [`record_own_write`] 登记后不再重复同步
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use notify::event::{AccessKind, AccessMode, EventKind, ModifyKind, RenameMode};
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use sha2::{Digest, Sha256};
+use tokio::sync::mpsc;
+
+use crate::cache::AppCache;
+use crate::db::repositories::{PassageRepository, Repository};
+use crate::handlers::api_handlers::sync;
+use crate::music_sync::MusicSyncService;
+
+/// 监听目录不存在时重新尝试监听的间隔
+const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 服务自身修改文件的登记保留时间，过期后不再比对
+const OWN_WRITE_TTL: Duration = Duration::from_secs(5);
+
+/// 文件内容的 SHA-256 摘要
+type Digest32 = [u8; 32];
+
+/// 服务自身刚修改过的文件（`markdown/…` 相对路径）、登记时间及写入后的内容摘要（已删除为 `None`）
+static OWN_WRITES: Mutex<BTreeMap<String, (Instant, Option<Digest32>)>> = Mutex::new(BTreeMap::new());
+
+/// 文件当前内容的摘要，文件不存在或无法读取时为 `None`
+fn content_digest(path: &str) -> Option<Digest32> {
+    std::fs::read(path).ok().map(|content| Sha256::digest(&content).into())
+}
+
+/// 在写入、改名或删除 markdown 文件之后登记其当前内容；监听到的事件只有在文件仍是这份内容时才被忽略
+pub fn record_own_write(path: &str) {
+    let digest = content_digest(path);
+    OWN_WRITES.lock().unwrap().insert(path.to_string(), (Instant::now(), digest));
+}
+
+/// 文件是否仍是服务自身最近写入的内容（同时清理过期的登记）
+fn is_own_write(path: &str) -> bool {
+    let mut writes = OWN_WRITES.lock().unwrap();
+    writes.retain(|_, (at, _)| at.elapsed() < OWN_WRITE_TTL);
+    writes.get(path).is_some_and(|(_, digest)| *digest == content_digest(path))
+}
+
+/// 监听配置
+#[derive(Debug, Clone)]
+pub struct WatcherConfig {
+    /// 合并事件的静默时间（毫秒）：最后一个事件之后这么久没有新事件才开始同步
+    pub debounce_ms: u64,
+}
+
+impl Default for WatcherConfig {
+    fn default() -> Self {
+        Self {
+            debounce_ms: 500,
+        }
+    }
+}
+
+/// 文件变化监听器
+pub struct FileWatcher {
+    _handle: tokio::task::JoinHandle<()>,
+}
+
+impl FileWatcher {
+    /// 创建并启动监听（监听以当前工作目录下的 markdown、music 目录为准）
+    pub fn new(repo: Arc<dyn Repository>, cache: Arc<AppCache>, config: WatcherConfig) -> Option<Self> {
+        let cwd = match std::env::current_dir() {
+            Ok(cwd) => cwd,
+            Err(e) => {
+                eprintln!("⚠️  获取工作目录失败，文件监听未启动: {}", e);
+                return None;
+            }
+        };
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let watcher = match notify::recommended_watcher(move |event| {
+            let _ = tx.send(event);
+        }) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                eprintln!("⚠️  创建文件监听失败: {}", e);
+                return None;
+            }
+        };
+
+        let mut task = WatchTask {
+            watcher,
+            markdown_root: cwd.join("markdown"),
+            music_root: cwd.join("music"),
+            cwd,
+            unwatched: Vec::new(),
+            repo,
+            cache,
+            config,
+        };
+        for root in [task.markdown_root.clone(), task.music_root.clone()] {
+            if let Err(e) = task.watcher.watch(&root, RecursiveMode::Recursive) {
+                eprintln!("⚠️  监听目录失败 {}: {}", root.display(), e);
+                task.unwatched.push(root);
+            }
+        }
+
+        Some(Self {
+            _handle: tokio::spawn(task.run(rx)),
+        })
+    }
+}
+
+/// 后台监听任务
+struct WatchTask {
+    watcher: RecommendedWatcher,
+    cwd: PathBuf,
+    markdown_root: PathBuf,
+    music_root: PathBuf,
+    /// 尚未成功监听（不存在或被替换）的目录
+    unwatched: Vec<PathBuf>,
+    repo: Arc<dyn Repository>,
+    cache: Arc<AppCache>,
+    config: WatcherConfig,
+}
+
+impl WatchTask {
+    async fn run(mut self, mut rx: mpsc::UnboundedReceiver<notify::Result<Event>>) {
+        let debounce = Duration::from_millis(self.config.debounce_ms.max(1));
+
+        loop {
+            let first = if self.unwatched.is_empty() {
+                rx.recv().await
+            } else {
+                match tokio::time::timeout(RETRY_INTERVAL, rx.recv()).await {
+                    Ok(first) => first,
+                    Err(_) => {
+                        for root in std::mem::take(&mut self.unwatched) {
+                            self.rescan(&root).await;
+                        }
+                        continue;
+                    }
+                }
+            };
+            let Some(first) = first else {
+                return;
+            };
+
+            let mut batch = Batch::new(&self.markdown_root, &self.music_root);
+            batch.record(first);
+            while let Ok(Some(event)) = tokio::time::timeout(debounce, rx.recv()).await {
+                batch.record(event);
+            }
+
+            let (markdown, music) = batch.finish();
+            self.apply_markdown(markdown).await;
+            self.apply_music(music).await;
+        }
+    }
+
+    /// 同步 markdown 目录中的变化
+    async fn apply_markdown(&mut self, changes: ChangeSet) {
+        if changes.is_empty() {
+            return;
+        }
+        let passage_repo = PassageRepository::new(self.repo.get_pool().clone());
+
+        for op in &changes.ops {
+            let result = match op {
+                // 删除后又重新创建的文件（部分编辑器保存时如此）按内容变化处理
+                Op::Remove(path) if path.exists() => Ok(0),
+                Op::Remove(path) => match self.relative(path) {
+                    Some(path) if !is_own_write(&path) => sync::remove_path(&path),
+                    _ => Ok(0),
+                },
+                // 编辑器把临时文件改名覆盖原文件时不是移动，由后面的内容同步更新原文章
+                Op::Rename(from, to) => match (self.relative(from), self.relative(to)) {
+                    (Some(from_rel), Some(to_rel)) if is_own_write(&from_rel) && is_own_write(&to_rel) => Ok(0),
+                    (Some(from_rel), Some(to_rel)) if to.is_dir() || (is_markdown(from) && is_markdown(to)) => {
+                        sync::move_path(&from_rel, &to_rel)
+                    }
+                    // 改成了非 markdown 文件名，视为删除
+                    (Some(from_rel), _) if !from.exists() => sync::remove_path(&from_rel),
+                    _ => Ok(0),
+                },
+            };
+            if let Err(e) = result {
+                eprintln!("⚠️  同步文件变化失败: {}", e);
+            }
+        }
+
+        let mut files = BTreeSet::new();
+        for path in &changes.changed {
+            if path.is_dir() {
+                collect_markdown_files(path, &mut files);
+            } else if is_markdown(path) && path.is_file() {
+                files.insert(path.clone());
+            }
+        }
+        for path in files {
+            let Some(relative) = self.relative(&path).filter(|relative| !is_own_write(relative)) else {
+                continue;
+            };
+            if let Err(e) = sync::sync_file(&passage_repo, Path::new(&relative)).await {
+                eprintln!("同步文件失败 {}: {}", relative, e);
+            }
+        }
+
+        if changes.rescan {
+            let root = self.markdown_root.clone();
+            self.rescan(&root).await;
+        }
+
+        self.cache.invalidate_passages();
+    }
+
+    /// 同步 music 目录中的变化（只处理顶层的音频文件和 covers 目录中的封面）
+    async fn apply_music(&mut self, changes: ChangeSet) {
+        if changes.is_empty() {
+            return;
+        }
+        let service = MusicSyncService::new(self.repo.clone());
+        let covers_dir = self.music_root.join("covers");
+
+        for op in &changes.ops {
+            let result = match op {
+                Op::Remove(path) if path.exists() => Ok(()),
+                Op::Remove(path) => match file_name_in(path, &self.music_root) {
+                    Some(name) => service.remove_file(name).await,
+                    None => Ok(()),
+                },
+                Op::Rename(from, to) => match (file_name_in(from, &self.music_root), file_name_in(to, &self.music_root)) {
+                    (Some(from_name), Some(to_name)) => service.rename_file(from_name, to_name).await,
+                    (Some(from_name), None) if !from.exists() => service.remove_file(from_name).await,
+                    _ => Ok(()),
+                },
+            };
+            if let Err(e) = result {
+                eprintln!("Warning: Failed to sync music change: {}", e);
+            }
+        }
+
+        for path in &changes.changed {
+            let result = if let Some(name) = file_name_in(path, &self.music_root) {
+                service.sync_file(name).await
+            } else if let Some(name) = file_name_in(path, &covers_dir) {
+                service.sync_cover(name).await
+            } else {
+                Ok(())
+            };
+            if let Err(e) = result {
+                eprintln!("Warning: Failed to sync music change: {}", e);
+            }
+        }
+
+        if changes.rescan {
+            let root = self.music_root.clone();
+            self.rescan(&root).await;
+        }
+    }
+
+    /// 目录被替换（如从备份恢复）或事件丢失时重新监听并完整同步
+    async fn rescan(&mut self, root: &Path) {
+        let _ = self.watcher.unwatch(root);
+        if let Err(e) = self.watcher.watch(root, RecursiveMode::Recursive) {
+            if !self.unwatched.iter().any(|path| path == root) {
+                eprintln!("⚠️  监听目录失败 {}，稍后重试: {}", root.display(), e);
+                self.unwatched.push(root.to_path_buf());
+            }
+            return;
+        }
+        println!("🔄 重新同步目录: {}", root.display());
+
+        if root == self.markdown_root {
+            let passage_repo = PassageRepository::new(self.repo.get_pool().clone());
+            match sync::sync_directory_internal(&passage_repo).await {
+                Ok(result) => println!("✅ {}", result.message),
+                Err(e) => eprintln!("⚠️  文章同步失败: {}", e),
+            }
+            self.cache.invalidate_passages();
+        } else {
+            match MusicSyncService::new(self.repo.clone()).sync_music_files_to_db().await {
+                Ok(result) => println!("✅ {}", result.message),
+                Err(e) => eprintln!("⚠️  音乐同步失败: {}", e),
+            }
+        }
+    }
+
+    /// 转换为数据库中保存的相对路径（如 markdown/2024/01/01/a.md）
+    fn relative(&self, path: &Path) -> Option<String> {
+        relative_path(&self.cwd, path)
+    }
+}
+
+/// 需要按顺序处理的变化
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Op {
+    /// 删除（或移出监听目录）
+    Remove(PathBuf),
+    /// 改名或在目录内移动
+    Rename(PathBuf, PathBuf),
+}
+
+/// 一个监听目录在一次合并窗口内的变化
+#[derive(Debug, Default, PartialEq)]
+struct ChangeSet {
+    /// 删除与改名，按发生顺序处理
+    ops: Vec<Op>,
+    /// 新建或内容变化的路径，在删除与改名之后同步
+    changed: BTreeSet<PathBuf>,
+    /// 事件丢失或目录本身被移走，需要重新监听并完整同步
+    rescan: bool,
+}
+
+impl ChangeSet {
+    fn is_empty(&self) -> bool {
+        self.ops.is_empty() && self.changed.is_empty() && !self.rescan
+    }
+}
+
+/// 一次合并窗口内收到的事件
+struct Batch {
+    markdown_root: PathBuf,
+    music_root: PathBuf,
+    markdown: ChangeSet,
+    music: ChangeSet,
+    /// 已收到移出事件、尚未收到对应移入事件的路径（按 inotify cookie 配对）
+    pending_moves: HashMap<usize, PathBuf>,
+}
+
+impl Batch {
+    fn new(markdown_root: &Path, music_root: &Path) -> Self {
+        Self {
+            markdown_root: markdown_root.to_path_buf(),
+            music_root: music_root.to_path_buf(),
+            markdown: ChangeSet::default(),
+            music: ChangeSet::default(),
+            pending_moves: HashMap::new(),
+        }
+    }
+
+    fn record(&mut self, event: notify::Result<Event>) {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                eprintln!("⚠️  文件监听出错: {}", e);
+                return;
+            }
+        };
+        if event.need_rescan() {
+            self.markdown.rescan = true;
+            self.music.rescan = true;
+            return;
+        }
+
+        match event.kind {
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                for path in event.paths.iter().cloned() {
+                    match event.tracker() {
+                        Some(tracker) => {
+                            self.pending_moves.insert(tracker, path);
+                        }
+                        None => self.remove(path),
+                    }
+                }
+            }
+            // 配对成功的移入事件之后紧接着会收到 Both 事件，这里只处理从外部移入
+            EventKind::Modify(ModifyKind::Name(RenameMode::To))
+                if !event.tracker().is_some_and(|tracker| self.pending_moves.contains_key(&tracker)) =>
+            {
+                for path in event.paths {
+                    self.change(path);
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+                if let Some(tracker) = event.tracker() {
+                    self.pending_moves.remove(&tracker);
+                }
+                if let [from, to] = event.paths.as_slice() {
+                    self.rename(from.clone(), to.clone());
+                }
+            }
+            EventKind::Create(_)
+            | EventKind::Modify(ModifyKind::Data(_))
+            | EventKind::Modify(ModifyKind::Any)
+            | EventKind::Access(AccessKind::Close(AccessMode::Write)) => {
+                for path in event.paths {
+                    self.change(path);
+                }
+            }
+            EventKind::Remove(_) => {
+                for path in event.paths {
+                    self.remove(path);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 结束合并窗口，没有配对的移出事件视为删除
+    fn finish(mut self) -> (ChangeSet, ChangeSet) {
+        for path in std::mem::take(&mut self.pending_moves).into_values() {
+            self.remove(path);
+        }
+        (self.markdown, self.music)
+    }
+
+    fn change(&mut self, path: PathBuf) {
+        if let Some((set, is_root)) = self.set_for(&path) {
+            if is_root {
+                set.rescan = true;
+            } else {
+                set.changed.insert(path);
+            }
+        }
+    }
+
+    fn remove(&mut self, path: PathBuf) {
+        if let Some((set, is_root)) = self.set_for(&path) {
+            if is_root {
+                set.rescan = true;
+            } else {
+                set.ops.push(Op::Remove(path));
+            }
+        }
+    }
+
+    fn rename(&mut self, from: PathBuf, to: PathBuf) {
+        let same_root = match (self.root_of(&from), self.root_of(&to)) {
+            (Some(from_root), Some(to_root)) => from_root == to_root && from != from_root && to != to_root,
+            _ => false,
+        };
+        if same_root {
+            if let Some((set, _)) = self.set_for(&from) {
+                set.ops.push(Op::Rename(from, to.clone()));
+                set.changed.insert(to);
+            }
+        } else {
+            self.remove(from);
+            self.change(to);
+        }
+    }
+
+    fn root_of(&self, path: &Path) -> Option<PathBuf> {
+        [&self.markdown_root, &self.music_root]
+            .into_iter()
+            .find(|root| path.starts_with(root))
+            .cloned()
+    }
+
+    /// 路径所属目录的变化集合，以及路径是否为监听目录本身
+    fn set_for(&mut self, path: &Path) -> Option<(&mut ChangeSet, bool)> {
+        if path.starts_with(&self.markdown_root) {
+            Some((&mut self.markdown, path == self.markdown_root))
+        } else if path.starts_with(&self.music_root) {
+            Some((&mut self.music, path == self.music_root))
+        } else {
+            None
+        }
+    }
+}
+
+fn is_markdown(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "md")
+}
+
+/// 相对于工作目录的路径，使用 `/` 分隔
+fn relative_path(cwd: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(cwd).ok()?;
+    let parts: Option<Vec<&str>> = relative.components().map(|c| c.as_os_str().to_str()).collect();
+    Some(parts?.join("/"))
+}
+
+/// 直接位于 `dir` 下的文件名
+fn file_name_in<'a>(path: &'a Path, dir: &Path) -> Option<&'a str> {
+    if path.parent() != Some(dir) {
+        return None;
+    }
+    path.file_name()?.to_str()
+}
+
+/// 收集目录下的所有 markdown 文件（使用迭代而非递归）
+fn collect_markdown_files(dir: &Path, files: &mut BTreeSet<PathBuf>) {
+    let mut dir_stack = vec![dir.to_path_buf()];
+    while let Some(current_dir) = dir_stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&current_dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                dir_stack.push(path);
+            } else if is_markdown(&path) {
+                files.insert(path);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, DataChange, RemoveKind};
+
+    fn new_batch() -> Batch {
+        Batch::new(Path::new("/site/markdown"), Path::new("/site/music"))
+    }
+
+    fn event(kind: EventKind, paths: &[&str], tracker: Option<usize>) -> notify::Result<Event> {
+        let mut event = Event::new(kind);
+        for path in paths {
+            event = event.add_path(PathBuf::from(path));
+        }
+        if let Some(tracker) = tracker {
+            event = event.set_tracker(tracker);
+        }
+        Ok(event)
+    }
+
+    /// 按 notify 在 Linux 上的顺序产生一次目录内的改名事件
+    fn rename_events(batch: &mut Batch, from: &str, to: &str, tracker: usize) {
+        batch.record(event(EventKind::Modify(ModifyKind::Name(RenameMode::From)), &[from], Some(tracker)));
+        batch.record(event(EventKind::Modify(ModifyKind::Name(RenameMode::To)), &[to], Some(tracker)));
+        batch.record(event(EventKind::Modify(ModifyKind::Name(RenameMode::Both)), &[from, to], Some(tracker)));
+    }
+
+    #[test]
+    fn test_rename_is_recorded_as_move() {
+        let mut batch = new_batch();
+        rename_events(&mut batch, "/site/markdown/a.md", "/site/markdown/2024/b.md", 7);
+        let (markdown, music) = batch.finish();
+
+        assert_eq!(
+            markdown.ops,
+            vec![Op::Rename(PathBuf::from("/site/markdown/a.md"), PathBuf::from("/site/markdown/2024/b.md"))]
+        );
+        assert_eq!(markdown.changed, BTreeSet::from([PathBuf::from("/site/markdown/2024/b.md")]));
+        assert!(!markdown.rescan);
+        assert!(music.is_empty());
+    }
+
+    #[test]
+    fn test_writes_are_merged() {
+        let mut batch = new_batch();
+        let path = "/site/markdown/a.md";
+        batch.record(event(EventKind::Create(CreateKind::File), &[path], None));
+        batch.record(event(EventKind::Modify(ModifyKind::Data(DataChange::Any)), &[path], None));
+        batch.record(event(EventKind::Access(AccessKind::Close(AccessMode::Write)), &[path], None));
+        batch.record(event(EventKind::Access(AccessKind::Open(AccessMode::Any)), &[path], None));
+        batch.record(event(EventKind::Create(CreateKind::File), &["/site/music/1_a.mp3"], None));
+        batch.record(event(EventKind::Create(CreateKind::File), &["/elsewhere/a.md"], None));
+        let (markdown, music) = batch.finish();
+
+        assert!(markdown.ops.is_empty());
+        assert_eq!(markdown.changed, BTreeSet::from([PathBuf::from(path)]));
+        assert_eq!(music.changed, BTreeSet::from([PathBuf::from("/site/music/1_a.mp3")]));
+    }
+
+    #[test]
+    fn test_moves_across_watch_boundary() {
+        let mut batch = new_batch();
+        // 移出监听目录：只有移出事件，合并结束时视为删除
+        batch.record(event(
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)),
+            &["/site/markdown/old.md"],
+            Some(1),
+        ));
+        // 从外部移入：只有移入事件，视为新建
+        batch.record(event(
+            EventKind::Modify(ModifyKind::Name(RenameMode::To)),
+            &["/site/markdown/new.md"],
+            Some(2),
+        ));
+        // 在两个监听目录之间移动
+        rename_events(&mut batch, "/site/music/x.md", "/site/markdown/x.md", 3);
+        let (markdown, music) = batch.finish();
+
+        assert_eq!(markdown.ops, vec![Op::Remove(PathBuf::from("/site/markdown/old.md"))]);
+        assert_eq!(
+            markdown.changed,
+            BTreeSet::from([PathBuf::from("/site/markdown/new.md"), PathBuf::from("/site/markdown/x.md")])
+        );
+        assert_eq!(music.ops, vec![Op::Remove(PathBuf::from("/site/music/x.md"))]);
+    }
+
+    #[test]
+    fn test_root_replaced_needs_rescan() {
+        let mut batch = new_batch();
+        batch.record(event(EventKind::Modify(ModifyKind::Name(RenameMode::From)), &["/site/markdown"], None));
+        batch.record(event(EventKind::Remove(RemoveKind::Folder), &["/site/markdown/a.md"], None));
+        let (markdown, music) = batch.finish();
+        assert!(markdown.rescan);
+        assert!(!music.rescan);
+
+        let mut batch = new_batch();
+        batch.record(Ok(Event::new(EventKind::Other).set_flag(notify::event::Flag::Rescan)));
+        let (markdown, music) = batch.finish();
+        assert!(markdown.rescan && music.rescan);
+    }
+
+    #[test]
+    fn test_own_writes_are_ignored() {
+        let dir = std::env::temp_dir().join(format!("rustblog-own-{}", crate::id_generator::generate_unique_id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("own.md").to_string_lossy().into_owned();
+
+        std::fs::write(&path, "服务写入").unwrap();
+        assert!(!is_own_write(&path));
+        record_own_write(&path);
+        assert!(is_own_write(&path));
+        assert!(!is_own_write(&dir.join("other.md").to_string_lossy()));
+
+        // 登记有效期内的外部修改仍要同步
+        std::fs::write(&path, "外部修改").unwrap();
+        assert!(!is_own_write(&path));
+
+        // 服务删除文件后，删除事件被忽略；外部又创建同名文件时不再忽略
+        std::fs::remove_file(&path).unwrap();
+        record_own_write(&path);
+        assert!(is_own_write(&path));
+        std::fs::write(&path, "外部创建").unwrap();
+        assert!(!is_own_write(&path));
+
+        record_own_write(&path);
+        OWN_WRITES.lock().unwrap().get_mut(&path).unwrap().0 = Instant::now() - OWN_WRITE_TTL;
+        assert!(!is_own_write(&path));
+        assert!(!OWN_WRITES.lock().unwrap().contains_key(&path));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_paths() {
+        let cwd = Path::new("/site");
+        assert_eq!(
+            relative_path(cwd, Path::new("/site/markdown/2024/01/01/a.md")).as_deref(),
+            Some("markdown/2024/01/01/a.md")
+        );
+        assert_eq!(relative_path(cwd, Path::new("/other/a.md")), None);
+
+        let music = Path::new("/site/music");
+        assert_eq!(file_name_in(Path::new("/site/music/1_a.mp3"), music), Some("1_a.mp3"));
+        assert_eq!(file_name_in(Path::new("/site/music/covers/1_cover.jpg"), music), None);
+        assert!(is_markdown(Path::new("a.md")));
+        assert!(!is_markdown(Path::new("a.md~")));
+    }
+}